@@ -1,9 +1,12 @@
+use std::fmt::Display;
+
 #[cfg(feature = "miette")]
 use miette::Diagnostic;
 use relative_path::RelativePathBuf;
 use serde::Deserialize;
 use thiserror::Error;
 use toml::Spanned;
+use toml_edit::{DocumentMut, Item};
 
 use crate::{action::Action, Version};
 
@@ -47,7 +50,7 @@ impl Cargo {
     }
 
     #[must_use]
-    pub fn set_version(mut self, new_version: &Version) -> Action {
+    pub fn set_version(mut self, new_version: &impl Display) -> Action {
         let start = self.parsed.package.version.span().start + 1;
         let end = self.parsed.package.version.span().end - 1;
         let version_str = new_version.to_string();
@@ -60,6 +63,50 @@ impl Cargo {
     }
 }
 
+/// Sets the version requirement of `dependency` in the `[workspace.dependencies]` table of
+/// `raw_toml` (a workspace's root `Cargo.toml`).
+///
+/// Returns `None` if the dependency isn't there or doesn't specify a version (e.g., path-only).
+///
+/// # Errors
+///
+/// If the TOML is invalid.
+pub fn set_workspace_dependency_version(
+    path: &RelativePathBuf,
+    raw_toml: &str,
+    dependency: &str,
+    new_version: &Version,
+) -> Result<Option<String>, EditError> {
+    let mut document = raw_toml
+        .parse::<DocumentMut>()
+        .map_err(|source| EditError {
+            path: path.clone(),
+            source,
+        })?;
+    let Some(entry) = document
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.get_mut("dependencies"))
+        .and_then(|dependencies| dependencies.get_mut(dependency))
+    else {
+        return Ok(None);
+    };
+    let version = if entry.is_str() {
+        entry.as_value_mut()
+    } else {
+        entry
+            .as_table_like_mut()
+            .and_then(|table| table.get_mut("version"))
+            .and_then(Item::as_value_mut)
+    };
+    let Some(version) = version else {
+        return Ok(None);
+    };
+    let decor = version.decor().clone();
+    *version = new_version.to_string().into();
+    *version.decor_mut() = decor;
+    Ok(Some(document.to_string()))
+}
+
 #[derive(Debug, Error)]
 #[cfg_attr(feature = "miette", derive(Diagnostic))]
 pub enum Error {
@@ -79,6 +126,23 @@ pub enum Error {
     Semver(#[from] crate::semver::Error),
 }
 
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+#[error("Error parsing {path}: {source}")]
+#[cfg_attr(
+    feature = "miette",
+    diagnostic(
+        code(cargo::edit),
+        help("Knope couldn't parse the workspace's Cargo.toml to update its dependencies."),
+        url("https://knope.tech/reference/config-file/packages/#update_workspace_dependencies")
+    )
+)]
+pub struct EditError {
+    path: RelativePathBuf,
+    #[source]
+    source: toml_edit::TomlError,
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
 pub struct Toml {
     pub package: Package,
@@ -122,4 +186,40 @@ mod tests {
 
         assert_eq!(new, expected);
     }
+
+    #[test]
+    fn test_set_workspace_dependency_version() {
+        let content = r#"
+        [workspace]
+        members = ["crates/*"]
+
+        [workspace.dependencies]
+        first = { path = "crates/first", version = "0.1.0" }  # keep me
+        second = "0.1.0"
+        path-only = { path = "crates/path-only" }
+
+        [workspace.dependencies.third]
+        path = "crates/third"
+        version = "0.1.0"
+        "#;
+        let path = RelativePathBuf::from("Cargo.toml");
+        let new_version = Version::from_str("1.2.3").unwrap();
+
+        let mut new = content.to_string();
+        for dependency in ["first", "second", "third"] {
+            new = set_workspace_dependency_version(&path, &new, dependency, &new_version)
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(new, content.replace("0.1.0", "1.2.3"));
+
+        assert_eq!(
+            set_workspace_dependency_version(&path, content, "path-only", &new_version).unwrap(),
+            None
+        );
+        assert_eq!(
+            set_workspace_dependency_version(&path, content, "missing", &new_version).unwrap(),
+            None
+        );
+    }
 }
@@ -0,0 +1,174 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+#[cfg(feature = "miette")]
+use miette::Diagnostic;
+use relative_path::RelativePathBuf;
+use serde_yaml::Value;
+use thiserror::Error;
+
+use crate::{action::Action, semver, Version};
+
+/// A `CITATION.cff` file, which is YAML with a top-level `version` field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Citation {
+    path: RelativePathBuf,
+    raw: String,
+    /// Where the version (without any quotes) is in `raw`.
+    span: Range<usize>,
+    version: Version,
+}
+
+impl Citation {
+    pub(crate) fn new(path: RelativePathBuf, raw: String) -> Result<Self, Error> {
+        if let Err(source) = serde_yaml::from_str::<Value>(&raw) {
+            return Err(Error::Deserialize { path, source });
+        }
+        let Some(span) = version_span(&raw) else {
+            return Err(Error::MissingVersion { path });
+        };
+        let version = raw
+            .get(span.clone())
+            .map(Version::from_str)
+            .transpose()?
+            .ok_or(Error::MissingVersion { path: path.clone() })?;
+        Ok(Self {
+            path,
+            raw,
+            span,
+            version,
+        })
+    }
+
+    pub(crate) fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn get_path(&self) -> &RelativePathBuf {
+        &self.path
+    }
+
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
+        // Only the version is replaced, since serde_yaml doesn't preserve comments or formatting
+        self.raw
+            .replace_range(self.span.clone(), &new_version.to_string());
+        Action::WriteToFile {
+            path: self.path,
+            content: self.raw,
+        }
+    }
+}
+
+/// Find the value of the top-level `version` key in `raw`, inside any quotes.
+fn version_span(raw: &str) -> Option<Range<usize>> {
+    let mut line_start = 0;
+    for line in raw.split_inclusive('\n') {
+        if let Some(span) = line_version_span(line) {
+            return Some(line_start + span.start..line_start + span.end);
+        }
+        line_start += line.len();
+    }
+    None
+}
+
+fn line_version_span(line: &str) -> Option<Range<usize>> {
+    let after_key = line.strip_prefix("version:")?;
+    let value_and_rest = after_key.trim_start_matches([' ', '\t']);
+    let value = match value_and_rest.chars().next()? {
+        quote @ ('"' | '\'') => value_and_rest
+            .get(1..)?
+            .split_once(quote)
+            .map(|(value, _rest)| value)?,
+        _ => value_and_rest
+            .split_once(" #")
+            .map_or(value_and_rest, |(value, _comment)| value)
+            .trim_end(),
+    };
+    if value.is_empty() {
+        return None;
+    }
+    let quote_len = usize::from(value_and_rest.starts_with(['"', '\'']));
+    let start = line.len() - value_and_rest.len() + quote_len;
+    Some(start..start + value.len())
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+pub enum Error {
+    #[error("Error deserializing {path}: {source}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(citation_cff::deserialize),
+            help("knope expects CITATION.cff to be a valid YAML file"),
+            url("https://knope.tech/reference/config-file/packages/#citationcff")
+        )
+    )]
+    Deserialize {
+        path: RelativePathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+    #[error("Could not find a version in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(citation_cff::missing_version),
+            help("Knope looks for a top-level `version` field, like `version: 1.2.3`."),
+            url("https://knope.tech/reference/config-file/packages/#citationcff")
+        )
+    )]
+    MissingVersion { path: RelativePathBuf },
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Version(#[from] semver::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CONTENT: &str = "# A comment\ncff-version: 1.2.0\nmessage: \"If you use this software, please cite it.\"\ntitle: knope\nversion: \"1.2.3\" # The version\nauthors:\n  - family-names: Doe\n    version: 0.0.0\n";
+
+    #[test]
+    fn get_version() {
+        let citation =
+            Citation::new(RelativePathBuf::from("CITATION.cff"), CONTENT.to_string()).unwrap();
+        assert_eq!(citation.get_version(), &Version::from_str("1.2.3").unwrap());
+    }
+
+    #[test]
+    fn set_version() {
+        let action = Citation::new(RelativePathBuf::from("CITATION.cff"), CONTENT.to_string())
+            .unwrap()
+            .set_version(&Version::from_str("2.0.0-rc.0").unwrap());
+        assert_eq!(
+            action,
+            Action::WriteToFile {
+                path: RelativePathBuf::from("CITATION.cff"),
+                content: CONTENT.replace("1.2.3", "2.0.0-rc.0"),
+            }
+        );
+    }
+
+    #[test]
+    fn unquoted_version() {
+        let citation = Citation::new(
+            RelativePathBuf::from("CITATION.cff"),
+            "cff-version: 1.2.0\nversion: 0.1.0\n".to_string(),
+        )
+        .unwrap();
+        assert_eq!(citation.get_version(), &Version::from_str("0.1.0").unwrap());
+    }
+
+    #[test]
+    fn missing_version() {
+        let result = Citation::new(
+            RelativePathBuf::from("CITATION.cff"),
+            "cff-version: 1.2.0\nauthors:\n  - version: 1.2.3\n".to_string(),
+        );
+        assert!(matches!(result, Err(Error::MissingVersion { .. })));
+    }
+}
@@ -0,0 +1,194 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+#[cfg(feature = "miette")]
+use miette::Diagnostic;
+use relative_path::RelativePathBuf;
+use thiserror::Error;
+
+use crate::{action::Action, Version};
+
+/// The constant used when none is configured.
+pub(crate) const DEFAULT_CONSTANT: &str = "Version";
+
+/// A Go source file (like `version.go`) which stores the version in a string constant, like
+/// `const Version = "1.2.3"`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GoConst {
+    path: RelativePathBuf,
+    raw: String,
+    /// Where the value of the constant (inside the quotes) is in `raw`.
+    span: Range<usize>,
+    version: Version,
+}
+
+impl GoConst {
+    pub(crate) fn new(path: RelativePathBuf, raw: String, constant: &str) -> Result<Self, Error> {
+        let missing_constant = || Error::MissingConstant {
+            path: path.clone(),
+            constant: constant.to_string(),
+        };
+        let span = value_span(&raw, constant).ok_or_else(missing_constant)?;
+        let version = raw
+            .get(span.clone())
+            .map(Version::from_str)
+            .transpose()?
+            .ok_or_else(missing_constant)?;
+        Ok(Self {
+            path,
+            raw,
+            span,
+            version,
+        })
+    }
+
+    pub(crate) fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn get_path(&self) -> &RelativePathBuf {
+        &self.path
+    }
+
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
+        self.raw
+            .replace_range(self.span.clone(), &new_version.to_string());
+        Action::WriteToFile {
+            path: self.path,
+            content: self.raw,
+        }
+    }
+}
+
+/// Find the string value of the first declaration of `constant` in `raw`, either on its own
+/// (`const Version = "1.2.3"`) or in a group (`const ( Version = "1.2.3" )`).
+fn value_span(raw: &str, constant: &str) -> Option<Range<usize>> {
+    let mut line_start = 0;
+    let mut in_group = false;
+    for line in raw.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let declaration = if let Some(rest) = trimmed.strip_prefix("const") {
+            let rest = rest.trim_start();
+            if rest.starts_with('(') {
+                in_group = true;
+                // A group can start on the same line, like `const ( Version = "1.2.3"`
+                Some(rest.trim_start_matches('(').trim_start())
+            } else {
+                (rest.len() < trimmed.len() - "const".len()).then_some(rest)
+            }
+        } else if in_group {
+            if trimmed.starts_with(')') {
+                in_group = false;
+                None
+            } else {
+                Some(trimmed)
+            }
+        } else {
+            None
+        };
+        if let Some(declaration) = declaration {
+            if let Some(span) = value_in(declaration, constant) {
+                let offset = line_start + line.len() - declaration.len();
+                return Some(offset + span.start..offset + span.end);
+            }
+        }
+        line_start += line.len();
+    }
+    None
+}
+
+/// The span of the string value (within `declaration`) if it declares `constant`, like
+/// `Version string = "1.2.3"`.
+fn value_in(declaration: &str, constant: &str) -> Option<Range<usize>> {
+    let after_name = declaration.strip_prefix(constant)?;
+    if !after_name.starts_with([' ', '\t', '=']) {
+        // Only part of a longer name, like `VersionSuffix`
+        return None;
+    }
+    let after_name = after_name.trim_start_matches([' ', '\t']);
+    let after_type = after_name
+        .strip_prefix("string")
+        .map_or(after_name, |rest| rest.trim_start_matches([' ', '\t']));
+    let after_operator = after_type
+        .strip_prefix('=')?
+        .trim_start_matches([' ', '\t']);
+    let quote = after_operator
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '`'))?;
+    let value = after_operator.get(1..)?;
+    let end = value.find(quote)?;
+    let start = declaration.len() - value.len();
+    Some(start..start + end)
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+pub enum Error {
+    #[error("Could not find the constant {constant} in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(go_const::missing_constant),
+            help(
+                "Knope looks for a string constant declaration, like `const {constant} = \"1.2.3\"`."
+            ),
+            url("https://knope.tech/reference/config-file/packages/#go-constants")
+        )
+    )]
+    MissingConstant {
+        path: RelativePathBuf,
+        constant: String,
+    },
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Semver(#[from] crate::semver::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CONTENT: &str = "package main\n\n// Version is the version\nconst Version = \"1.2.3\"\n\nconst (\n\tVersionSuffix = \"\"\n\tAppVersion string = `0.1.0` // The app\n)\n";
+
+    #[test]
+    fn get_version() {
+        let go_const = GoConst::new(
+            RelativePathBuf::from("version.go"),
+            CONTENT.to_string(),
+            "Version",
+        )
+        .unwrap();
+        assert_eq!(go_const.get_version(), &Version::from_str("1.2.3").unwrap());
+    }
+
+    #[test]
+    fn set_version_in_group() {
+        let action = GoConst::new(
+            RelativePathBuf::from("version.go"),
+            CONTENT.to_string(),
+            "AppVersion",
+        )
+        .unwrap()
+        .set_version(&Version::from_str("1.0.0-rc.0").unwrap());
+        assert_eq!(
+            action,
+            Action::WriteToFile {
+                path: RelativePathBuf::from("version.go"),
+                content: CONTENT.replace("0.1.0", "1.0.0-rc.0"),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_constant() {
+        let result = GoConst::new(
+            RelativePathBuf::from("version.go"),
+            "package main\n\nvar Version = \"1.2.3\"\nconst Versions = \"1.2.3\"\n".to_string(),
+            "Version",
+        );
+        assert!(matches!(result, Err(Error::MissingConstant { .. })));
+    }
+}
@@ -1,12 +1,20 @@
 mod action;
 pub mod cargo;
+mod cff;
+mod go_const;
 mod go_mod;
+mod makefile;
+mod nimble;
 mod package;
-mod package_json;
+pub mod package_json;
+mod pattern;
+mod pom_xml;
 mod pubspec;
 mod pyproject;
 pub mod semver;
 mod versioned_file;
+mod yaml;
+mod zig_zon;
 
 pub use action::Action;
 use cargo::Cargo;
@@ -14,7 +22,10 @@ pub use go_mod::GoVersioning;
 pub use package::{NewError as PackageNewError, Package};
 use pubspec::PubSpec;
 use pyproject::PyProject;
-pub use semver::{Label, PreVersion, Prerelease, StableVersion, Version};
+pub use semver::{
+    BuildMetadata, Label, PreVersion, Prerelease, PrereleaseSeparator, StableVersion, Version,
+};
 pub use versioned_file::{
-    Error as VersionedFileError, Path as VersionedFilePath, SetError, UnknownFile, VersionedFile,
+    Error as VersionedFileError, KeyNotSupported, Path as VersionedFilePath, SetError, UnknownFile,
+    VariableNotSupported, VersionedFile,
 };
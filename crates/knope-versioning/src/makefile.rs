@@ -0,0 +1,167 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+#[cfg(feature = "miette")]
+use miette::Diagnostic;
+use relative_path::RelativePathBuf;
+use thiserror::Error;
+
+use crate::{action::Action, Version};
+
+/// The variable used when none is configured.
+pub(crate) const DEFAULT_VARIABLE: &str = "VERSION";
+
+/// The assignment operators GNU Make supports, longest first so they don't shadow each other.
+const OPERATORS: [&str; 4] = ["::=", ":=", "?=", "="];
+
+/// A `Makefile` which stores the version in a variable, like `VERSION = 1.2.3`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Makefile {
+    path: RelativePathBuf,
+    raw: String,
+    /// Where the value of the variable is in `raw`.
+    span: Range<usize>,
+    version: Version,
+}
+
+impl Makefile {
+    pub(crate) fn new(path: RelativePathBuf, raw: String, variable: &str) -> Result<Self, Error> {
+        let Some(span) = value_span(&raw, variable) else {
+            return Err(Error::MissingVariable {
+                path,
+                variable: variable.to_string(),
+            });
+        };
+        let version = raw
+            .get(span.clone())
+            .map(Version::from_str)
+            .transpose()?
+            .ok_or_else(|| Error::MissingVariable {
+                path: path.clone(),
+                variable: variable.to_string(),
+            })?;
+        Ok(Self {
+            path,
+            raw,
+            span,
+            version,
+        })
+    }
+
+    pub(crate) fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn get_path(&self) -> &RelativePathBuf {
+        &self.path
+    }
+
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
+        self.raw
+            .replace_range(self.span.clone(), &new_version.to_string());
+        Action::WriteToFile {
+            path: self.path,
+            content: self.raw,
+        }
+    }
+}
+
+/// Find the value of the first assignment to `variable` (at the start of a line) in `raw`.
+fn value_span(raw: &str, variable: &str) -> Option<Range<usize>> {
+    let mut line_start = 0;
+    for line in raw.split_inclusive('\n') {
+        if let Some(span) = line_value_span(line, variable) {
+            return Some(line_start + span.start..line_start + span.end);
+        }
+        line_start += line.len();
+    }
+    None
+}
+
+fn line_value_span(line: &str, variable: &str) -> Option<Range<usize>> {
+    let after_name = line.strip_prefix(variable)?.trim_start_matches([' ', '\t']);
+    let after_operator = OPERATORS
+        .iter()
+        .find_map(|operator| after_name.strip_prefix(operator))?;
+    let value_and_rest = after_operator.trim_start_matches([' ', '\t']);
+    let value = value_and_rest
+        .split_once('#')
+        .map_or(value_and_rest, |(value, _comment)| value)
+        .trim_end();
+    if value.is_empty() {
+        return None;
+    }
+    let start = line.len() - value_and_rest.len();
+    Some(start..start + value.len())
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+pub enum Error {
+    #[error("Could not find the variable {variable} in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(makefile::missing_variable),
+            help(
+                "Knope looks for a line which starts with the variable, like `{variable} = 1.2.3`."
+            ),
+            url("https://knope.tech/reference/config-file/packages/#makefile")
+        )
+    )]
+    MissingVariable {
+        path: RelativePathBuf,
+        variable: String,
+    },
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Semver(#[from] crate::semver::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CONTENT: &str = "CC = gcc\nAPP_VERSION := 0.1.0 # The version\nVERSION\t= 1.2.3\n\nall:\n\techo $(VERSION)\n";
+
+    #[test]
+    fn get_version() {
+        let makefile = Makefile::new(
+            RelativePathBuf::from("Makefile"),
+            CONTENT.to_string(),
+            "VERSION",
+        )
+        .unwrap();
+        assert_eq!(makefile.get_version(), &Version::from_str("1.2.3").unwrap());
+    }
+
+    #[test]
+    fn set_version() {
+        let action = Makefile::new(
+            RelativePathBuf::from("Makefile"),
+            CONTENT.to_string(),
+            "APP_VERSION",
+        )
+        .unwrap()
+        .set_version(&Version::from_str("1.0.0-rc.0").unwrap());
+        assert_eq!(
+            action,
+            Action::WriteToFile {
+                path: RelativePathBuf::from("Makefile"),
+                content: CONTENT.replace("0.1.0", "1.0.0-rc.0"),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_variable() {
+        let result = Makefile::new(
+            RelativePathBuf::from("Makefile"),
+            "\tVERSION = 1.2.3\nVERSIONS = 1.2.3\n".to_string(),
+            "VERSION",
+        );
+        assert!(matches!(result, Err(Error::MissingVariable { .. })));
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+#[cfg(feature = "miette")]
+use miette::Diagnostic;
+use relative_path::RelativePathBuf;
+use thiserror::Error;
+
+use crate::{action::Action, Version};
+
+/// A Nim package's `.nimble` file, which declares the version like `version = "1.2.3"`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Nimble {
+    path: RelativePathBuf,
+    raw: String,
+    /// Where the version is in `raw`, without the quotes.
+    span: Range<usize>,
+    version: Version,
+}
+
+impl Nimble {
+    pub(crate) fn new(path: RelativePathBuf, raw: String) -> Result<Self, Error> {
+        let mut spans = Vec::new();
+        let mut line_start = 0;
+        for line in raw.split_inclusive('\n') {
+            if let Some(span) = line_version_span(line) {
+                spans.push(line_start + span.start..line_start + span.end);
+            }
+            line_start += line.len();
+        }
+        let span = match spans.as_slice() {
+            [] => return Err(Error::MissingVersion { path }),
+            [span] => span.clone(),
+            _ => return Err(Error::MultipleVersions { path }),
+        };
+        let version = raw
+            .get(span.clone())
+            .map(Version::from_str)
+            .transpose()?
+            .ok_or(Error::MissingVersion { path: path.clone() })?;
+        Ok(Self {
+            path,
+            raw,
+            span,
+            version,
+        })
+    }
+
+    pub(crate) fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn get_path(&self) -> &RelativePathBuf {
+        &self.path
+    }
+
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
+        self.raw
+            .replace_range(self.span.clone(), &new_version.to_string());
+        Action::WriteToFile {
+            path: self.path,
+            content: self.raw,
+        }
+    }
+}
+
+/// The span of the quoted value in a top-level `version = "..."` line, without the quotes.
+fn line_version_span(line: &str) -> Option<Range<usize>> {
+    let after_name = line
+        .strip_prefix("version")?
+        .trim_start_matches([' ', '\t']);
+    let after_equals = after_name
+        .strip_prefix('=')?
+        .trim_start_matches([' ', '\t']);
+    let value = after_equals.strip_prefix('"')?;
+    let end = value.find('"')?;
+    let start = line.len() - value.len();
+    Some(start..start + end)
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+pub enum Error {
+    #[error("Could not find the version in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nimble::missing_version),
+            help("Knope looks for a line which starts with `version = \"1.2.3\"`."),
+            url("https://knope.tech/reference/config-file/packages/#nimble")
+        )
+    )]
+    MissingVersion { path: RelativePathBuf },
+    #[error("Found more than one version in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(nimble::multiple_versions),
+            help("Knope can only update a `.nimble` file with a single `version = \"...\"` line."),
+            url("https://knope.tech/reference/config-file/packages/#nimble")
+        )
+    )]
+    MultipleVersions { path: RelativePathBuf },
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Semver(#[from] crate::semver::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CONTENT: &str = r#"# Package
+
+version       = "1.2.3"
+author        = "Someone"
+description   = "A new awesome nimble package"
+license       = "MIT"
+srcDir        = "src"
+
+# Dependencies
+
+requires "nim >= 2.0.0"
+"#;
+
+    fn nimble(content: &str) -> Result<Nimble, Error> {
+        Nimble::new(
+            RelativePathBuf::from("my_package.nimble"),
+            content.to_string(),
+        )
+    }
+
+    #[test]
+    fn get_version() {
+        assert_eq!(
+            nimble(CONTENT).unwrap().get_version(),
+            &Version::from_str("1.2.3").unwrap()
+        );
+    }
+
+    #[test]
+    fn set_version() {
+        let action = nimble(CONTENT)
+            .unwrap()
+            .set_version(&Version::from_str("2.0.0-rc.0").unwrap());
+        assert_eq!(
+            action,
+            Action::WriteToFile {
+                path: RelativePathBuf::from("my_package.nimble"),
+                content: CONTENT.replace("\"1.2.3\"", "\"2.0.0-rc.0\""),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_version() {
+        assert!(matches!(
+            nimble("  version = \"1.2.3\"\nversions = \"1.2.3\"\n"),
+            Err(Error::MissingVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn multiple_versions() {
+        assert!(matches!(
+            nimble("version = \"1.2.3\"\nversion = \"1.2.4\"\n"),
+            Err(Error::MultipleVersions { .. })
+        ));
+    }
+}
@@ -1,13 +1,14 @@
 use itertools::Itertools;
 #[cfg(feature = "miette")]
 use miette::Diagnostic;
+use relative_path::{RelativePath, RelativePathBuf};
 use thiserror::Error;
 
 use crate::{
     action::Action,
     go_mod::GoVersioning,
     versioned_file::{SetError, VersionedFile},
-    Version,
+    BuildMetadata, Version,
 };
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -22,11 +23,12 @@ impl Package {
     ///
     /// There must be at least one versioned file and all files must have the same version.
     pub fn new(versioned_files: Vec<VersionedFile>) -> Result<Self, NewError> {
-        if let Some(first) = versioned_files.first() {
-            if let Some(conflict) = versioned_files
-                .iter()
-                .find(|f| f.version() != first.version())
-            {
+        if let Some(first) = versioned_files
+            .iter()
+            .find(|f| f.has_full_version())
+            .or_else(|| versioned_files.first())
+        {
+            if let Some(conflict) = versioned_files.iter().find(|f| !f.matches(first.version())) {
                 return Err(NewError::InconsistentVersions(
                     Box::new(first.clone()),
                     Box::new(conflict.clone()),
@@ -38,18 +40,45 @@ impl Package {
         Ok(Self { versioned_files })
     }
 
+    /// Combine versioned files into one package whose version always comes from `source_of_truth`,
+    /// even if the other files don't match it (see [`Self::sync_versions`]).
+    ///
+    /// # Errors
+    ///
+    /// If `source_of_truth` is not one of the versioned files.
+    pub fn with_source_of_truth(
+        mut versioned_files: Vec<VersionedFile>,
+        source_of_truth: &RelativePath,
+    ) -> Result<Self, NewError> {
+        let index = versioned_files
+            .iter()
+            .position(|file| file.path() == source_of_truth)
+            .ok_or_else(|| {
+                NewError::UnknownSourceOfTruth(source_of_truth.to_relative_path_buf())
+            })?;
+        let source = versioned_files.remove(index);
+        versioned_files.insert(0, source);
+        Ok(Self { versioned_files })
+    }
+
     #[must_use]
     pub fn versioned_files(&self) -> &[VersionedFile] {
         &self.versioned_files
     }
 
+    /// The version of the first file which doesn't strip the pre-release component (if any).
     #[must_use]
     #[allow(clippy::indexing_slicing)] // Construction check guarantees there is at least one versioned file
     pub fn get_version(&self) -> &Version {
-        self.versioned_files[0].version()
+        self.versioned_files
+            .iter()
+            .find(|f| f.has_full_version())
+            .unwrap_or(&self.versioned_files[0])
+            .version()
     }
 
-    /// Returns the actions that must be taken to set this package to the new version.
+    /// Returns the actions that must be taken to set this package to the new version, with `build`
+    /// metadata in the files that allow it.
     ///
     /// # Errors
     ///
@@ -59,13 +88,37 @@ impl Package {
     pub fn set_version(
         self,
         new_version: &Version,
+        build: Option<&BuildMetadata>,
         go_versioning: GoVersioning,
     ) -> Result<Vec<Action>, SetError> {
         self.versioned_files
             .into_iter()
-            .map(|f| f.set_version(new_version, go_versioning))
+            .map(|f| f.set_version(new_version, build, go_versioning))
             .process_results(|iter| iter.flatten().collect())
     }
+
+    /// Returns the actions that would make every versioned file match the version of the first one
+    /// (the source of truth, for [`Self::with_source_of_truth`]).
+    ///
+    /// Files which already match are skipped, and no tags are created.
+    ///
+    /// # Errors
+    ///
+    /// If the file is a `go.mod`, there are rules about what versions are allowed.
+    ///
+    /// If serialization of some sort fails, which is a bug, then this will return an error.
+    pub fn sync_versions(self, go_versioning: GoVersioning) -> Result<Vec<Action>, SetError> {
+        let version = self.get_version().clone();
+        self.versioned_files
+            .into_iter()
+            .filter(|file| !file.matches(&version))
+            .map(|file| file.set_version(&version, None, go_versioning))
+            .process_results(|iter| {
+                iter.flatten()
+                    .filter(|action| matches!(action, Action::WriteToFile { .. }))
+                    .collect()
+            })
+    }
 }
 
 #[derive(Debug, Error)]
@@ -83,4 +136,14 @@ pub enum NewError {
     InconsistentVersions(Box<VersionedFile>, Box<VersionedFile>),
     #[error("Packages must have at least one versioned file")]
     NoPackages,
+    #[error("The source of truth {0} is not one of the package's versioned files")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code = "knope_versioning::unknown_source_of_truth",
+            url = "https://knope.tech/reference/config-file/packages/#source_of_truth",
+            help = "Add the file to `versioned_files`, or choose one of them as the source of truth"
+        )
+    )]
+    UnknownSourceOfTruth(RelativePathBuf),
 }
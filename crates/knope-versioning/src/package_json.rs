@@ -1,3 +1,5 @@
+use std::{fmt::Display, str::FromStr};
+
 #[cfg(feature = "miette")]
 use miette::Diagnostic;
 use relative_path::RelativePathBuf;
@@ -34,7 +36,13 @@ impl PackageJson {
         &self.path
     }
 
-    pub(crate) fn set_version(self, new_version: &Version) -> serde_json::Result<Action> {
+    /// The `name` of the package, if it has one.
+    #[must_use]
+    pub fn get_package_name(&self) -> Option<&str> {
+        self.parsed.name.as_deref()
+    }
+
+    pub(crate) fn set_version(self, new_version: &impl Display) -> serde_json::Result<Action> {
         let mut json = serde_json::from_str::<Map<String, Value>>(&self.raw)?;
         json.insert(
             "version".to_string(),
@@ -48,6 +56,257 @@ impl PackageJson {
     }
 }
 
+/// Sets the version of `dependency` in the `dependencies` and `devDependencies` of `raw_json`
+/// (some other package's `package.json`) to `new_version`, keeping the range's operator and
+/// any `workspace:` protocol.
+///
+/// Ranges that aren't a single version, like `workspace:*` or `1.x`, are left alone.
+/// Only the changed values are replaced, so the rest of the file keeps its formatting.
+/// Returns `None` if nothing changed.
+///
+/// # Errors
+///
+/// If the JSON is invalid.
+pub fn set_dependency_version(
+    path: &RelativePathBuf,
+    raw_json: &str,
+    dependency: &str,
+    new_version: &Version,
+) -> Result<Option<String>, EditError> {
+    let json =
+        serde_json::from_str::<Map<String, Value>>(raw_json).map_err(|source| EditError {
+            path: path.clone(),
+            source,
+        })?;
+    let mut content = raw_json.to_string();
+    let mut changed = false;
+    for section in ["dependencies", "devDependencies"] {
+        let Some(range) = json
+            .get(section)
+            .and_then(|dependencies| dependencies.get(dependency))
+            .and_then(Value::as_str)
+        else {
+            continue;
+        };
+        let Some(new_range) = updated_range(range, new_version) else {
+            continue;
+        };
+        if new_range != range {
+            content = replace_entry(&content, dependency, range, &new_range);
+            changed = true;
+        }
+    }
+    Ok(changed.then_some(content))
+}
+
+/// Replace the version in a range like `^1.2.3` or `workspace:~1.2.3` with `new_version`.
+fn updated_range(range: &str, new_version: &Version) -> Option<String> {
+    let (protocol, range) = range
+        .strip_prefix("workspace:")
+        .map_or(("", range), |range| ("workspace:", range));
+    let (operator, version) = range.split_at(range.find(|c: char| c.is_ascii_digit())?);
+    if !["", "^", "~", "=", ">="].contains(&operator) {
+        return None;
+    }
+    Version::from_str(version).ok()?;
+    Some(format!("{protocol}{operator}{new_version}"))
+}
+
+/// Replace every `"dependency": "old_range"` in `raw_json` with `new_range`, without touching
+/// the whitespace around it.
+fn replace_entry(raw_json: &str, dependency: &str, old_range: &str, new_range: &str) -> String {
+    let key = format!("\"{dependency}\"");
+    let old_value = format!("\"{old_range}\"");
+    let mut result = String::with_capacity(raw_json.len());
+    let mut rest = raw_json;
+    while let Some(index) = rest.find(&key) {
+        let (before, after_key) = rest.split_at(index + key.len());
+        result.push_str(before);
+        rest = after_key;
+        let Some(value) = rest.trim_start().strip_prefix(':').map(str::trim_start) else {
+            continue;
+        };
+        if let Some(after_value) = value.strip_prefix(old_value.as_str()) {
+            let (separator, _) = rest.split_at(rest.len() - value.len());
+            result.push_str(separator);
+            result.push('"');
+            result.push_str(new_range);
+            result.push('"');
+            rest = after_value;
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// A lockfile which a JavaScript package manager keeps next to `package.json`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Lockfile {
+    /// `pnpm-lock.yaml`
+    Pnpm,
+    /// `yarn.lock`
+    Yarn,
+}
+
+impl Lockfile {
+    pub const ALL: [Self; 2] = [Lockfile::Pnpm, Lockfile::Yarn];
+
+    #[must_use]
+    pub const fn file_name(self) -> &'static str {
+        match self {
+            Lockfile::Pnpm => "pnpm-lock.yaml",
+            Lockfile::Yarn => "yarn.lock",
+        }
+    }
+
+    /// Update the version that the lockfile `raw` records for the local package `package_name`
+    /// (like a `file:` or `link:` dependency) from `old_version` to `new_version`.
+    ///
+    /// These formats are too complex to rewrite safely, so this only replaces the value of a single,
+    /// unambiguous `version` line: the entry must be the only one for the package at `old_version`,
+    /// and it must not point to a published artifact (no `resolved`, `integrity`, or `checksum`).
+    /// Returns `None` if there is no such entry, leaving the lockfile untouched.
+    #[must_use]
+    pub fn set_self_version(
+        self,
+        raw: &str,
+        package_name: &str,
+        old_version: &Version,
+        new_version: &Version,
+    ) -> Option<String> {
+        let lines = raw.split_inclusive('\n').collect::<Vec<_>>();
+        let old_version = old_version.to_string();
+        let entries = match self {
+            Lockfile::Pnpm => pnpm_entries(&lines, package_name),
+            Lockfile::Yarn => yarn_entries(&lines, package_name),
+        };
+        let mut candidates = entries.into_iter().filter_map(|entry| {
+            let (start, end) = entry;
+            let block = lines.get(start..end)?;
+            if block.iter().any(|line| {
+                let line = line.trim_start();
+                ["resolved", "integrity", "checksum", "tarball"]
+                    .iter()
+                    .any(|key| line.starts_with(key) || line.contains(&format!("{key}:")))
+            }) {
+                return None;
+            }
+            let index = block
+                .iter()
+                .position(|line| version_value(line) == Some(old_version.as_str()))?;
+            Some(start + index)
+        });
+        let line_index = candidates.next()?;
+        if candidates.next().is_some() {
+            return None;
+        }
+        let mut content = String::with_capacity(raw.len());
+        for (index, line) in lines.iter().enumerate() {
+            if index == line_index {
+                let (key, value) = line.split_at(line.find("version")? + "version".len());
+                content.push_str(key);
+                content.push_str(&value.replacen(&old_version, &new_version.to_string(), 1));
+            } else {
+                content.push_str(line);
+            }
+        }
+        Some(content)
+    }
+}
+
+/// The ranges of lines that make up each entry of a `pnpm-lock.yaml` with `name: package_name`.
+///
+/// An entry is every line between the parent key of the `name` line and the next line that is
+/// indented less than `name`.
+fn pnpm_entries(lines: &[&str], package_name: &str) -> Vec<(usize, usize)> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| {
+            line.trim()
+                .strip_prefix("name:")
+                .map(|name| unquote(name.trim()))
+                == Some(package_name)
+        })
+        .map(|(index, line)| {
+            let indent = indentation(line);
+            let (before, after) = lines.split_at(index);
+            let start = before
+                .iter()
+                .rposition(|line| !line.trim().is_empty() && indentation(line) < indent)
+                .map_or(0, |parent| parent + 1);
+            let end = after
+                .iter()
+                .position(|line| !line.trim().is_empty() && indentation(line) < indent)
+                .map_or(lines.len(), |next| index + next);
+            (start, end)
+        })
+        .collect()
+}
+
+/// The ranges of lines that make up each entry of a `yarn.lock` whose descriptors (like
+/// `"package@^1.2.3", "package@file:./package":`) all refer to `package_name`.
+fn yarn_entries(lines: &[&str], package_name: &str) -> Vec<(usize, usize)> {
+    let prefix = format!("{package_name}@");
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim_end();
+            indentation(line) == 0
+                && !line.starts_with('#')
+                && line.strip_suffix(':').is_some_and(|descriptors| {
+                    descriptors
+                        .split(',')
+                        .all(|descriptor| unquote(descriptor.trim()).starts_with(&prefix))
+                })
+        })
+        .map(|(index, _)| {
+            let (_, after) = lines.split_at(index + 1);
+            let end = after
+                .iter()
+                .position(|line| !line.trim().is_empty() && indentation(line) == 0)
+                .map_or(lines.len(), |next| index + 1 + next);
+            (index + 1, end)
+        })
+        .collect()
+}
+
+/// The value of a `version "1.2.3"` (Yarn v1) or `version: 1.2.3` (YAML) line.
+fn version_value(line: &str) -> Option<&str> {
+    let value = line.trim().strip_prefix("version")?;
+    let value = value.strip_prefix(':').unwrap_or(value);
+    if value.len() == value.trim_start().len() {
+        return None;
+    }
+    Some(unquote(value.trim()))
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+#[error("Error parsing {path}: {source}")]
+#[cfg_attr(
+    feature = "miette",
+    diagnostic(
+        code(package_json::edit),
+        help("Knope couldn't parse this package.json to update the versions of its dependencies."),
+        url("https://knope.tech/reference/config-file/packages/#packagejson")
+    )
+)]
+pub struct EditError {
+    path: RelativePathBuf,
+    #[source]
+    source: serde_json::Error,
+}
+
 #[derive(Debug, Error)]
 #[cfg_attr(feature = "miette", derive(Diagnostic))]
 pub enum Error {
@@ -66,10 +325,12 @@ pub enum Error {
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
 struct Json {
+    name: Option<String>,
     version: Version,
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use std::str::FromStr;
 
@@ -141,4 +402,103 @@ mod tests {
         };
         assert_eq!(new, expected);
     }
+
+    #[test]
+    fn test_set_dependency_version() {
+        let content = r#"{
+    "name": "dependent",
+    "version": "0.1.0",
+    "dependencies": {
+        "pinned": "1.0.0",
+        "caret":   "^1.0.0",
+        "workspace-tilde": "workspace:~1.0.0",
+        "workspace-star": "workspace:*",
+        "x-range": "1.x"
+    },
+    "devDependencies": {"caret": "^1.0.0"}
+}"#;
+        let path = RelativePathBuf::from("package.json");
+        let new_version = Version::from_str("1.2.3").unwrap();
+
+        let mut new = content.to_string();
+        for dependency in ["pinned", "caret", "workspace-tilde"] {
+            new = set_dependency_version(&path, &new, dependency, &new_version)
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(new, content.replace("1.0.0", "1.2.3"));
+
+        for dependency in ["workspace-star", "x-range", "missing"] {
+            assert_eq!(
+                set_dependency_version(&path, content, dependency, &new_version).unwrap(),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn lockfile_self_version() {
+        let old = Version::from_str("1.2.3").unwrap();
+        let new = Version::from_str("1.3.0").unwrap();
+
+        let yarn = r#"# yarn lockfile v1
+
+
+"local@file:./local":
+  version "1.2.3"
+  dependencies:
+    other "^1.2.3"
+
+other@^1.2.3:
+  version "1.2.3"
+  resolved "https://registry.yarnpkg.com/other/-/other-1.2.3.tgz#abc"
+  integrity sha512-abc
+"#;
+        assert_eq!(
+            Lockfile::Yarn.set_self_version(yarn, "local", &old, &new),
+            Some(yarn.replacen("version \"1.2.3\"", "version \"1.3.0\"", 1))
+        );
+        assert_eq!(
+            Lockfile::Yarn.set_self_version(yarn, "other", &old, &new),
+            None,
+            "Published packages must not be changed"
+        );
+
+        let pnpm = "lockfileVersion: '6.0'
+
+dependencies:
+  local:
+    specifier: file:local
+    version: file:local
+
+packages:
+
+  file:local:
+    resolution: {directory: local, type: directory}
+    name: local
+    version: 1.2.3
+    dev: false
+
+  /other@1.2.3:
+    resolution: {integrity: sha512-abc}
+    name: other
+    version: 1.2.3
+    dev: false
+";
+        assert_eq!(
+            Lockfile::Pnpm.set_self_version(pnpm, "local", &old, &new),
+            Some(pnpm.replacen("version: 1.2.3", "version: 1.3.0", 1))
+        );
+        assert_eq!(
+            Lockfile::Pnpm.set_self_version(pnpm, "other", &old, &new),
+            None
+        );
+
+        let ambiguous = format!("{pnpm}\n  file:copy:\n    name: local\n    version: 1.2.3\n");
+        assert_eq!(
+            Lockfile::Pnpm.set_self_version(&ambiguous, "local", &old, &new),
+            None,
+            "Multiple entries are ambiguous"
+        );
+    }
 }
@@ -0,0 +1,284 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+#[cfg(feature = "miette")]
+use miette::Diagnostic;
+use regex::Regex;
+use relative_path::RelativePathBuf;
+use thiserror::Error;
+
+use crate::{action::Action, Version};
+
+/// Any file which stores the version where a configured regex matches, like the URL of a version
+/// badge in a `README.md`.
+///
+/// The version is the capture group named `version` if there is one, otherwise the first capture
+/// group. Every match is a place the version is kept, so they must all capture the same version
+/// (matches where the group didn't participate are skipped). Only the captured versions are
+/// replaced.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pattern {
+    path: RelativePathBuf,
+    raw: String,
+    /// Where each captured version is in `raw`, in order.
+    spans: Vec<Range<usize>>,
+    version: Version,
+}
+
+/// The name of the capture group that holds the version, if the pattern has more than one group.
+const VERSION_GROUP: &str = "version";
+
+impl Pattern {
+    pub(crate) fn new(path: RelativePathBuf, raw: String, pattern: &str) -> Result<Self, Error> {
+        let regex = Regex::new(pattern).map_err(|source| Error::InvalidPattern {
+            pattern: pattern.to_string(),
+            source,
+        })?;
+        if regex.captures_len() < 2 {
+            return Err(Error::NoCaptureGroup {
+                pattern: pattern.to_string(),
+            });
+        }
+        let has_version_group = regex
+            .capture_names()
+            .any(|name| name == Some(VERSION_GROUP));
+        let spans: Vec<Range<usize>> = regex
+            .captures_iter(&raw)
+            .filter_map(|captures| {
+                if has_version_group {
+                    captures.name(VERSION_GROUP)
+                } else {
+                    captures.get(1)
+                }
+            })
+            .map(|version| version.range())
+            .collect();
+        let mut versions = spans
+            .iter()
+            .filter_map(|span| raw.get(span.clone()))
+            .map(Version::from_str);
+        let Some(version) = versions.next().transpose()? else {
+            return Err(Error::NoMatch {
+                path,
+                pattern: pattern.to_string(),
+            });
+        };
+        for other in versions {
+            let other = other?;
+            if other != version {
+                return Err(Error::InconsistentVersions {
+                    path,
+                    first: version.to_string(),
+                    other: other.to_string(),
+                });
+            }
+        }
+        Ok(Self {
+            path,
+            raw,
+            spans,
+            version,
+        })
+    }
+
+    pub(crate) fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn get_path(&self) -> &RelativePathBuf {
+        &self.path
+    }
+
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
+        let new_version = new_version.to_string();
+        // From the end, so replacing one doesn't move the others
+        for span in self.spans.into_iter().rev() {
+            self.raw.replace_range(span, &new_version);
+        }
+        Action::WriteToFile {
+            path: self.path,
+            content: self.raw,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+pub enum Error {
+    #[error("The pattern {pattern} didn't match anything in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(pattern::no_match),
+            help("The first capture group of the pattern (or the one named `version`), like `version-(.*)-blue`, should match the version."),
+            url("https://knope.tech/reference/config-file/packages/#pattern")
+        )
+    )]
+    NoMatch {
+        path: RelativePathBuf,
+        pattern: String,
+    },
+    #[error("The pattern {pattern} has no capture group for the version")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(pattern::no_capture_group),
+            help("Wrap the version in parentheses, like `version-(.*)-blue`."),
+            url("https://knope.tech/reference/config-file/packages/#pattern")
+        )
+    )]
+    NoCaptureGroup { pattern: String },
+    #[error("The pattern matched different versions in {path}: {first} and {other}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(pattern::inconsistent_versions),
+            help("Every match is updated, so they must all have the same version. Make the pattern more specific to match only one of them."),
+            url("https://knope.tech/reference/config-file/packages/#pattern")
+        )
+    )]
+    InconsistentVersions {
+        path: RelativePathBuf,
+        first: String,
+        other: String,
+    },
+    #[error("The pattern {pattern} is not a valid regex: {source}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(pattern::invalid_pattern),
+            help("The pattern uses the syntax of the Rust `regex` crate."),
+            url("https://docs.rs/regex/latest/regex/#syntax")
+        )
+    )]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Semver(#[from] crate::semver::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const README: &str = "# My Project\n\n![Version](https://img.shields.io/badge/version-1.2.3-blue)\n\nInstall version 1.2.3 with `cargo install`.\n";
+
+    fn readme(pattern: &str) -> Result<Pattern, Error> {
+        Pattern::new(
+            RelativePathBuf::from("README.md"),
+            README.to_string(),
+            pattern,
+        )
+    }
+
+    #[test]
+    fn get_version() {
+        assert_eq!(
+            readme(r"badge/version-([^-]+)-blue").unwrap().get_version(),
+            &Version::from_str("1.2.3").unwrap()
+        );
+    }
+
+    #[test]
+    fn set_version_only_replaces_capture() {
+        let action = readme(r"badge/version-([^-]+)-blue")
+            .unwrap()
+            .set_version(&Version::from_str("1.3.0").unwrap());
+        assert_eq!(
+            action,
+            Action::WriteToFile {
+                path: RelativePathBuf::from("README.md"),
+                content: README.replace("version-1.2.3-blue", "version-1.3.0-blue"),
+            }
+        );
+    }
+
+    #[test]
+    fn every_match_is_replaced() {
+        let pattern = Pattern::new(
+            RelativePathBuf::from("build.zig"),
+            "const version = \"1.2.3\";\nconst other_version = \"1.2.3\";\n".to_string(),
+            r#"version = "(.*)""#,
+        )
+        .unwrap();
+        assert_eq!(
+            pattern.set_version(&Version::from_str("2.0.0-rc.0").unwrap()),
+            Action::WriteToFile {
+                path: RelativePathBuf::from("build.zig"),
+                content: "const version = \"2.0.0-rc.0\";\nconst other_version = \"2.0.0-rc.0\";\n"
+                    .to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn same_version_matched_twice() {
+        assert_eq!(readme(r"(\d+\.\d+\.\d+)").unwrap().spans.len(), 2);
+    }
+
+    #[test]
+    fn inconsistent_versions() {
+        assert!(matches!(
+            Pattern::new(
+                RelativePathBuf::from("README.md"),
+                "1.2.3 and 1.2.4".to_string(),
+                r"(\d+\.\d+\.\d+)",
+            ),
+            Err(Error::InconsistentVersions { .. })
+        ));
+    }
+
+    #[test]
+    fn named_group() {
+        let pattern = readme(r"badge/(version)-(?<version>[^-]+)-(blue)").unwrap();
+        assert_eq!(pattern.get_version(), &Version::from_str("1.2.3").unwrap());
+        assert_eq!(
+            pattern.set_version(&Version::from_str("1.3.0").unwrap()),
+            Action::WriteToFile {
+                path: RelativePathBuf::from("README.md"),
+                content: README.replace("version-1.2.3-blue", "version-1.3.0-blue"),
+            }
+        );
+    }
+
+    #[test]
+    fn optional_group_not_matched() {
+        let pattern = Pattern::new(
+            RelativePathBuf::from("VERSION"),
+            "unreleased\nversion 1.2.3\n".to_string(),
+            r"(?m)^(?:unreleased|version (\S+))$",
+        )
+        .unwrap();
+        assert_eq!(pattern.get_version(), &Version::from_str("1.2.3").unwrap());
+    }
+
+    #[test]
+    fn no_capture_group() {
+        assert!(matches!(
+            readme(r"version-[^-]+-blue"),
+            Err(Error::NoCaptureGroup { .. })
+        ));
+    }
+
+    #[test]
+    fn no_match() {
+        assert!(matches!(
+            readme(r"badge/release-([^-]+)-blue"),
+            Err(Error::NoMatch { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(matches!(
+            readme(r"version-(\d+"),
+            Err(Error::InvalidPattern { .. })
+        ));
+    }
+}
@@ -0,0 +1,222 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+#[cfg(feature = "miette")]
+use miette::Diagnostic;
+use relative_path::RelativePathBuf;
+use thiserror::Error;
+
+use crate::{action::Action, Version};
+
+/// A Maven `pom.xml`, which declares the version in a `<version>` element directly inside
+/// `<project>`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PomXml {
+    path: RelativePathBuf,
+    raw: String,
+    /// Where the version is in `raw`, without surrounding whitespace.
+    span: Range<usize>,
+    version: Version,
+}
+
+impl PomXml {
+    pub(crate) fn new(path: RelativePathBuf, raw: String) -> Result<Self, Error> {
+        let span = match project_version_span(&raw) {
+            ProjectVersion::Found(span) => span,
+            ProjectVersion::Inherited => return Err(Error::InheritedVersion { path }),
+            ProjectVersion::Missing => return Err(Error::MissingVersion { path }),
+        };
+        let version = raw
+            .get(span.clone())
+            .map(Version::from_str)
+            .transpose()?
+            .ok_or(Error::MissingVersion { path: path.clone() })?;
+        Ok(Self {
+            path,
+            raw,
+            span,
+            version,
+        })
+    }
+
+    pub(crate) fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn get_path(&self) -> &RelativePathBuf {
+        &self.path
+    }
+
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
+        self.raw
+            .replace_range(self.span.clone(), &new_version.to_string());
+        Action::WriteToFile {
+            path: self.path,
+            content: self.raw,
+        }
+    }
+}
+
+enum ProjectVersion {
+    Found(Range<usize>),
+    /// There's no version, but there is a `<parent>` to inherit it from
+    Inherited,
+    Missing,
+}
+
+/// Find the text of the `<version>` which is a direct child of the root element, skipping
+/// comments, CDATA, and any deeper `<version>` (like in `<parent>` or `<dependencies>`).
+fn project_version_span(raw: &str) -> ProjectVersion {
+    let mut depth = 0_usize;
+    let mut has_parent = false;
+    let mut index = 0;
+    while let Some((start, rest)) = raw
+        .get(index..)
+        .and_then(|rest| rest.find('<'))
+        .and_then(|start| Some((index + start, raw.get(index + start..)?)))
+    {
+        let skip_until = if rest.starts_with("<!--") {
+            Some("-->")
+        } else if rest.starts_with("<![CDATA[") {
+            Some("]]>")
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            Some(">")
+        } else {
+            None
+        };
+        let Some(tag_len) = rest.find(skip_until.unwrap_or(">")) else {
+            break;
+        };
+        index = start + tag_len + skip_until.map_or(1, str::len);
+        if skip_until.is_some() {
+            continue;
+        }
+        let tag = rest.get(1..tag_len).unwrap_or_default();
+        if tag.starts_with('/') {
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+        let name = tag
+            .split(|char: char| char.is_whitespace() || char == '/')
+            .next()
+            .unwrap_or_default();
+        if depth == 1 && name == "parent" {
+            has_parent = true;
+        }
+        if tag.ends_with('/') {
+            continue;
+        }
+        if depth == 1 && name == "version" {
+            let text = raw.get(index..).unwrap_or_default();
+            let text = text.split('<').next().unwrap_or_default();
+            let text_start = index + (text.len() - text.trim_start().len());
+            return ProjectVersion::Found(text_start..text_start + text.trim().len());
+        }
+        depth += 1;
+    }
+    if has_parent {
+        ProjectVersion::Inherited
+    } else {
+        ProjectVersion::Missing
+    }
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+pub enum Error {
+    #[error("Could not find the project version in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(pom_xml::missing_version),
+            help("Knope looks for a `<version>` directly inside `<project>`."),
+            url("https://knope.tech/reference/config-file/packages/#pomxml")
+        )
+    )]
+    MissingVersion { path: RelativePathBuf },
+    #[error("The project in {path} inherits its version from <parent>")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(pom_xml::inherited_version),
+            help(
+                "Add a `<version>` directly inside `<project>` for Knope to update, or version the \
+                parent's `pom.xml` instead."
+            ),
+            url("https://knope.tech/reference/config-file/packages/#pomxml")
+        )
+    )]
+    InheritedVersion { path: RelativePathBuf },
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Semver(#[from] crate::semver::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CONTENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+    <modelVersion>4.0.0</modelVersion>
+    <parent>
+        <groupId>com.example</groupId>
+        <artifactId>parent</artifactId>
+        <version>9.9.9</version>
+    </parent>
+    <!-- <version>0.0.0</version> -->
+    <artifactId>my-app</artifactId>
+    <version>
+        1.2.3
+    </version>
+    <dependencies>
+        <dependency>
+            <groupId>com.example</groupId>
+            <artifactId>other</artifactId>
+            <version>4.5.6</version>
+        </dependency>
+    </dependencies>
+</project>
+"#;
+
+    fn pom(content: &str) -> Result<PomXml, Error> {
+        PomXml::new(RelativePathBuf::from("pom.xml"), content.to_string())
+    }
+
+    #[test]
+    fn get_version() {
+        assert_eq!(
+            pom(CONTENT).unwrap().get_version(),
+            &Version::from_str("1.2.3").unwrap()
+        );
+    }
+
+    #[test]
+    fn set_version() {
+        let action = pom(CONTENT)
+            .unwrap()
+            .set_version(&Version::from_str("2.0.0-rc.0").unwrap());
+        assert_eq!(
+            action,
+            Action::WriteToFile {
+                path: RelativePathBuf::from("pom.xml"),
+                content: CONTENT.replace("        1.2.3\n", "        2.0.0-rc.0\n"),
+            }
+        );
+    }
+
+    #[test]
+    fn inherited_version() {
+        let content =
+            "<project>\n  <parent>\n    <version>1.0.0</version>\n  </parent>\n</project>\n";
+        assert!(matches!(pom(content), Err(Error::InheritedVersion { .. })));
+    }
+
+    #[test]
+    fn missing_version() {
+        let content = "<project>\n  <dependencies><dependency><version>1.0.0</version></dependency></dependencies>\n</project>\n";
+        assert!(matches!(pom(content), Err(Error::MissingVersion { .. })));
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 #[cfg(feature = "miette")]
 use miette::Diagnostic;
 use relative_path::RelativePathBuf;
@@ -34,17 +36,16 @@ impl PubSpec {
         &self.path
     }
 
-    pub(crate) fn set_version(self, new_version: &Version) -> serde_yaml::Result<Action> {
+    pub(crate) fn set_version(self, new_version: &impl Display) -> serde_yaml::Result<Action> {
         let version_line = self.raw.lines().find(|line| line.starts_with("version: "));
         let new_content = if let Some(version_line) = version_line {
             // Replace only the required bit to preserve formatting & comments (since serde_yaml doesn't preserve them)
-            self.raw.replace(
-                version_line,
-                to_string(&Yaml {
-                    version: new_version.clone(),
-                })?
-                .trim(),
-            )
+            let mut new_line = Mapping::new();
+            new_line.insert(
+                Value::String("version".to_string()),
+                Value::String(new_version.to_string()),
+            );
+            self.raw.replace(version_line, to_string(&new_line)?.trim())
         } else {
             // Can't replace just the one line, resort to replacing the whole thing
             let mut yaml = from_str::<Mapping>(&self.raw)?;
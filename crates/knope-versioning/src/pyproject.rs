@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{fmt::Display, str::FromStr};
 
 #[cfg(feature = "miette")]
 use miette::Diagnostic;
@@ -40,7 +40,7 @@ impl PyProject {
         &self.path
     }
 
-    pub(crate) fn set_version(mut self, new_version: &Version) -> Action {
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
         let version_str = new_version.to_string();
         let (poetry_version, project_version) = self.parsed.versions();
 
@@ -154,6 +154,7 @@ struct Metadata {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::panic)]
 mod tests {
     use pretty_assertions::assert_eq;
 
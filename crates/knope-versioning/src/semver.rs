@@ -23,6 +23,15 @@ impl Version {
     pub const fn is_prerelease(&self) -> bool {
         matches!(self, Version::Pre(_))
     }
+
+    /// This version followed by `build` metadata (if any), like `1.2.3+20240501`.
+    #[must_use]
+    pub fn with_build(&self, build: Option<&BuildMetadata>) -> String {
+        match build {
+            Some(build) => format!("{self}+{build}"),
+            None => self.to_string(),
+        }
+    }
 }
 
 impl Version {
@@ -157,7 +166,16 @@ impl PartialOrd for Version {
 impl FromStr for Version {
     type Err = Error;
 
+    /// Build metadata (like `+20240501`) is accepted but dropped, since it doesn't affect
+    /// precedence.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = match s.split_once('+') {
+            Some((version, build)) => {
+                BuildMetadata::from_str(build)?;
+                version
+            }
+            None => s,
+        };
         let (version, pre) = s
             .split_once('-')
             .map_or((s, None), |(version, pre)| (version, Some(pre)));
@@ -212,11 +230,12 @@ impl Display for Version {
 pub struct Prerelease {
     pub label: Label,
     pub version: u64,
+    pub separator: PrereleaseSeparator,
 }
 
 impl Display for Prerelease {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}.{}", self.label, self.version)
+        write!(f, "{}{}{}", self.label, self.separator, self.version)
     }
 }
 
@@ -224,14 +243,22 @@ impl FromStr for Prerelease {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (label, version) = s
-            .split_once('.')
-            .ok_or_else(|| Error("Invalid prerelease".to_string()))?;
+        let (label, version, separator) = if let Some((label, version)) = s.split_once('.') {
+            (label, version, PrereleaseSeparator::Dot)
+        } else {
+            let label = s.trim_end_matches(|c: char| c.is_ascii_digit());
+            let (label, version) = s.split_at(label.len());
+            (label, version, PrereleaseSeparator::None)
+        };
+        if label.is_empty() {
+            return Err(Error("Invalid prerelease".to_string()));
+        }
         Ok(Self {
             label: Label(String::from(label)),
             version: version
                 .parse::<u64>()
                 .map_err(|err| Error(err.to_string()))?,
+            separator,
         })
     }
 }
@@ -241,6 +268,7 @@ impl Ord for Prerelease {
         self.label
             .cmp(&other.label)
             .then(self.version.cmp(&other.version))
+            .then(self.separator.cmp(&other.separator))
     }
 }
 
@@ -253,7 +281,64 @@ impl PartialOrd for Prerelease {
 impl Prerelease {
     #[must_use]
     pub fn new(label: Label, version: u64) -> Self {
-        Self { label, version }
+        Self {
+            label,
+            version,
+            separator: PrereleaseSeparator::default(),
+        }
+    }
+}
+
+/// The build metadata of a version (e.g., "20240501" in "1.2.3+20240501"), which is written out
+/// but ignored when comparing versions.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuildMetadata(String);
+
+impl Display for BuildMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for BuildMetadata {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid = s.split('.').all(|identifier| {
+            !identifier.is_empty()
+                && identifier
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+        if valid {
+            Ok(Self(s.to_string()))
+        } else {
+            Err(Error(format!(
+                "Invalid build metadata {s}, it must be dot-separated identifiers of ASCII letters, digits, and hyphens"
+            )))
+        }
+    }
+}
+
+/// What goes between the label and the number of a [`Prerelease`]
+/// (e.g., the "." in "1.0.0-rc.1").
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum PrereleaseSeparator {
+    /// Like `1.0.0-rc.1`
+    #[default]
+    #[serde(rename = ".")]
+    Dot,
+    /// Like `1.0.0-rc1`
+    #[serde(rename = "")]
+    None,
+}
+
+impl Display for PrereleaseSeparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dot => write!(f, "."),
+            Self::None => Ok(()),
+        }
     }
 }
 
@@ -273,3 +358,65 @@ impl From<&str> for Label {
         Self(s.to_string())
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn prerelease_round_trips() {
+        for version in ["1.2.3-rc.0", "1.2.3-rc.1", "1.2.3-rc1", "1.2.3-beta12"] {
+            assert_eq!(Version::from_str(version).unwrap().to_string(), version);
+        }
+    }
+
+    #[test]
+    fn prerelease_without_separator() {
+        let pre = Prerelease::from_str("rc12").unwrap();
+        assert_eq!(pre.label, Label::from("rc"));
+        assert_eq!(pre.version, 12);
+        assert_eq!(pre.separator, PrereleaseSeparator::None);
+    }
+
+    #[test]
+    fn build_metadata_is_ignored() {
+        assert_eq!(
+            Version::from_str("1.2.3+20240501").unwrap(),
+            Version::from_str("1.2.3").unwrap()
+        );
+        assert_eq!(
+            Version::from_str("1.2.3-rc.1+build-5.abc").unwrap(),
+            Version::from_str("1.2.3-rc.1").unwrap()
+        );
+        for version in ["1.2.3+", "1.2.3+a..b", "1.2.3+a_b"] {
+            assert!(
+                Version::from_str(version).is_err(),
+                "{version} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn with_build() {
+        let version = Version::from_str("1.2.3-rc.1").unwrap();
+        let build = BuildMetadata::from_str("exp.sha.5114f85").unwrap();
+        assert_eq!(
+            version.with_build(Some(&build)),
+            "1.2.3-rc.1+exp.sha.5114f85"
+        );
+        assert_eq!(version.with_build(None), "1.2.3-rc.1");
+    }
+
+    #[test]
+    fn invalid_prereleases() {
+        for pre in ["rc", "12", "rc.x"] {
+            assert!(
+                Prerelease::from_str(pre).is_err(),
+                "{pre} should be invalid"
+            );
+        }
+    }
+}
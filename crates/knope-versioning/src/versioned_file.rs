@@ -8,11 +8,27 @@ use crate::{
         ActionSet,
         ActionSet::{Single, Two},
     },
-    cargo, go_mod,
+    cargo, cff,
+    cff::Citation,
+    go_const,
+    go_const::GoConst,
+    go_mod,
     go_mod::{GoMod, GoVersioning},
+    makefile,
+    makefile::Makefile,
+    nimble,
+    nimble::Nimble,
     package_json,
     package_json::PackageJson,
-    pubspec, pyproject, Cargo, PubSpec, PyProject, Version,
+    pattern,
+    pattern::Pattern,
+    pom_xml,
+    pom_xml::PomXml,
+    pubspec, pyproject, yaml,
+    yaml::Yaml,
+    zig_zon,
+    zig_zon::ZigZon,
+    BuildMetadata, Cargo, PubSpec, PyProject, Version,
 };
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -20,8 +36,18 @@ pub enum VersionedFile {
     Cargo(Cargo),
     PubSpec(PubSpec),
     GoMod(GoMod),
+    GoConst(GoConst),
     PackageJson(PackageJson),
     PyProject(PyProject),
+    Makefile(Makefile),
+    CitationCff(Citation),
+    Nimble(Nimble),
+    Yaml(Yaml),
+    Pattern(Pattern),
+    ZigZon(ZigZon),
+    PomXml(PomXml),
+    /// A file whose version never has a pre-release component, even when the package's does.
+    StripPrerelease(Box<VersionedFile>),
 }
 
 impl VersionedFile {
@@ -37,7 +63,7 @@ impl VersionedFile {
         git_tags: &[S],
     ) -> Result<Self, Error> {
         let relative_path = path.as_path();
-        match path.format {
+        let file = match path.format {
             Format::Cargo => Cargo::new(relative_path, content)
                 .map(VersionedFile::Cargo)
                 .map_err(Error::Cargo),
@@ -50,9 +76,58 @@ impl VersionedFile {
             Format::GoMod => GoMod::new(relative_path, content, git_tags)
                 .map(VersionedFile::GoMod)
                 .map_err(Error::GoMod),
+            Format::GoConst => GoConst::new(
+                relative_path,
+                content,
+                path.variable
+                    .as_deref()
+                    .unwrap_or(go_const::DEFAULT_CONSTANT),
+            )
+            .map(VersionedFile::GoConst)
+            .map_err(Error::GoConst),
             Format::PackageJson => PackageJson::new(relative_path, content)
                 .map(VersionedFile::PackageJson)
                 .map_err(Error::PackageJson),
+            Format::Makefile => Makefile::new(
+                relative_path,
+                content,
+                path.variable
+                    .as_deref()
+                    .unwrap_or(makefile::DEFAULT_VARIABLE),
+            )
+            .map(VersionedFile::Makefile)
+            .map_err(Error::Makefile),
+            Format::CitationCff => Citation::new(relative_path, content)
+                .map(VersionedFile::CitationCff)
+                .map_err(Error::CitationCff),
+            Format::Nimble => Nimble::new(relative_path, content)
+                .map(VersionedFile::Nimble)
+                .map_err(Error::Nimble),
+            Format::Yaml => Yaml::new(
+                relative_path,
+                content,
+                path.key.as_deref().unwrap_or_default(),
+            )
+            .map(VersionedFile::Yaml)
+            .map_err(Error::Yaml),
+            Format::Pattern => Pattern::new(
+                relative_path,
+                content,
+                path.pattern.as_deref().unwrap_or_default(),
+            )
+            .map(VersionedFile::Pattern)
+            .map_err(Error::Pattern),
+            Format::ZigZon => ZigZon::new(relative_path, content)
+                .map(VersionedFile::ZigZon)
+                .map_err(Error::ZigZon),
+            Format::PomXml => PomXml::new(relative_path, content)
+                .map(VersionedFile::PomXml)
+                .map_err(Error::PomXml),
+        }?;
+        if path.strip_prerelease {
+            Ok(VersionedFile::StripPrerelease(Box::new(file)))
+        } else {
+            Ok(file)
         }
     }
 
@@ -63,7 +138,16 @@ impl VersionedFile {
             VersionedFile::PyProject(pyproject) => pyproject.get_path(),
             VersionedFile::PubSpec(pubspec) => pubspec.get_path(),
             VersionedFile::GoMod(gomod) => gomod.get_path(),
+            VersionedFile::GoConst(go_const) => go_const.get_path(),
             VersionedFile::PackageJson(package_json) => package_json.get_path(),
+            VersionedFile::Makefile(makefile) => makefile.get_path(),
+            VersionedFile::CitationCff(citation) => citation.get_path(),
+            VersionedFile::Nimble(nimble) => nimble.get_path(),
+            VersionedFile::Yaml(yaml) => yaml.get_path(),
+            VersionedFile::Pattern(pattern) => pattern.get_path(),
+            VersionedFile::ZigZon(zig_zon) => zig_zon.get_path(),
+            VersionedFile::PomXml(pom_xml) => pom_xml.get_path(),
+            VersionedFile::StripPrerelease(file) => file.path(),
         }
     }
 
@@ -74,11 +158,40 @@ impl VersionedFile {
             VersionedFile::PyProject(pyproject) => pyproject.get_version(),
             VersionedFile::PubSpec(pubspec) => pubspec.get_version(),
             VersionedFile::GoMod(gomod) => gomod.get_version(),
+            VersionedFile::GoConst(go_const) => go_const.get_version(),
             VersionedFile::PackageJson(package_json) => package_json.get_version(),
+            VersionedFile::Makefile(makefile) => makefile.get_version(),
+            VersionedFile::CitationCff(citation) => citation.get_version(),
+            VersionedFile::Nimble(nimble) => nimble.get_version(),
+            VersionedFile::Yaml(yaml) => yaml.get_version(),
+            VersionedFile::Pattern(pattern) => pattern.get_version(),
+            VersionedFile::ZigZon(zig_zon) => zig_zon.get_version(),
+            VersionedFile::PomXml(pom_xml) => pom_xml.get_version(),
+            VersionedFile::StripPrerelease(file) => file.version(),
         }
     }
 
-    /// Set the version in the file.
+    /// The version this file holds when the package is at `version`.
+    #[must_use]
+    pub fn version_for(&self, version: &Version) -> Version {
+        match self {
+            VersionedFile::StripPrerelease(_) => Version::Stable(version.stable_component()),
+            _ => version.clone(),
+        }
+    }
+
+    /// Whether the version in this file is what it should be when the package is at `version`.
+    #[must_use]
+    pub fn matches(&self, version: &Version) -> bool {
+        self.version() == &self.version_for(version)
+    }
+
+    /// Whether this file's version can be used as the version of the whole package.
+    pub(crate) const fn has_full_version(&self) -> bool {
+        !matches!(self, VersionedFile::StripPrerelease(_))
+    }
+
+    /// Set the version in the file, with `build` metadata if the format allows it.
     ///
     /// # Errors
     ///
@@ -86,23 +199,39 @@ impl VersionedFile {
     pub(crate) fn set_version(
         self,
         new_version: &Version,
+        build: Option<&BuildMetadata>,
         go_versioning: GoVersioning,
     ) -> Result<ActionSet, SetError> {
+        let full_version = &new_version.with_build(build);
         match self {
-            VersionedFile::Cargo(cargo) => Ok(Single(cargo.set_version(new_version))),
-            VersionedFile::PyProject(pyproject) => Ok(Single(pyproject.set_version(new_version))),
+            VersionedFile::Cargo(cargo) => Ok(Single(cargo.set_version(full_version))),
+            VersionedFile::PyProject(pyproject) => Ok(Single(pyproject.set_version(full_version))),
             VersionedFile::PubSpec(pubspec) => pubspec
-                .set_version(new_version)
+                .set_version(full_version)
                 .map_err(SetError::Yaml)
                 .map(Single),
+            // Go module versions can't have build metadata
             VersionedFile::GoMod(gomod) => gomod
                 .set_version(new_version, go_versioning)
                 .map_err(SetError::GoMod)
                 .map(Two),
+            VersionedFile::GoConst(go_const) => Ok(Single(go_const.set_version(full_version))),
             VersionedFile::PackageJson(package_json) => package_json
-                .set_version(new_version)
+                .set_version(full_version)
                 .map_err(SetError::Json)
                 .map(Single),
+            VersionedFile::Makefile(makefile) => Ok(Single(makefile.set_version(full_version))),
+            VersionedFile::CitationCff(citation) => Ok(Single(citation.set_version(full_version))),
+            VersionedFile::Nimble(nimble) => Ok(Single(nimble.set_version(full_version))),
+            VersionedFile::Yaml(yaml) => Ok(Single(yaml.set_version(full_version))),
+            VersionedFile::Pattern(pattern) => Ok(Single(pattern.set_version(full_version))),
+            VersionedFile::ZigZon(zig_zon) => Ok(Single(zig_zon.set_version(full_version))),
+            VersionedFile::PomXml(pom_xml) => Ok(Single(pom_xml.set_version(full_version))),
+            VersionedFile::StripPrerelease(file) => file.set_version(
+                &Version::Stable(new_version.stable_component()),
+                None,
+                go_versioning,
+            ),
         }
     }
 }
@@ -152,13 +281,49 @@ pub enum Error {
     GoMod(#[from] go_mod::Error),
     #[error(transparent)]
     #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    GoConst(#[from] go_const::Error),
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
     PackageJson(#[from] package_json::Error),
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Makefile(#[from] makefile::Error),
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    CitationCff(#[from] cff::Error),
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Nimble(#[from] nimble::Error),
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Yaml(#[from] yaml::Error),
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Pattern(#[from] pattern::Error),
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    ZigZon(#[from] zig_zon::Error),
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    PomXml(#[from] pom_xml::Error),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Path {
     parent: Option<RelativePathBuf>,
     format: Format,
+    /// The variable which holds the version, only for [`Format::Makefile`] and
+    /// [`Format::GoConst`].
+    variable: Option<String>,
+    /// The dotted key which holds the version, only for [`Format::Yaml`].
+    key: Option<String>,
+    /// The regex whose first capture group is the version, only for [`Format::Pattern`].
+    pattern: Option<String>,
+    /// The name of the file, only for [`Format::Nimble`], [`Format::GoConst`], [`Format::Yaml`],
+    /// and [`Format::Pattern`] since it can be anything.
+    file_name: Option<String>,
+    /// Whether to leave the pre-release component out of the version in this file.
+    strip_prerelease: bool,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -167,7 +332,19 @@ enum Format {
     PyProject,
     PubSpec,
     GoMod,
+    /// Any Go source file (ending in `.go`), with a string constant holding the version.
+    GoConst,
     PackageJson,
+    Makefile,
+    CitationCff,
+    /// Any file ending in `.nimble`.
+    Nimble,
+    /// Any YAML file, with a `key` to find the version at.
+    Yaml,
+    /// Any file, with a `pattern` to find the version with.
+    Pattern,
+    ZigZon,
+    PomXml,
 }
 
 impl Format {
@@ -178,6 +355,12 @@ impl Format {
             Format::PubSpec => "pubspec.yaml",
             Format::GoMod => "go.mod",
             Format::PackageJson => "package.json",
+            Format::Makefile => "Makefile",
+            Format::CitationCff => "CITATION.cff",
+            Format::ZigZon => "build.zig.zon",
+            Format::PomXml => "pom.xml",
+            // The name is stored in the `Path` instead, since it can be anything
+            Format::Nimble | Format::GoConst | Format::Yaml | Format::Pattern => "",
         }
     }
 
@@ -188,6 +371,10 @@ impl Format {
             "pubspec.yaml" => Some(Format::PubSpec),
             "go.mod" => Some(Format::GoMod),
             "package.json" => Some(Format::PackageJson),
+            "Makefile" => Some(Format::Makefile),
+            "CITATION.cff" => Some(Format::CitationCff),
+            "build.zig.zon" => Some(Format::ZigZon),
+            "pom.xml" => Some(Format::PomXml),
             _ => None,
         }
     }
@@ -208,6 +395,36 @@ pub struct UnknownFile {
     pub path: RelativePathBuf,
 }
 
+#[derive(Debug, thiserror::Error)]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
+#[error("{path} does not support setting a variable")]
+#[cfg_attr(
+    feature = "miette",
+    diagnostic(
+        code(knope_versioning::versioned_file::variable_not_supported),
+        help("Only a `Makefile` or a Go file (ending in `.go`) can have a `variable`."),
+        url("https://knope.tech/reference/config-file/packages#makefile")
+    )
+)]
+pub struct VariableNotSupported {
+    pub path: RelativePathBuf,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[cfg_attr(feature = "miette", derive(miette::Diagnostic))]
+#[error("{path} does not support setting a key")]
+#[cfg_attr(
+    feature = "miette",
+    diagnostic(
+        code(knope_versioning::versioned_file::key_not_supported),
+        help("Only YAML files (ending in `.yml` or `.yaml`) can have a `key`."),
+        url("https://knope.tech/reference/config-file/packages#yaml")
+    )
+)]
+pub struct KeyNotSupported {
+    pub path: RelativePathBuf,
+}
+
 impl Path {
     /// Create a verified `Path` from a `RelativePathBuf`.
     ///
@@ -217,15 +434,135 @@ impl Path {
     pub fn new(path: RelativePathBuf) -> Result<Self, UnknownFile> {
         let file_name = path.file_name().ok_or(UnknownFile { path: path.clone() })?;
         let parent = path.parent().map(RelativePathBuf::from);
-        let format = Format::try_from(file_name).ok_or(UnknownFile { path })?;
-        Ok(Path { parent, format })
+        let (format, file_name) = match Format::try_from(file_name) {
+            Some(format) => (format, None),
+            // Nim packages name the file after the package, like `my_package.nimble`
+            None if path.extension() == Some("nimble") => {
+                (Format::Nimble, Some(file_name.to_string()))
+            }
+            None if path.extension() == Some("go") => {
+                (Format::GoConst, Some(file_name.to_string()))
+            }
+            None => return Err(UnknownFile { path }),
+        };
+        Ok(Path {
+            parent,
+            format,
+            variable: None,
+            key: None,
+            pattern: None,
+            file_name,
+            strip_prerelease: false,
+        })
+    }
+
+    /// Create a `Path` for any YAML file, with the version at the dotted `key` (like
+    /// `inputs.version.default`).
+    ///
+    /// # Errors
+    ///
+    /// If the file name doesn't end in `.yml` or `.yaml`
+    pub fn with_key(path: RelativePathBuf, key: String) -> Result<Self, KeyNotSupported> {
+        let Some(file_name) = path
+            .file_name()
+            .filter(|_| matches!(path.extension(), Some("yml" | "yaml")))
+        else {
+            return Err(KeyNotSupported { path });
+        };
+        Ok(Path {
+            parent: path.parent().map(RelativePathBuf::from),
+            format: Format::Yaml,
+            variable: None,
+            key: Some(key),
+            pattern: None,
+            file_name: Some(file_name.to_string()),
+            strip_prerelease: false,
+        })
+    }
+
+    /// Create a `Path` for any file, with the version in the first capture group (or the one named
+    /// `version`) of every match of the regex `pattern`.
+    ///
+    /// # Errors
+    ///
+    /// If the path has no file name
+    pub fn with_pattern(path: RelativePathBuf, pattern: String) -> Result<Self, UnknownFile> {
+        let Some(file_name) = path.file_name() else {
+            return Err(UnknownFile { path });
+        };
+        Ok(Path {
+            parent: path.parent().map(RelativePathBuf::from),
+            format: Format::Pattern,
+            variable: None,
+            key: None,
+            pattern: Some(pattern),
+            file_name: Some(file_name.to_string()),
+            strip_prerelease: false,
+        })
+    }
+
+    /// Set the variable which holds the version, which is only supported for `Makefile`s and
+    /// Go files (where it's the name of the constant).
+    ///
+    /// # Errors
+    ///
+    /// If the file is not a `Makefile` or Go file
+    pub fn with_variable(self, variable: String) -> Result<Self, VariableNotSupported> {
+        if matches!(self.format, Format::Makefile | Format::GoConst) {
+            Ok(Self {
+                variable: Some(variable),
+                ..self
+            })
+        } else {
+            Err(VariableNotSupported {
+                path: self.as_path(),
+            })
+        }
+    }
+
+    /// Leave the pre-release component out of the version written to this file, so it's always a
+    /// stable version like `1.2.3` (even when the package is at `1.2.3-rc.1`).
+    #[must_use]
+    pub fn with_strip_prerelease(self) -> Self {
+        Self {
+            strip_prerelease: true,
+            ..self
+        }
+    }
+
+    /// Whether [`Self::with_strip_prerelease`] was used.
+    #[must_use]
+    pub const fn strip_prerelease(&self) -> bool {
+        self.strip_prerelease
+    }
+
+    /// The variable which holds the version, if one was set with [`Self::with_variable`].
+    #[must_use]
+    pub fn variable(&self) -> Option<&str> {
+        self.variable.as_deref()
+    }
+
+    /// The key which holds the version, if this was created with [`Self::with_key`].
+    #[must_use]
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// The regex which finds the version, if this was created with [`Self::with_pattern`].
+    #[must_use]
+    pub fn pattern(&self) -> Option<&str> {
+        self.pattern.as_deref()
     }
 
     #[must_use]
     pub fn as_path(&self) -> RelativePathBuf {
+        let file_name = self
+            .file_name
+            .as_deref()
+            .unwrap_or_else(|| self.format.file_name());
         self.parent.as_ref().map_or_else(
-            || RelativePathBuf::from(self.format.file_name()),
-            |parent| parent.join(self.format.file_name()),
+            || RelativePathBuf::from(file_name),
+            |parent| parent.join(file_name),
         )
     }
 
@@ -239,35 +576,110 @@ impl Path {
         [
             Path {
                 parent: None,
+                variable: None,
+                key: None,
+                pattern: None,
+                file_name: None,
+                strip_prerelease: false,
                 format: Format::Cargo,
             },
             Path {
                 parent: None,
+                variable: None,
+                key: None,
+                pattern: None,
+                file_name: None,
+                strip_prerelease: false,
                 format: Format::GoMod,
             },
             Path {
                 parent: None,
+                variable: None,
+                key: None,
+                pattern: None,
+                file_name: None,
+                strip_prerelease: false,
                 format: Format::PackageJson,
             },
             Path {
                 parent: None,
+                variable: None,
+                key: None,
+                pattern: None,
+                file_name: None,
+                strip_prerelease: false,
                 format: Format::PubSpec,
             },
             Path {
                 parent: None,
+                variable: None,
+                key: None,
+                pattern: None,
+                file_name: None,
+                strip_prerelease: false,
                 format: Format::PyProject,
             },
         ]
     }
 }
 
+/// How a [`Path`] is written in config: either just the path, or a table with extra options.
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum PathConfig {
+    Path(RelativePathBuf),
+    WithOptions {
+        path: RelativePathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        variable: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+        #[serde(default, alias = "search", skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        strip_prerelease: bool,
+    },
+}
+
 impl<'de> Deserialize<'de> for Path {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let path = RelativePathBuf::deserialize(deserializer)?;
-        Path::new(path).map_err(serde::de::Error::custom)
+        match PathConfig::deserialize(deserializer)? {
+            PathConfig::Path(path) => Path::new(path).map_err(serde::de::Error::custom),
+            PathConfig::WithOptions {
+                path,
+                variable,
+                key,
+                pattern,
+                strip_prerelease,
+            } => {
+                let mut path = match (key, pattern) {
+                    (Some(_), Some(_)) => {
+                        return Err(serde::de::Error::custom(
+                            "a versioned file can't have both a `key` and a `pattern`",
+                        ))
+                    }
+                    (Some(key), None) => {
+                        Path::with_key(path, key).map_err(serde::de::Error::custom)?
+                    }
+                    (None, Some(pattern)) => {
+                        Path::with_pattern(path, pattern).map_err(serde::de::Error::custom)?
+                    }
+                    (None, None) => Path::new(path).map_err(serde::de::Error::custom)?,
+                };
+                if let Some(variable) = variable {
+                    path = path
+                        .with_variable(variable)
+                        .map_err(serde::de::Error::custom)?;
+                }
+                if strip_prerelease {
+                    path = path.with_strip_prerelease();
+                }
+                Ok(path)
+            }
+        }
     }
 }
 
@@ -276,7 +688,22 @@ impl Serialize for Path {
     where
         S: Serializer,
     {
-        self.as_path().serialize(serializer)
+        if self.variable.is_some()
+            || self.key.is_some()
+            || self.pattern.is_some()
+            || self.strip_prerelease
+        {
+            PathConfig::WithOptions {
+                path: self.as_path(),
+                variable: self.variable.clone(),
+                key: self.key.clone(),
+                pattern: self.pattern.clone(),
+                strip_prerelease: self.strip_prerelease,
+            }
+        } else {
+            PathConfig::Path(self.as_path())
+        }
+        .serialize(serializer)
     }
 }
 
@@ -0,0 +1,235 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+#[cfg(feature = "miette")]
+use miette::Diagnostic;
+use relative_path::RelativePathBuf;
+use thiserror::Error;
+
+use crate::{action::Action, Version};
+
+/// Any YAML file which stores the version at a configured key, like `inputs.version.default` in
+/// the `action.yml` of a GitHub Action.
+///
+/// Only block mappings are followed (not sequences or flow mappings like `{ a: b }`), which is
+/// what lets the rest of the file stay exactly as it was.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Yaml {
+    path: RelativePathBuf,
+    raw: String,
+    /// Where the value of the key is in `raw`, without any quotes.
+    span: Range<usize>,
+    version: Version,
+}
+
+impl Yaml {
+    pub(crate) fn new(path: RelativePathBuf, raw: String, key: &str) -> Result<Self, Error> {
+        let Some(span) = value_span(&raw, key) else {
+            return Err(Error::MissingKey {
+                path,
+                key: key.to_string(),
+            });
+        };
+        let version = raw
+            .get(span.clone())
+            .map(Version::from_str)
+            .transpose()?
+            .ok_or_else(|| Error::MissingKey {
+                path: path.clone(),
+                key: key.to_string(),
+            })?;
+        Ok(Self {
+            path,
+            raw,
+            span,
+            version,
+        })
+    }
+
+    pub(crate) fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn get_path(&self) -> &RelativePathBuf {
+        &self.path
+    }
+
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
+        self.raw
+            .replace_range(self.span.clone(), &new_version.to_string());
+        Action::WriteToFile {
+            path: self.path,
+            content: self.raw,
+        }
+    }
+}
+
+/// Find the scalar value at the dotted `key` in `raw`.
+fn value_span(raw: &str, key: &str) -> Option<Range<usize>> {
+    let mut segments = key.split('.').peekable();
+    let mut segment = segments.next()?;
+    // The indentation of the key whose block is being searched, if it's not the top level
+    let mut parent_indent = None;
+    // The indentation of the keys directly in that block, known from its first line
+    let mut child_indent = None;
+    let mut line_start = 0;
+    for line in raw.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_start_matches(' ');
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+            continue;
+        }
+        let indent = content.len() - trimmed.len();
+        if parent_indent.is_some_and(|parent_indent| indent <= parent_indent) {
+            // Left the block without finding the key
+            return None;
+        }
+        if *child_indent.get_or_insert(indent) != indent {
+            continue;
+        }
+        let Some(value) = strip_key(trimmed, segment) else {
+            continue;
+        };
+        if let Some(next) = segments.next() {
+            segment = next;
+            parent_indent = Some(indent);
+            child_indent = None;
+            continue;
+        }
+        let value_start = start + content.len() - value.len();
+        return scalar_span(value).map(|span| value_start + span.start..value_start + span.end);
+    }
+    None
+}
+
+/// If `line` is the (possibly quoted) `key`, return everything after its `:`.
+fn strip_key<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let after_key = [
+        line.strip_prefix(key),
+        line.strip_prefix('"')
+            .and_then(|line| line.strip_prefix(key))
+            .and_then(|line| line.strip_prefix('"')),
+        line.strip_prefix('\'')
+            .and_then(|line| line.strip_prefix(key))
+            .and_then(|line| line.strip_prefix('\'')),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|rest| rest.trim_start_matches(' ').strip_prefix(':'))?;
+    if after_key.is_empty() || after_key.starts_with([' ', '\t']) {
+        Some(after_key)
+    } else {
+        // Like `key:value`, which YAML reads as a single string instead of a key
+        None
+    }
+}
+
+/// The span of a scalar `value` (everything after the `:`), without whitespace, comments, or quotes.
+fn scalar_span(value: &str) -> Option<Range<usize>> {
+    let trimmed = value.trim_start_matches([' ', '\t']);
+    let start = value.len() - trimmed.len();
+    let without_comment = trimmed
+        .find(" #")
+        .map_or(trimmed, |comment| trimmed.get(..comment).unwrap_or(trimmed))
+        .trim_end();
+    let unquoted = ['"', '\''].into_iter().find_map(|quote| {
+        without_comment
+            .strip_prefix(quote)
+            .and_then(|it| it.strip_suffix(quote))
+    });
+    match unquoted {
+        Some(unquoted) => Some(start + 1..start + 1 + unquoted.len()),
+        None if without_comment.is_empty() => None,
+        None => Some(start..start + without_comment.len()),
+    }
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+pub enum Error {
+    #[error("Could not find the key {key} in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(yaml::missing_key),
+            help(
+                "Each part of the key should be a key in a block mapping, and the last one should have the version as its value."
+            ),
+            url("https://knope.tech/reference/config-file/packages#yaml")
+        )
+    )]
+    MissingKey { path: RelativePathBuf, key: String },
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Semver(#[from] crate::semver::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const ACTION: &str = r#"name: "My Action"
+description: Does things # version: 0.0.0
+inputs:
+  token:
+    description: A token
+    default: ""
+  version:
+    description: "The version to install"
+    default: '1.2.3' # Bumped by Knope
+runs:
+  using: composite
+  steps:
+    - run: echo "version: 9.9.9"
+"#;
+
+    fn action(key: &str) -> Result<Yaml, Error> {
+        Yaml::new(RelativePathBuf::from("action.yml"), ACTION.to_string(), key)
+    }
+
+    #[test]
+    fn get_version() {
+        assert_eq!(
+            action("inputs.version.default").unwrap().get_version(),
+            &Version::from_str("1.2.3").unwrap()
+        );
+    }
+
+    #[test]
+    fn set_version() {
+        let action = action("inputs.version.default")
+            .unwrap()
+            .set_version(&Version::from_str("2.0.0-rc.0").unwrap());
+        assert_eq!(
+            action,
+            Action::WriteToFile {
+                path: RelativePathBuf::from("action.yml"),
+                content: ACTION.replace("'1.2.3'", "'2.0.0-rc.0'"),
+            }
+        );
+    }
+
+    #[test]
+    fn top_level_key() {
+        let yaml = Yaml::new(
+            RelativePathBuf::from("chart.yaml"),
+            "apiVersion: v2\nversion: 0.1.0\n".to_string(),
+            "version",
+        )
+        .unwrap();
+        assert_eq!(yaml.get_version(), &Version::from_str("0.1.0").unwrap());
+    }
+
+    #[test]
+    fn missing_key() {
+        assert!(matches!(
+            action("inputs.token.version"),
+            Err(Error::MissingKey { .. })
+        ));
+        assert!(matches!(action("version"), Err(Error::MissingKey { .. })));
+    }
+}
@@ -0,0 +1,195 @@
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+#[cfg(feature = "miette")]
+use miette::Diagnostic;
+use relative_path::RelativePathBuf;
+use thiserror::Error;
+
+use crate::{action::Action, Version};
+
+/// A Zig `build.zig.zon`, which declares the version in a top-level `.version = "1.2.3"` field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ZigZon {
+    path: RelativePathBuf,
+    raw: String,
+    /// Where the version is in `raw`, without the quotes.
+    span: Range<usize>,
+    version: Version,
+}
+
+impl ZigZon {
+    pub(crate) fn new(path: RelativePathBuf, raw: String) -> Result<Self, Error> {
+        let Some(span) = version_span(&raw) else {
+            return Err(Error::MissingVersion { path });
+        };
+        let version = raw
+            .get(span.clone())
+            .map(Version::from_str)
+            .transpose()?
+            .ok_or(Error::MissingVersion { path: path.clone() })?;
+        Ok(Self {
+            path,
+            raw,
+            span,
+            version,
+        })
+    }
+
+    pub(crate) fn get_version(&self) -> &Version {
+        &self.version
+    }
+
+    pub(crate) fn get_path(&self) -> &RelativePathBuf {
+        &self.path
+    }
+
+    pub(crate) fn set_version(mut self, new_version: &impl Display) -> Action {
+        self.raw
+            .replace_range(self.span.clone(), &new_version.to_string());
+        Action::WriteToFile {
+            path: self.path,
+            content: self.raw,
+        }
+    }
+}
+
+const FIELD: &str = ".version";
+
+/// Find the string literal of the `.version` field of the outermost struct, skipping strings and
+/// comments, and ignoring any `.version` within nested structs (like `.dependencies`).
+fn version_span(raw: &str) -> Option<Range<usize>> {
+    let mut depth = 0_usize;
+    let mut index = 0;
+    while let Some(rest) = raw.get(index..) {
+        let mut chars = rest.chars();
+        let Some(char) = chars.next() else {
+            break;
+        };
+        if rest.starts_with("//") || rest.starts_with("\\\\") {
+            // A comment or a line of a multiline string, both of which end with the line
+            index += rest.find('\n').unwrap_or(rest.len());
+            continue;
+        }
+        match char {
+            '"' => {
+                index += 1 + string_len(rest.get(1..)?)? + 1;
+                continue;
+            }
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            '.' if depth == 1 => {
+                if let Some(span) = field_value_span(raw, index) {
+                    return Some(span);
+                }
+            }
+            _ => {}
+        }
+        index += char.len_utf8();
+    }
+    None
+}
+
+/// If `.version = "..."` starts at `start`, the span of the value without quotes.
+fn field_value_span(raw: &str, start: usize) -> Option<Range<usize>> {
+    let after_field = raw.get(start..)?.strip_prefix(FIELD)?;
+    if after_field.starts_with(|char: char| char.is_alphanumeric() || char == '_') {
+        // Another field, like `.version_name`
+        return None;
+    }
+    let value = after_field
+        .trim_start()
+        .strip_prefix('=')?
+        .trim_start()
+        .strip_prefix('"')?;
+    let value_start = raw.len() - value.len();
+    Some(value_start..value_start + string_len(value)?)
+}
+
+/// The length of a string literal's contents, where `rest` starts just after the opening quote.
+fn string_len(rest: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (index, char) in rest.char_indices() {
+        match char {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some(index),
+            '\n' => return None,
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+#[derive(Debug, Error)]
+#[cfg_attr(feature = "miette", derive(Diagnostic))]
+pub enum Error {
+    #[error("Could not find the version in {path}")]
+    #[cfg_attr(
+        feature = "miette",
+        diagnostic(
+            code(zig_zon::missing_version),
+            help("Knope looks for a `.version = \"1.2.3\"` field in the outermost struct."),
+            url("https://knope.tech/reference/config-file/packages/#buildzigzon")
+        )
+    )]
+    MissingVersion { path: RelativePathBuf },
+    #[error(transparent)]
+    #[cfg_attr(feature = "miette", diagnostic(transparent))]
+    Semver(#[from] crate::semver::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const CONTENT: &str = r#".{
+    .name = "my_package",
+    // .version = "0.0.0",
+    .description = "Not .version = \"0.0.1\"",
+    .dependencies = .{
+        .other = .{
+            .version = "0.0.2",
+            .url = "https://example.com/other.tar.gz",
+        },
+    },
+    .version = "1.2.3",
+    .paths = .{""},
+}
+"#;
+
+    fn zon(content: &str) -> Result<ZigZon, Error> {
+        ZigZon::new(RelativePathBuf::from("build.zig.zon"), content.to_string())
+    }
+
+    #[test]
+    fn get_version() {
+        assert_eq!(
+            zon(CONTENT).unwrap().get_version(),
+            &Version::from_str("1.2.3").unwrap()
+        );
+    }
+
+    #[test]
+    fn set_version() {
+        let action = zon(CONTENT)
+            .unwrap()
+            .set_version(&Version::from_str("2.0.0-rc.0").unwrap());
+        assert_eq!(
+            action,
+            Action::WriteToFile {
+                path: RelativePathBuf::from("build.zig.zon"),
+                content: CONTENT.replace("\"1.2.3\"", "\"2.0.0-rc.0\""),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_version() {
+        assert!(matches!(
+            zon(".{\n    .name = \"my_package\",\n    .version_name = \"1.2.3\",\n}\n"),
+            Err(Error::MissingVersion { .. })
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use ::toml::{from_str, to_string, Spanned};
 use indexmap::IndexMap;
@@ -12,7 +12,7 @@ use toml::ConfigLoader;
 use crate::{
     fs,
     integrations::git,
-    step::{PrepareRelease, Step},
+    step::{command, releases::ChangeFileArgs, Release, Step},
     variables::Variable,
     workflow::Workflow,
 };
@@ -20,10 +20,11 @@ use crate::{
 mod package;
 mod toml;
 
-pub(crate) use toml::{GitHub, Gitea, Jira};
+pub(crate) use toml::{Git, GitHub, Gitea, Jira};
 
 pub(crate) use self::package::{
-    ChangeLogSectionName, ChangelogSection, CommitFooter, CustomChangeType,
+    Bump, ChangeLogSectionName, ChangelogSection, CommitFooter, CommitType, CustomChangeType,
+    DependencyUpdates,
 };
 
 /// A valid config, loaded from a supported file (or detected via default)
@@ -32,12 +33,18 @@ pub(crate) struct Config {
     pub(crate) packages: Vec<Package>,
     /// The list of defined workflows that are selectable
     pub(crate) workflows: Vec<Workflow>,
+    /// The workflow to run when none is selected
+    pub(crate) default_workflow: Option<String>,
     /// Optional configuration for Jira
     pub(crate) jira: Option<Jira>,
     /// Optional configuration to talk to GitHub
     pub(crate) github: Option<GitHub>,
     /// Optional configuration to communicate with a Gitea instance
     pub(crate) gitea: Option<Gitea>,
+    /// Optional identity to use for Git commits and tags
+    pub(crate) git: Option<Git>,
+    /// The directory to create and read change files in, `.changeset` if not set
+    pub(crate) change_directory: Option<PathBuf>,
 }
 
 impl Config {
@@ -45,10 +52,20 @@ impl Config {
 
     /// Create a Config from a TOML file or load the default config via `generate`
     ///
+    /// If `path` is set (from `--config`), that file is used instead of `knope.toml` and must exist.
+    ///
     /// ## Errors
     /// 1. Cannot parse file contents into a Config
-    pub(crate) fn load() -> Result<ConfigSource, Error> {
-        let Ok(source_code) = fs::read_to_string(Self::CONFIG_PATH) else {
+    /// 2. `path` is set but can't be read
+    pub(crate) fn load(path: Option<&Path>) -> Result<ConfigSource, Error> {
+        let source_code = if let Some(path) = path {
+            std::fs::read_to_string(path).map_err(|source| Error::CouldNotRead {
+                path: path.to_path_buf(),
+                source,
+            })?
+        } else if let Ok(source_code) = fs::read_to_string(Self::CONFIG_PATH) {
+            source_code
+        } else {
             log::debug!("No `knope.toml` found, using default config");
             return Ok(ConfigSource::Default(generate()?));
         };
@@ -58,6 +75,24 @@ impl Config {
         config_source.fill_in_gaps()
     }
 
+    /// If there's no `knope.toml` in the current directory, change to the closest parent directory
+    /// which has one, stopping at the root of the Git repository. If there's no config, the Git
+    /// root is used instead. This way, every relative path (in the config and elsewhere) means
+    /// the same thing from anywhere in the repository.
+    pub(crate) fn change_to_project_root() -> Result<(), Error> {
+        let Ok(current_dir) = std::env::current_dir() else {
+            return Ok(());
+        };
+        let Some(root) = find_project_root(&current_dir) else {
+            return Ok(());
+        };
+        if root == current_dir {
+            return Ok(());
+        }
+        log::debug!("Running from the project root {}", root.display());
+        std::env::set_current_dir(&root).map_err(|source| Error::ChangeDir { path: root, source })
+    }
+
     /// Set the prerelease label for all `PrepareRelease` steps in all workflows in `self`.
     pub(crate) fn set_prerelease_label(&mut self, label: &str) {
         for workflow in &mut self.workflows {
@@ -65,8 +100,29 @@ impl Config {
         }
     }
 
-    /// Write out the Config to `knope.toml`.
-    pub(crate) fn write_out(mut self) -> Result<()> {
+    /// Set `keep_change_files` for all `PrepareRelease` steps in all workflows in `self`.
+    pub(crate) fn set_keep_change_files(&mut self) {
+        for workflow in &mut self.workflows {
+            workflow.set_keep_change_files();
+        }
+    }
+
+    /// Set `since_date` for all `PrepareRelease` steps in all workflows in `self`.
+    pub(crate) fn set_since_date(&mut self, since_date: &str) {
+        for workflow in &mut self.workflows {
+            workflow.set_since_date(since_date);
+        }
+    }
+
+    /// Set the arguments of all `CreateChangeFile` steps in all workflows in `self`.
+    pub(crate) fn set_change_file_args(&mut self, args: &ChangeFileArgs) {
+        for workflow in &mut self.workflows {
+            workflow.set_change_file_args(args);
+        }
+    }
+
+    /// Write out the Config to `path`, or `knope.toml` if not set.
+    pub(crate) fn write_out(mut self, path: Option<&Path>) -> Result<()> {
         #[derive(Serialize)]
         struct SimpleConfig {
             #[serde(skip_serializing_if = "Option::is_none")]
@@ -74,6 +130,8 @@ impl Config {
             #[serde(skip_serializing_if = "Vec::is_empty")]
             packages: Vec<toml::Package>,
             workflows: Vec<Workflow>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            default_workflow: Option<String>,
             github: Option<GitHub>,
             gitea: Option<Gitea>,
         }
@@ -88,13 +146,15 @@ impl Config {
             package,
             packages,
             workflows: self.workflows,
+            default_workflow: self.default_workflow,
             github: self.github,
             gitea: self.gitea,
         };
         #[allow(clippy::unwrap_used)] // because serde is annoying... I know it will serialize
         let serialized = to_string(&config).unwrap();
 
-        fs::write(&mut None, "", Path::new(Config::CONFIG_PATH), serialized).into_diagnostic()
+        let path = path.unwrap_or_else(|| Path::new(Config::CONFIG_PATH));
+        fs::write(&mut None, "", path, serialized).into_diagnostic()
     }
 }
 
@@ -141,7 +201,7 @@ impl TryFrom<(ConfigLoader, String)> for Config {
             return Err(Error::GiteaAssetUploads);
         }
 
-        let workflows = config
+        let workflows: Vec<Workflow> = config
             .workflows
             .map(|workflows| {
                 workflows
@@ -152,12 +212,35 @@ impl TryFrom<(ConfigLoader, String)> for Config {
             })
             .unwrap_or_default();
 
+        let default_workflow = config
+            .default_workflow
+            .map(|default_workflow| {
+                // Without any workflows, the default ones are used, which are checked when running
+                if workflows.is_empty()
+                    || workflows
+                        .iter()
+                        .any(|workflow| workflow.name == *default_workflow.get_ref())
+                {
+                    Ok(default_workflow.into_inner())
+                } else {
+                    Err(Error::UnknownDefaultWorkflow {
+                        name: default_workflow.get_ref().clone(),
+                        source_code: source_code.clone(),
+                        span: default_workflow.span().into(),
+                    })
+                }
+            })
+            .transpose()?;
+
         Ok(Self {
             packages,
             workflows,
+            default_workflow,
             jira: config.jira.map(Spanned::into_inner),
             github: config.github.map(Spanned::into_inner),
             gitea: config.gitea.map(Spanned::into_inner),
+            git: config.git.map(Spanned::into_inner),
+            change_directory: config.change_directory.map(Spanned::into_inner),
         })
     }
 }
@@ -207,6 +290,26 @@ pub(crate) enum Error {
         url("https://knope.tech/reference/config-file/packages/")
     )]
     Toml(#[from] ::toml::de::Error),
+    #[error("Could not read the config file {path}")]
+    #[diagnostic(
+        code(config::could_not_read),
+        help("The path passed to `--config` is relative to the current directory."),
+        url("https://knope.tech/reference/command-line-arguments/#--config")
+    )]
+    CouldNotRead {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not change to the project root {path}")]
+    #[diagnostic(
+        code(config::change_dir),
+        help("Knope runs from the closest directory with a `knope.toml` (or the Git root), so it needs access to it."),
+        url("https://knope.tech/reference/command-line-arguments/#workflow")
+    )]
+    ChangeDir {
+        path: PathBuf,
+        source: std::io::Error,
+    },
     #[error("You cannot define both `packages` and `package`")]
     #[diagnostic(
         code(config::conflicting_packages),
@@ -235,6 +338,19 @@ pub(crate) enum Error {
         url("https://github.com/knope-dev/knope/issues/779")
     )]
     GiteaAssetUploads,
+    #[error("There is no workflow named {name}")]
+    #[diagnostic(
+        code(config::unknown_default_workflow),
+        help("`default_workflow` must be the name of one of the `workflows`."),
+        url("https://knope.tech/reference/config-file/workflow/#default_workflow")
+    )]
+    UnknownDefaultWorkflow {
+        name: String,
+        #[source_code]
+        source_code: String,
+        #[label("set here")]
+        span: SourceSpan,
+    },
     #[error(transparent)]
     #[diagnostic(transparent)]
     Package(#[from] package::Error),
@@ -243,6 +359,19 @@ pub(crate) enum Error {
     VersionedFile(#[from] package::VersionedFileError),
 }
 
+/// The first of `start` and its parents which has a `knope.toml` or is the root of a Git repository.
+/// The JSON Schema of `knope.toml`, which editors can use to validate and complete it.
+pub(crate) fn schema() -> String {
+    serde_json::to_string_pretty(&schemars::schema_for!(ConfigLoader)).unwrap_or_default()
+}
+
+fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(Config::CONFIG_PATH).is_file() || dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
 /// Generate a brand new Config for the project in the current directory.
 pub(crate) fn generate() -> Result<Config, package::Error> {
     let packages = Package::find_in_working_dir()?;
@@ -282,9 +411,12 @@ pub(crate) fn generate() -> Result<Config, package::Error> {
 
     Ok(Config {
         workflows: generate_workflows(github.is_some() || gitea.is_some(), &packages),
+        default_workflow: None,
         jira: None,
         github,
         gitea,
+        git: None,
+        change_directory: None,
         packages,
     })
 }
@@ -300,39 +432,44 @@ fn generate_workflows(has_forge: bool, packages: &[Package]) -> Vec<Workflow> {
 
     let mut release_steps = if has_forge {
         vec![
-            Step::Command {
+            Step::Command(command::RunCommand {
                 command: format!("git commit -m \"{commit_message}\"",),
                 variables,
                 shell: None,
-            },
-            Step::Command {
+                use_git_identity: true,
+            }),
+            Step::Command(command::RunCommand {
                 command: String::from("git push"),
                 variables: None,
                 shell: None,
-            },
-            Step::Release,
+                use_git_identity: false,
+            }),
+            Step::Release(Release::default()),
         ]
     } else {
         vec![
-            Step::Command {
+            Step::Command(command::RunCommand {
                 command: format!("git commit -m \"{commit_message}\""),
                 variables,
                 shell: None,
-            },
-            Step::Release,
-            Step::Command {
+                use_git_identity: true,
+            }),
+            Step::Release(Release::default()),
+            Step::Command(command::RunCommand {
                 command: String::from("git push"),
                 variables: None,
                 shell: None,
-            },
-            Step::Command {
+                use_git_identity: false,
+            }),
+            Step::Command(command::RunCommand {
                 command: String::from("git push --tags"),
                 variables: None,
                 shell: None,
-            },
+                use_git_identity: false,
+            }),
         ]
     };
-    release_steps.insert(0, Step::PrepareRelease(PrepareRelease::default()));
+    release_steps.insert(0, Step::PrepareRelease(Box::default()));
 
     let mut workflows = vec![
         Workflow {
@@ -343,7 +480,7 @@ fn generate_workflows(has_forge: bool, packages: &[Package]) -> Vec<Workflow> {
         Workflow {
             name: String::from("document-change"),
             help_text: None,
-            steps: vec![Step::CreateChangeFile],
+            steps: vec![Step::CreateChangeFile { args: None }],
         },
     ];
 
@@ -351,11 +488,12 @@ fn generate_workflows(has_forge: bool, packages: &[Package]) -> Vec<Workflow> {
         let mut get_version_variables = IndexMap::new();
         get_version_variables.insert(String::from("$version"), Variable::Version);
 
-        let get_version_steps = vec![Step::Command {
+        let get_version_steps = vec![Step::Command(command::RunCommand {
             command: String::from("echo \"$version\""),
             variables: Some(get_version_variables),
             shell: None,
-        }];
+            use_git_identity: false,
+        })];
 
         workflows.push(Workflow {
             name: String::from("get-version"),
@@ -1,11 +1,13 @@
 use std::{fmt, fmt::Display, ops::Range, path::PathBuf, str::FromStr};
 
-use ::toml::{from_str, Value};
+use ::toml::{from_str, Spanned, Value};
 use git_conventional::FooterToken;
+use indexmap::IndexMap;
 use itertools::Itertools;
-use knope_versioning::{cargo, VersionedFilePath};
+use knope_versioning::{cargo, Label, PrereleaseSeparator, VersionedFilePath};
 use miette::Diagnostic;
 use relative_path::{RelativePath, RelativePathBuf};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -26,15 +28,45 @@ pub struct Package {
     pub(crate) name: Option<PackageName>,
     /// The files which define the current version of the package.
     pub(crate) versioned_files: Vec<VersionedFilePath>,
+    /// The one of `versioned_files` that the others are synced to by [`Step::SyncVersions`].
+    pub(crate) source_of_truth: Option<VersionedFilePath>,
     /// The path to the `CHANGELOG.md` file (if any) to be updated when running [`Step::PrepareRelease`].
     pub(crate) changelog: Option<RelativePathBuf>,
+    /// Written at the top of `changelog` when Knope creates it.
+    pub(crate) changelog_header: Option<String>,
+    /// The line in `changelog` which new releases are added right after, if it's there.
+    pub(crate) changelog_anchor: Option<String>,
     /// Optional scopes that can be used to filter commits when running [`Step::PrepareRelease`].
     pub(crate) scopes: Option<Vec<String>>,
     /// Extra sections that should be added to the changelog from custom footers in commit messages
     /// or change set types.
     pub(crate) extra_changelog_sections: Vec<ChangelogSection>,
+    /// How to order the entries within each section of the changelog.
+    pub(crate) changelog_sort: Option<changelog::Sort>,
+    /// The column to wrap new changelog entries at, if any.
+    pub(crate) changelog_wrap: Option<usize>,
+    /// Conventional commit types (beyond `feat` and `fix`) which appear in the changelog or bump the version.
+    pub(crate) commit_types: Vec<CommitType>,
+    /// Other names for conventional commit types, like `bug` for `fix`.
+    pub(crate) commit_type_aliases: IndexMap<String, String>,
+    /// How commits which update dependencies (like those from Dependabot) are recognized.
+    pub(crate) dependency_updates: Option<DependencyUpdates>,
     pub(crate) assets: Option<Vec<Asset>>,
     pub(crate) ignore_go_major_versioning: bool,
+    /// Whether to bump `0.x` versions like any other, instead of treating them as initial development.
+    pub(crate) strict_semver: bool,
+    /// Whether to update this package's entry in the root `Cargo.toml`'s `[workspace.dependencies]`.
+    pub(crate) update_workspace_dependencies: bool,
+    /// The label to use for this package's pre-releases, overriding the one set on [`Step::PrepareRelease`].
+    pub(crate) prerelease_label: Option<Label>,
+    /// What goes between the label and number of pre-release versions.
+    pub(crate) prerelease_separator: Option<PrereleaseSeparator>,
+    /// The number of the first pre-release version for each label.
+    pub(crate) prerelease_start: Option<u64>,
+    /// The changelog entry to use when a release is forced (with `--override-version`) without any changes.
+    pub(crate) no_changes_entry: Option<String>,
+    /// An environment variable which, when set, is the version of the package.
+    pub(crate) version_env_var: Option<String>,
 }
 
 impl Package {
@@ -115,48 +147,140 @@ impl Package {
     ) -> std::result::Result<Self, VersionedFileError> {
         let toml::Package {
             versioned_files,
+            source_of_truth,
             changelog,
+            changelog_header,
+            changelog_anchor,
             scopes,
             extra_changelog_sections,
+            changelog_sort,
+            changelog_wrap,
+            commit_types,
+            commit_type_aliases,
+            dependency_updates,
             assets,
             ignore_go_major_versioning,
+            strict_semver,
+            update_workspace_dependencies,
+            prerelease_label,
+            prerelease_separator,
+            prerelease_start,
+            no_changes_entry,
+            version_env_var,
         } = package;
         let versioned_files = versioned_files
             .into_iter()
+            .map(|spanned| versioned_file_path(spanned, source_code))
+            .try_collect::<_, Vec<_>, _>()?;
+        let source_of_truth = source_of_truth
             .map(|spanned| {
                 let span = spanned.span();
-                VersionedFilePath::new(spanned.into_inner())
-                    .map_err(|source| VersionedFileError::Unknown {
-                        file_name: source.path.file_name().unwrap_or_default().to_string(),
-                        span: span.clone(),
+                let path = spanned.into_inner();
+                versioned_files
+                    .iter()
+                    .find(|versioned_file| versioned_file.as_path() == path)
+                    .cloned()
+                    .ok_or_else(|| VersionedFileError::UnknownSourceOfTruth {
+                        path,
+                        span,
                         source_code: source_code.to_string(),
                     })
-                    .and_then(|path| {
-                        let pathbuf = path.to_pathbuf();
-                        if pathbuf.exists() {
-                            Ok(path)
-                        } else {
-                            Err(VersionedFileError::Missing {
-                                path: pathbuf,
-                                span,
-                                source_code: source_code.to_string(),
-                            })
-                        }
-                    })
             })
-            .try_collect()?;
+            .transpose()?;
         Ok(Self {
             name,
             versioned_files,
+            source_of_truth,
             changelog,
+            changelog_header,
+            changelog_anchor,
             scopes,
             extra_changelog_sections,
+            changelog_sort,
+            changelog_wrap,
+            commit_types,
+            commit_type_aliases,
+            dependency_updates,
             assets,
             ignore_go_major_versioning,
+            strict_semver,
+            update_workspace_dependencies,
+            prerelease_label,
+            prerelease_separator,
+            prerelease_start,
+            no_changes_entry,
+            version_env_var,
         })
     }
 }
 
+/// Validate an entry of `versioned_files`, including that the file exists.
+fn versioned_file_path(
+    spanned: Spanned<toml::VersionedFile>,
+    source_code: &str,
+) -> std::result::Result<VersionedFilePath, VersionedFileError> {
+    let span = spanned.span();
+    let (path, variable, key, pattern, strip_prerelease) = match spanned.into_inner() {
+        toml::VersionedFile::Path(path) => (path, None, None, None, false),
+        toml::VersionedFile::WithOptions {
+            path,
+            variable,
+            key,
+            pattern,
+            strip_prerelease,
+        } => (path, variable, key, pattern, strip_prerelease),
+    };
+    let unknown = |source: knope_versioning::UnknownFile| VersionedFileError::Unknown {
+        file_name: source.path.file_name().unwrap_or_default().to_string(),
+        span: span.clone(),
+        source_code: source_code.to_string(),
+    };
+    let path = match (key, pattern) {
+        (Some(_), Some(_)) => Err(VersionedFileError::KeyAndPattern {
+            span: span.clone(),
+            source_code: source_code.to_string(),
+        }),
+        (None, Some(pattern)) => VersionedFilePath::with_pattern(path, pattern).map_err(unknown),
+        (Some(key), None) => VersionedFilePath::with_key(path, key).map_err(|source| {
+            VersionedFileError::KeyNotSupported {
+                path: source.path,
+                span: span.clone(),
+                source_code: source_code.to_string(),
+            }
+        }),
+        (None, None) => VersionedFilePath::new(path).map_err(unknown),
+    };
+    path.and_then(|path| match variable {
+        Some(variable) => path.with_variable(variable).map_err(|source| {
+            VersionedFileError::VariableNotSupported {
+                path: source.path,
+                span: span.clone(),
+                source_code: source_code.to_string(),
+            }
+        }),
+        None => Ok(path),
+    })
+    .map(|path| {
+        if strip_prerelease {
+            path.with_strip_prerelease()
+        } else {
+            path
+        }
+    })
+    .and_then(|path| {
+        let pathbuf = path.to_pathbuf();
+        if pathbuf.exists() {
+            Ok(path)
+        } else {
+            Err(VersionedFileError::Missing {
+                path: pathbuf,
+                span,
+                source_code: source_code.to_string(),
+            })
+        }
+    })
+}
+
 #[derive(Debug, Diagnostic, Error)]
 pub enum VersionedFileError {
     #[error("Unknown file name {file_name}")]
@@ -184,6 +308,57 @@ pub enum VersionedFileError {
         #[label("Declared here")]
         span: Range<usize>,
     },
+    #[error("{path} does not support setting a variable")]
+    #[diagnostic(
+        code(config::variable_not_supported),
+        help("Only a `Makefile` or a Go file (ending in `.go`) can have a `variable`."),
+        url("https://knope.tech/reference/config-file/packages#makefile")
+    )]
+    VariableNotSupported {
+        path: RelativePathBuf,
+        #[source_code]
+        source_code: String,
+        #[label("Declared here")]
+        span: Range<usize>,
+    },
+    #[error("{path} does not support setting a key")]
+    #[diagnostic(
+        code(config::key_not_supported),
+        help("Only YAML files (ending in `.yml` or `.yaml`) can have a `key`."),
+        url("https://knope.tech/reference/config-file/packages#yaml")
+    )]
+    KeyNotSupported {
+        path: RelativePathBuf,
+        #[source_code]
+        source_code: String,
+        #[label("Declared here")]
+        span: Range<usize>,
+    },
+    #[error("A versioned file can't have both a `key` and a `pattern`")]
+    #[diagnostic(
+        code(config::key_and_pattern),
+        help("Use a `key` for YAML files, or a `pattern` for any other file."),
+        url("https://knope.tech/reference/config-file/packages#pattern")
+    )]
+    KeyAndPattern {
+        #[source_code]
+        source_code: String,
+        #[label("Declared here")]
+        span: Range<usize>,
+    },
+    #[error("The source of truth {path} is not one of the versioned files")]
+    #[diagnostic(
+        code(config::unknown_source_of_truth),
+        help("The `source_of_truth` must also be listed in `versioned_files`."),
+        url("https://knope.tech/reference/config-file/packages#source_of_truth")
+    )]
+    UnknownSourceOfTruth {
+        path: RelativePathBuf,
+        #[source_code]
+        source_code: String,
+        #[label("Declared here")]
+        span: Range<usize>,
+    },
 }
 
 #[derive(Debug, Diagnostic, thiserror::Error)]
@@ -217,7 +392,7 @@ pub(crate) enum Error {
 
 type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
 pub(crate) struct ChangelogSection {
     pub(crate) name: ChangeLogSectionName,
     #[serde(default)]
@@ -226,7 +401,104 @@ pub(crate) struct ChangelogSection {
     pub(crate) types: Vec<CustomChangeType>,
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+/// How commits of a conventional commit type (other than `feat` and `fix`) affect releases.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+pub(crate) struct CommitType {
+    /// The type of commit, like `docs` in `docs: Update README`
+    #[serde(rename = "type")]
+    pub(crate) name: CustomChangeType,
+    /// The changelog section to list these commits in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) section: Option<ChangeLogSectionName>,
+    /// How much these commits bump the version
+    #[serde(default)]
+    pub(crate) bump: Bump,
+}
+
+impl CommitType {
+    /// Whether `commit_type` (from a commit message) is this type, ignoring case like all other
+    /// conventional commit types.
+    pub(crate) fn matches(&self, commit_type: &str) -> bool {
+        self.name.0.eq_ignore_ascii_case(commit_type)
+    }
+}
+
+/// How commits which update dependencies are recognized, so they can be released on their own
+/// terms instead of as whatever conventional commit type they use.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+pub(crate) struct DependencyUpdates {
+    /// The changelog section to list dependency updates in
+    #[serde(default = "DependencyUpdates::default_section")]
+    pub(crate) section: ChangeLogSectionName,
+    /// How much dependency updates bump the version
+    #[serde(default)]
+    pub(crate) bump: Bump,
+    /// Conventional commit scopes which mark a commit as a dependency update, like `deps` in
+    /// `chore(deps): bump serde from 1.0.0 to 1.0.1`
+    #[serde(default = "DependencyUpdates::default_scopes")]
+    pub(crate) scopes: Vec<String>,
+    /// Commit authors (like bots) whose commits are always dependency updates, even if they
+    /// aren't conventional commits
+    #[serde(default = "DependencyUpdates::default_authors")]
+    pub(crate) authors: Vec<String>,
+}
+
+impl DependencyUpdates {
+    fn default_section() -> ChangeLogSectionName {
+        ChangeLogSectionName::from("Dependencies")
+    }
+
+    fn default_scopes() -> Vec<String> {
+        vec![String::from("deps"), String::from("deps-dev")]
+    }
+
+    fn default_authors() -> Vec<String> {
+        vec![
+            String::from("dependabot[bot]"),
+            String::from("renovate[bot]"),
+        ]
+    }
+
+    /// Whether a conventional commit with this `scope` (which may list several) is a dependency update.
+    pub(crate) fn matches_scope(&self, scope: &str) -> bool {
+        scope
+            .split(',')
+            .map(str::trim)
+            .any(|scope| self.scopes.iter().any(|expected| expected == scope))
+    }
+
+    /// Whether every commit by `author` is a dependency update, ignoring case.
+    pub(crate) fn matches_author(&self, author: &str) -> bool {
+        self.authors
+            .iter()
+            .any(|expected| expected.eq_ignore_ascii_case(author.trim()))
+    }
+}
+
+impl Default for DependencyUpdates {
+    fn default() -> Self {
+        Self {
+            section: Self::default_section(),
+            bump: Bump::default(),
+            scopes: Self::default_scopes(),
+            authors: Self::default_authors(),
+        }
+    }
+}
+
+/// How much a change bumps the version of a package.
+#[derive(Clone, Copy, Debug, Default, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Bump {
+    /// Include the change in the changelog, but don't release because of it.
+    None,
+    #[default]
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, Hash, PartialEq, Serialize)]
 #[serde(transparent)]
 pub(crate) struct CommitFooter(String);
 
@@ -242,6 +514,12 @@ impl From<FooterToken<'_>> for CommitFooter {
     }
 }
 
+impl AsRef<str> for CommitFooter {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
 impl From<&str> for CommitFooter {
     fn from(token: &str) -> Self {
         Self(token.into())
@@ -254,7 +532,7 @@ impl From<CommitFooter> for ChangeType {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, Hash, PartialEq, Serialize)]
 #[serde(transparent)]
 pub(crate) struct CustomChangeType(String);
 
@@ -301,7 +579,7 @@ impl From<changesets::ChangeType> for ChangeType {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, Hash, PartialEq, Serialize)]
 #[serde(transparent)]
 pub(crate) struct ChangeLogSectionName(String);
 
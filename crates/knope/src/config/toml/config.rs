@@ -1,5 +1,9 @@
+use std::path::PathBuf;
+
 use indexmap::IndexMap;
 use itertools::Itertools;
+use relative_path::RelativePath;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use toml::Spanned;
 
@@ -8,18 +12,34 @@ use crate::{step::releases::package::PackageName, workflow::Workflow};
 
 /// Loads a `crate::Config` from a TOML file with as much span information as possible for better
 /// error messages.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(title = "knope.toml", description = "The config file for Knope")]
 pub(crate) struct ConfigLoader {
+    #[schemars(with = "Option<Package>")]
     pub(crate) package: Option<Spanned<Package>>,
+    #[schemars(with = "Option<IndexMap<PackageName, Package>>")]
     pub(crate) packages: Option<IndexMap<PackageName, Spanned<Package>>>,
     /// The list of defined workflows that are selectable
+    #[schemars(with = "Option<Vec<Workflow>>")]
     pub(crate) workflows: Option<Spanned<Vec<Spanned<Workflow>>>>,
+    /// The workflow to run when none is selected
+    #[schemars(with = "Option<String>")]
+    pub(crate) default_workflow: Option<Spanned<String>>,
     /// Optional configuration for Jira
+    #[schemars(with = "Option<Jira>")]
     pub(crate) jira: Option<Spanned<Jira>>,
     /// Optional configuration to talk to GitHub
+    #[schemars(with = "Option<GitHub>")]
     pub(crate) github: Option<Spanned<GitHub>>,
     /// Optional configuration to talk to a Gitea instance
+    #[schemars(with = "Option<Gitea>")]
     pub(crate) gitea: Option<Spanned<Gitea>>,
+    /// Optional identity to use for Git commits and tags
+    #[schemars(with = "Option<Git>")]
+    pub(crate) git: Option<Spanned<Git>>,
+    /// The directory to create and read change files in, `.changeset` by default
+    #[schemars(with = "Option<String>")]
+    pub(crate) change_directory: Option<Spanned<PathBuf>>,
 }
 
 #[cfg(test)]
@@ -62,7 +82,7 @@ mod test_package_configs {
 }
 
 /// Config required for steps that interact with Jira.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
 pub(crate) struct Jira {
     /// The URL to your Atlassian instance running Jira
     pub(crate) url: String,
@@ -70,8 +90,131 @@ pub(crate) struct Jira {
     pub(crate) project: String,
 }
 
+impl Jira {
+    /// Get the URL to view the issue with `key` in the browser
+    pub(crate) fn get_issue_url(&self, key: &str) -> String {
+        format!("{url}/browse/{key}", url = self.url)
+    }
+}
+
+/// The identity Knope uses for the Git commits and tags it creates, and where it records releases.
+///
+/// Anything not set here falls back to Git's own config.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub(crate) struct Git {
+    /// Used instead of the `user.name` Git config option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) user_name: Option<String>,
+    /// Used instead of the `user.email` Git config option
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) user_email: Option<String>,
+    /// If set, releases are recorded in Git notes under this ref (like `knope` for
+    /// `refs/notes/knope`), which also count as tags when looking for versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) notes_ref: Option<String>,
+}
+
+impl Git {
+    const USER_NAME_VAR: &'static str = "KNOPE_GIT_USER_NAME";
+    const USER_EMAIL_VAR: &'static str = "KNOPE_GIT_USER_EMAIL";
+
+    /// Combine the config from `knope.toml` (if any) with environment variables, which take
+    /// precedence.
+    pub(crate) fn with_env(config: Option<Self>) -> Self {
+        Self::merge(
+            config.unwrap_or_default(),
+            std::env::var(Self::USER_NAME_VAR).ok(),
+            std::env::var(Self::USER_EMAIL_VAR).ok(),
+        )
+    }
+
+    fn merge(config: Self, user_name: Option<String>, user_email: Option<String>) -> Self {
+        Self {
+            user_name: user_name
+                .filter(|name| !name.is_empty())
+                .or(config.user_name),
+            user_email: user_email
+                .filter(|email| !email.is_empty())
+                .or(config.user_email),
+            notes_ref: config.notes_ref,
+        }
+    }
+
+    /// The environment variables which make the `git` commands Knope runs for its own commits use
+    /// this identity for both the author and the committer.
+    pub(crate) fn commit_env_vars(&self) -> Vec<(&'static str, &str)> {
+        let names = self.user_name.iter().flat_map(|name| {
+            [
+                ("GIT_AUTHOR_NAME", name.as_str()),
+                ("GIT_COMMITTER_NAME", name.as_str()),
+            ]
+        });
+        let emails = self.user_email.iter().flat_map(|email| {
+            [
+                ("GIT_AUTHOR_EMAIL", email.as_str()),
+                ("GIT_COMMITTER_EMAIL", email.as_str()),
+            ]
+        });
+        names.chain(emails).collect()
+    }
+}
+
+#[cfg(test)]
+mod test_git {
+    use pretty_assertions::assert_eq;
+
+    use super::Git;
+
+    #[test]
+    fn env_takes_precedence() {
+        let config = Git {
+            user_name: Some("From Config".to_string()),
+            user_email: Some("config@example.com".to_string()),
+            notes_ref: None,
+        };
+        let merged = Git::merge(config, Some("From Env".to_string()), None);
+        assert_eq!(
+            merged,
+            Git {
+                user_name: Some("From Env".to_string()),
+                user_email: Some("config@example.com".to_string()),
+                notes_ref: None,
+            }
+        );
+    }
+
+    #[test]
+    fn empty_env_is_ignored() {
+        let config = Git {
+            user_name: Some("From Config".to_string()),
+            user_email: None,
+            notes_ref: None,
+        };
+        let merged = Git::merge(config, Some(String::new()), None);
+        assert_eq!(merged.user_name.as_deref(), Some("From Config"));
+    }
+
+    #[test]
+    fn commit_env_vars() {
+        let git = Git {
+            user_name: Some("Knope".to_string()),
+            user_email: None,
+            notes_ref: None,
+        };
+        assert_eq!(
+            git.commit_env_vars(),
+            vec![
+                ("GIT_AUTHOR_NAME", "Knope"),
+                ("GIT_COMMITTER_NAME", "Knope")
+            ]
+        );
+        assert!(Git::default().commit_env_vars().is_empty());
+    }
+}
+
 /// Details needed to use steps that interact with GitHub.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
 pub(crate) struct GitHub {
     /// The user or organization that owns the `repo`.
     pub(crate) owner: String,
@@ -79,8 +222,28 @@ pub(crate) struct GitHub {
     pub(crate) repo: String,
 }
 
+impl GitHub {
+    /// Get the URL to view `path` as of `tag` in the browser
+    pub(crate) fn get_file_url(&self, tag: &str, path: &RelativePath) -> String {
+        format!(
+            "https://github.com/{owner}/{repo}/blob/{tag}/{path}",
+            owner = self.owner,
+            repo = self.repo
+        )
+    }
+
+    /// Get the URL to view the issue with `number` in the browser
+    pub(crate) fn get_issue_url(&self, number: &str) -> String {
+        format!(
+            "https://github.com/{owner}/{repo}/issues/{number}",
+            owner = self.owner,
+            repo = self.repo
+        )
+    }
+}
+
 /// Details needed to use steps that interact with a Gitea instance.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
 pub(crate) struct Gitea {
     /// The user or organization that owns the `repo`.
@@ -124,6 +287,26 @@ impl Gitea {
         )
     }
 
+    /// Get the URL to view `path` as of `tag` in the browser
+    pub(crate) fn get_file_url(&self, tag: &str, path: &RelativePath) -> String {
+        format!(
+            "{host}/{owner}/{repo}/src/tag/{tag}/{path}",
+            host = self.host,
+            owner = self.owner,
+            repo = self.repo
+        )
+    }
+
+    /// Get the URL to view the issue with `number` in the browser
+    pub(crate) fn get_issue_web_url(&self, number: &str) -> String {
+        format!(
+            "{host}/{owner}/{repo}/issues/{number}",
+            host = self.host,
+            owner = self.owner,
+            repo = self.repo
+        )
+    }
+
     /// Get the URL to list repo issues
     pub(crate) fn get_issues_url(&self) -> String {
         format!(
@@ -2,5 +2,5 @@ mod config;
 pub(crate) mod package;
 
 pub(super) use config::ConfigLoader;
-pub(crate) use config::{GitHub, Gitea, Jira};
-pub(crate) use package::Package;
+pub(crate) use config::{Git, GitHub, Gitea, Jira};
+pub(crate) use package::{Package, VersionedFile};
@@ -1,32 +1,112 @@
 use std::ops::Not;
 
+use indexmap::IndexMap;
+use knope_versioning::{Label, PrereleaseSeparator};
 use miette::Diagnostic;
 use relative_path::RelativePathBuf;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use toml::Spanned;
 
 use crate::{
-    config::ChangelogSection,
+    config::{ChangelogSection, CommitType, DependencyUpdates},
     step::releases::{changelog, package::Asset},
 };
 
 /// Represents a single package in `knope.toml`.
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Eq, Serialize)]
 pub struct Package {
     /// The files which define the current version of the package.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub(crate) versioned_files: Vec<Spanned<RelativePathBuf>>,
+    #[schemars(with = "Vec<VersionedFile>")]
+    pub(crate) versioned_files: Vec<Spanned<VersionedFile>>,
+    /// The one of `versioned_files` that the others are synced to by [`Step::SyncVersions`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub(crate) source_of_truth: Option<Spanned<RelativePathBuf>>,
     /// The path to the `CHANGELOG.md` file (if any) to be updated when running [`Step::PrepareRelease`].
+    #[schemars(with = "Option<String>")]
     pub(crate) changelog: Option<RelativePathBuf>,
+    /// Written at the top of `changelog` when Knope creates it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) changelog_header: Option<String>,
+    /// A line in `changelog` (like `<!-- knope-insert -->`) which new releases are added right
+    /// after, instead of before the latest release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) changelog_anchor: Option<String>,
     /// Optional scopes that can be used to filter commits when running [`Step::PrepareRelease`].
     pub(crate) scopes: Option<Vec<String>>,
     /// Extra sections that should be added to the changelog from custom footers in commit messages.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub(crate) extra_changelog_sections: Vec<ChangelogSection>,
+    /// How to order the entries within each section of the changelog.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) changelog_sort: Option<changelog::Sort>,
+    /// The column to wrap new changelog entries at. Entries aren't wrapped by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) changelog_wrap: Option<usize>,
+    /// Conventional commit types (beyond `feat` and `fix`) which appear in the changelog or bump the version.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) commit_types: Vec<CommitType>,
+    /// Other names for conventional commit types, like `bug` for `fix`.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub(crate) commit_type_aliases: IndexMap<String, String>,
+    /// How commits which update dependencies (like those from Dependabot) are recognized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) dependency_updates: Option<DependencyUpdates>,
     pub(crate) assets: Option<Vec<Asset>>,
     #[serde(default, skip_serializing_if = "<&bool>::not")]
     pub(crate) ignore_go_major_versioning: bool,
+    /// Whether to bump `0.x` versions like any other, instead of treating them as initial development.
+    #[serde(default, skip_serializing_if = "<&bool>::not")]
+    pub(crate) strict_semver: bool,
+    /// Whether to update this package's entry in the root `Cargo.toml`'s `[workspace.dependencies]`.
+    #[serde(default, skip_serializing_if = "<&bool>::not")]
+    pub(crate) update_workspace_dependencies: bool,
+    /// The label to use for this package's pre-releases, overriding the one set on [`Step::PrepareRelease`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub(crate) prerelease_label: Option<Label>,
+    /// What goes between the label and number of pre-release versions, `"."` (the default) or `""`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub(crate) prerelease_separator: Option<PrereleaseSeparator>,
+    /// The number of the first pre-release version for each label, 0 by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) prerelease_start: Option<u64>,
+    /// The changelog entry to use when a release is forced (with `--override-version`) without any changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) no_changes_entry: Option<String>,
+    /// An environment variable which, when set, is the version of the package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) version_env_var: Option<String>,
+}
+
+/// An entry in `versioned_files`: either just the path, or a table with extra options.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub(crate) enum VersionedFile {
+    #[schemars(with = "String")]
+    Path(RelativePathBuf),
+    WithOptions {
+        #[schemars(with = "String")]
+        path: RelativePathBuf,
+        /// For a `Makefile`, the variable which stores the version, instead of `VERSION`. For a Go
+        /// file, the string constant which stores the version, instead of `Version`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        variable: Option<String>,
+        /// For any YAML file, the dotted key which stores the version, like `inputs.version.default`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+        /// For any file, a regex whose first capture group (or the one named `version`) is the
+        /// version, like `version-(.*)-blue`. Can also be written as `search`.
+        #[serde(default, alias = "search", skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+        /// Leave the pre-release component out of the version written to this file.
+        #[serde(default, skip_serializing_if = "<&bool>::not")]
+        strip_prerelease: bool,
+    },
 }
 
 impl From<crate::config::Package> for Package {
@@ -35,13 +115,48 @@ impl From<crate::config::Package> for Package {
             versioned_files: package
                 .versioned_files
                 .iter()
-                .map(|it| Spanned::new(0..0, it.as_path()))
+                .map(|it| {
+                    let path = it.as_path();
+                    let versioned_file = if it.variable().is_some()
+                        || it.key().is_some()
+                        || it.pattern().is_some()
+                        || it.strip_prerelease()
+                    {
+                        VersionedFile::WithOptions {
+                            path,
+                            variable: it.variable().map(ToString::to_string),
+                            key: it.key().map(ToString::to_string),
+                            pattern: it.pattern().map(ToString::to_string),
+                            strip_prerelease: it.strip_prerelease(),
+                        }
+                    } else {
+                        VersionedFile::Path(path)
+                    };
+                    Spanned::new(0..0, versioned_file)
+                })
                 .collect(),
+            source_of_truth: package
+                .source_of_truth
+                .map(|path| Spanned::new(0..0, path.as_path())),
             changelog: package.changelog,
+            changelog_header: package.changelog_header,
+            changelog_anchor: package.changelog_anchor,
             scopes: package.scopes,
             extra_changelog_sections: package.extra_changelog_sections,
+            changelog_sort: package.changelog_sort,
+            changelog_wrap: package.changelog_wrap,
+            commit_types: package.commit_types,
+            commit_type_aliases: package.commit_type_aliases,
+            dependency_updates: package.dependency_updates,
             assets: package.assets,
             ignore_go_major_versioning: package.ignore_go_major_versioning,
+            strict_semver: package.strict_semver,
+            update_workspace_dependencies: package.update_workspace_dependencies,
+            prerelease_label: package.prerelease_label,
+            prerelease_separator: package.prerelease_separator,
+            prerelease_start: package.prerelease_start,
+            no_changes_entry: package.no_changes_entry,
+            version_env_var: package.version_env_var,
         }
     }
 }
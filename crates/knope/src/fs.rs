@@ -3,6 +3,7 @@
 use std::{
     fmt::Display,
     io,
+    io::Write,
     path::{Path, PathBuf},
 };
 
@@ -35,6 +36,25 @@ pub(crate) fn write<C: AsRef<[u8]> + Display>(
     }
 }
 
+/// Appends `contents` to a file (creating it if needed) if this is not a dry run, or prints them
+/// to stdout if it is.
+pub(crate) fn append(dry_run: DryRun, path: &Path, contents: &str) -> Result<(), Error> {
+    if let Some(stdout) = dry_run {
+        writeln!(stdout, "Would append to {}:\n{contents}", path.display()).map_err(Error::Stdout)
+    } else {
+        trace!("Appending {} to {}", contents, path.display());
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .map_err(|source| Error::Write {
+                path: path.into(),
+                source,
+            })
+    }
+}
+
 pub(crate) fn create_dir(dry_run: DryRun, path: &Path) -> Result<(), Error> {
     if let Some(stdout) = dry_run {
         writeln!(stdout, "Would create directory {}", path.display()).map_err(Error::Stdout)
@@ -1,18 +1,25 @@
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{HashSet, VecDeque},
     env::current_dir,
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
-use git2::{build::CheckoutBuilder, Branch, BranchType, IndexAddOption, Repository};
-use gix::{object::Kind, refs::transaction::PreviousValue, ObjectId};
+use git2::{build::CheckoutBuilder, Branch, BranchType, IndexAddOption, ObjectType, Repository};
+use gix::{
+    actor::SignatureRef, bstr::BStr, object::Kind, refs::transaction::PreviousValue,
+    traverse::commit::simple::Sorting, ObjectId,
+};
+use indexmap::IndexMap;
 use itertools::Itertools;
 use knope_versioning::Version;
 use log::error;
 use miette::Diagnostic;
+use serde::{Deserialize, Serialize};
+use time::Date;
 
 use crate::{
+    config,
     dry_run::DryRun,
     fs, prompt,
     prompt::select,
@@ -24,13 +31,16 @@ use crate::{
 
 /// Based on the selected issue, either checks out an existing branch matching the name or creates
 /// a new one, prompting for which branch to base it on.
-pub(crate) fn switch_branches(run_type: RunType) -> Result<RunType, Error> {
+pub(crate) fn switch_branches(
+    run_type: RunType,
+    branch_prefixes: &IndexMap<String, String>,
+) -> Result<RunType, Error> {
     let (state, dry_run_stdout) = run_type.decompose();
     let issue = match &state.issue {
         state::Issue::Initial => return Err(ErrorKind::NoIssueSelected.into()),
         state::Issue::Selected(issue) => issue,
     };
-    let new_branch_name = branch_name_from_issue(issue);
+    let new_branch_name = branch_name_from_issue(issue, branch_prefixes);
     if let Some(mut stdout) = dry_run_stdout {
         writeln!(
             stdout,
@@ -142,7 +152,7 @@ enum ErrorKind {
         code(git::no_committer),
         help(
             "We couldn't determine who to commit the changes as. Please set the `user.name` and \
-                `user.email` Git config options."
+                `user.email` Git config options, or the `[git]` section of `knope.toml`."
         )
     )]
     NoCommitter,
@@ -162,6 +172,24 @@ enum ErrorKind {
         #[source]
         source: gix::reference::find::existing::Error,
     },
+    #[error("Could not find the Git revision {revision}")]
+    #[diagnostic(
+        code(git::invalid_revision),
+        help("The revision must be something that Git can resolve to a commit, like a tag, a branch, or a commit hash."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#options")
+    )]
+    InvalidRevision {
+        revision: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Could not look up tag {tag}: {source}")]
+    #[diagnostic(code(git::find_tag))]
+    FindTag {
+        tag: String,
+        #[source]
+        source: gix::reference::find::Error,
+    },
     #[error("Could not peel oid: {0}")]
     #[diagnostic(
         code(releases::git::peel_oid),
@@ -170,6 +198,166 @@ enum ErrorKind {
     PeelOid(#[from] gix::reference::peel::Error),
     #[error("Could not walk commits back from HEAD: {0}")]
     RevisionWalk(#[from] gix::revision::walk::Error),
+    #[error("Could not fetch tags from {remote}")]
+    #[diagnostic(
+        code(git::fetch_tags),
+        help("Make sure the remote exists and that Git can access it, for example by running `git fetch --tags {remote}`."),
+        url("https://knope.tech/reference/config-file/steps/fetch-tags/")
+    )]
+    FetchTags {
+        remote: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Could not authenticate to {remote}")]
+    #[diagnostic(
+        code(git::remote_auth),
+        help(
+            "Knope uses Git's own credentials, so set up a credential helper (like `gh auth setup-git`) \
+            for HTTPS remotes or add your key to `ssh-agent` for SSH remotes, then check that \
+            `git fetch {remote}` works."
+        ),
+        url("https://knope.tech/reference/config-file/steps/fetch-tags/#authentication")
+    )]
+    RemoteAuth {
+        remote: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Could not commit the release changes")]
+    #[diagnostic(
+        code(git::commit),
+        help(
+            "Make sure Git can commit in this repository, for example that any commit hooks pass."
+        ),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#separate-commits")
+    )]
+    Commit(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Could not commit the synced files in {path}")]
+    #[diagnostic(
+        code(git::commit_downstream),
+        help("Make sure {path} is a Git repository which Git can commit in, for example that any commit hooks pass."),
+        url("https://knope.tech/reference/config-file/steps/sync-downstream/")
+    )]
+    CommitDownstream {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Could not add a Git note to {notes_ref}")]
+    #[diagnostic(
+        code(git::add_note),
+        help("Make sure Git can write notes in this repository, for example by running `git notes --ref {notes_ref} add HEAD`."),
+        url("https://knope.tech/reference/config-file/git/#notes_ref")
+    )]
+    AddNote {
+        notes_ref: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Could not revert the release commit")]
+    #[diagnostic(
+        code(git::revert),
+        help(
+            "Make sure the working tree is clean and that Git can commit in this repository, then try `git revert HEAD` yourself."
+        ),
+        url("https://knope.tech/reference/config-file/steps/rollback/")
+    )]
+    Revert(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("The commit {commit} of release tag {tag} isn't in the local repository")]
+    #[diagnostic(
+        code(git::missing_release_commit),
+        help("Fetch more history (like with `git fetch --unshallow`) so Knope can find the commits since that release."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#previous-version-source")
+    )]
+    MissingReleaseCommit { tag: String, commit: String },
+}
+
+/// The remote that [`fetch_tags`] uses when none is configured.
+const DEFAULT_REMOTE: &str = "origin";
+
+/// Fetch every tag from `remote` (or `origin`), then reload the tags used to find versions.
+///
+/// In a shallow clone, the rest of the history is fetched too, since tags which aren't ancestors
+/// of `HEAD` are ignored.
+pub(crate) fn fetch_tags(run_type: RunType, remote: Option<&str>) -> Result<RunType, Error> {
+    let (mut state, mut dry_run) = run_type.decompose();
+    let remote = remote.unwrap_or(DEFAULT_REMOTE);
+    let repo = gix::open(current_dir().map_err(ErrorKind::CurrentDirectory)?)?;
+    let mut args = vec!["fetch", "--tags"];
+    if repo.is_shallow() {
+        args.push("--unshallow");
+    }
+    args.push(remote);
+
+    if let Some(stdout) = dry_run.as_mut() {
+        writeln!(stdout, "Would run git {}", args.join(" ")).map_err(fs::Error::Stdout)?;
+        return Ok(RunType::recompose(state, dry_run));
+    }
+
+    let fetch_error = |source| ErrorKind::FetchTags {
+        remote: remote.to_string(),
+        source,
+    };
+    // Running `git` (instead of fetching with a library) means the user's credential helpers and
+    // `ssh-agent` work without any extra configuration.
+    let output = std::process::Command::new("git")
+        .args(&args)
+        .output()
+        .map_err(|err| fetch_error(Box::new(err)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(remote_auth_error(remote, &stderr)
+            .unwrap_or_else(|| fetch_error(stderr.trim().into()).into()));
+    }
+    state.all_git_tags = all_tags_on_branch(state.git_config.notes_ref.as_deref(), state.verbose)?;
+    Ok(RunType::recompose(state, dry_run))
+}
+
+/// The error for `git` failing with `stderr` while talking to `remote`, if it failed because it
+/// couldn't get credentials for the remote.
+pub(crate) fn remote_auth_error(remote: &str, stderr: &str) -> Option<Error> {
+    is_auth_failure(stderr).then(|| {
+        ErrorKind::RemoteAuth {
+            remote: remote.to_string(),
+            source: stderr.trim().into(),
+        }
+        .into()
+    })
+}
+
+/// Whether `git` failed (going by its `stderr`) because it couldn't get credentials for a remote.
+fn is_auth_failure(stderr: &str) -> bool {
+    const MESSAGES: [&str; 5] = [
+        "Authentication failed",
+        "could not read Username",
+        "could not read Password",
+        "terminal prompts disabled",
+        "Permission denied (publickey",
+    ];
+    MESSAGES.iter().any(|message| stderr.contains(message))
+}
+
+/// Whether a local branch named `name` exists.
+pub(crate) fn branch_exists(name: &str) -> Result<bool, Error> {
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let exists = repo.find_branch(name, BranchType::Local).is_ok();
+    Ok(exists)
+}
+
+/// Create a branch named `name` at `HEAD` and switch to it, like `git switch -c`.
+///
+/// The new branch points at the same commit, so uncommitted changes are kept.
+pub(crate) fn create_and_switch_to_branch(name: &str) -> Result<(), Error> {
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let branch = repo.branch(name, &head, false)?;
+    let ref_name = branch
+        .get()
+        .name()
+        .ok_or(Error::from(ErrorKind::BadGitBranchName))?;
+    repo.set_head(ref_name)?;
+    Ok(())
 }
 
 /// Rebase the current branch onto the selected one.
@@ -208,6 +396,8 @@ pub(crate) fn select_issue_from_current_branch(run_type: RunType) -> Result<RunT
             state.issue = state::Issue::Selected(Issue {
                 key: String::from("123"),
                 summary: String::from("Fake Issue"),
+                labels: Vec::new(),
+                url: None,
             });
             Ok(RunType::DryRun { state, stdout })
         }
@@ -220,6 +410,18 @@ pub(crate) fn select_issue_from_current_branch(run_type: RunType) -> Result<RunT
     }
 }
 
+/// The short name (like `main`) of the branch that `HEAD` is on.
+pub(crate) fn current_branch_name() -> Result<String, Error> {
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Err(ErrorKind::NotOnAGitBranch.into());
+    }
+    head.shorthand()
+        .map(ToString::to_string)
+        .ok_or_else(|| ErrorKind::NotOnAGitBranch.into())
+}
+
 pub(crate) fn current_branch() -> Result<String, Error> {
     let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
     let head = repo.head()?;
@@ -227,6 +429,13 @@ pub(crate) fn current_branch() -> Result<String, Error> {
     Ok(ref_name.to_owned())
 }
 
+/// Get the full SHA of the commit `HEAD` points to.
+pub(crate) fn head_commit_sha() -> Result<String, Error> {
+    let repo = gix::open(".")?;
+    let sha = repo.head_commit()?.id.to_string();
+    Ok(sha)
+}
+
 /// Get the first remote of the Git repo, if any.
 pub(crate) fn get_first_remote() -> Option<String> {
     let repo = Repository::open(".").ok()?;
@@ -238,7 +447,9 @@ pub(crate) fn get_first_remote() -> Option<String> {
 }
 
 fn select_issue_from_branch_name(ref_name: &str) -> Result<Issue, Error> {
-    let mut parts: VecDeque<&str> = ref_name.split('-').collect();
+    // Skip `refs/heads/` and any prefix added by `SwitchBranches`, like `fix/`
+    let branch_name = ref_name.rsplit('/').next().unwrap_or(ref_name);
+    let mut parts: VecDeque<&str> = branch_name.split('-').collect();
 
     let issue_key = parts.pop_front().ok_or(ErrorKind::BadGitBranchName)?;
     if let Ok(github_issue) = usize::from_str(issue_key) {
@@ -246,6 +457,8 @@ fn select_issue_from_branch_name(ref_name: &str) -> Result<Issue, Error> {
         return Ok(Issue {
             key: github_issue.to_string(),
             summary: parts.iter().join("-"),
+            labels: Vec::new(),
+            url: None,
         });
     }
     let project_key = issue_key;
@@ -259,6 +472,8 @@ fn select_issue_from_branch_name(ref_name: &str) -> Result<Issue, Error> {
     return Ok(Issue {
         key: jira_issue,
         summary: parts.iter().join("-"),
+        labels: Vec::new(),
+        url: None,
     });
 }
 
@@ -277,6 +492,8 @@ mod test_select_issue_from_branch_name {
             Issue {
                 key: "ABC-123".to_string(),
                 summary: "some-summary".to_string(),
+                labels: Vec::new(),
+                url: None,
             }
         );
     }
@@ -291,6 +508,24 @@ mod test_select_issue_from_branch_name {
             Issue {
                 key: "123".to_string(),
                 summary: "some-summary".to_string(),
+                labels: Vec::new(),
+                url: None,
+            }
+        );
+    }
+
+    #[test]
+    fn prefixed() {
+        let issue = select_issue_from_branch_name("refs/heads/fix/ABC-123-some-summary")
+            .expect("Failed to parse branch name");
+
+        assert_eq!(
+            issue,
+            Issue {
+                key: "ABC-123".to_string(),
+                summary: "some-summary".to_string(),
+                labels: Vec::new(),
+                url: None,
             }
         );
     }
@@ -367,8 +602,51 @@ fn get_all_branches(repo: &Repository) -> Result<Vec<Branch>, Error> {
         .collect())
 }
 
-pub(crate) fn branch_name_from_issue(issue: &Issue) -> String {
-    format!("{}-{}", issue.key, issue.summary.to_ascii_lowercase()).replace(' ', "-")
+/// The name of the branch for `issue`, starting with the first of `branch_prefixes` whose key
+/// matches one of the issue's labels (ignoring case).
+pub(crate) fn branch_name_from_issue(
+    issue: &Issue,
+    branch_prefixes: &IndexMap<String, String>,
+) -> String {
+    let prefix = branch_prefixes
+        .iter()
+        .find(|(label, _)| {
+            issue
+                .labels
+                .iter()
+                .any(|issue_label| issue_label.eq_ignore_ascii_case(label))
+        })
+        .map_or("", |(_, prefix)| prefix.as_str());
+    format!(
+        "{prefix}{}",
+        format!("{}-{}", issue.key, issue.summary.to_ascii_lowercase()).replace(' ', "-")
+    )
+}
+
+#[cfg(test)]
+mod test_is_auth_failure {
+    use super::is_auth_failure;
+
+    #[test]
+    fn https_without_credentials() {
+        assert!(is_auth_failure(
+            "fatal: could not read Username for 'https://github.com': terminal prompts disabled"
+        ));
+    }
+
+    #[test]
+    fn ssh_without_key() {
+        assert!(is_auth_failure(
+            "git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository."
+        ));
+    }
+
+    #[test]
+    fn missing_remote() {
+        assert!(!is_auth_failure(
+            "fatal: 'origin' does not appear to be a git repository\nfatal: Could not read from remote repository.\n\nPlease make sure you have the correct access rights\nand the repository exists."
+        ));
+    }
 }
 
 #[cfg(test)]
@@ -380,33 +658,177 @@ mod test_branch_name_from_issue {
         let issue = Issue {
             key: "FLOW-5".to_string(),
             summary: "A test issue".to_string(),
+            labels: Vec::new(),
+            url: None,
         };
-        let branch_name = super::branch_name_from_issue(&issue);
+        let branch_name = super::branch_name_from_issue(&issue, &IndexMap::new());
         assert_eq!(&branch_name, "FLOW-5-a-test-issue");
     }
+
+    #[test]
+    fn branch_prefixes() {
+        let issue = Issue {
+            key: "FLOW-5".to_string(),
+            summary: "A test issue".to_string(),
+            labels: vec!["Bug".to_string(), "In Progress".to_string()],
+            url: None,
+        };
+        let branch_prefixes = IndexMap::from([
+            ("story".to_string(), "feat/".to_string()),
+            ("bug".to_string(), "fix/".to_string()),
+        ]);
+        let branch_name = super::branch_name_from_issue(&issue, &branch_prefixes);
+        assert_eq!(&branch_name, "fix/FLOW-5-a-test-issue");
+    }
 }
 
 /// Add some files to Git to be committed later.
-pub(crate) fn add_files(file_names: &[PathBuf]) -> Result<(), Error> {
+///
+/// Any files which no longer exist (like consumed change files) are removed from the index instead.
+pub(crate) fn add_files(dry_run: DryRun, file_names: &[PathBuf]) -> Result<(), Error> {
     if file_names.is_empty() {
         return Ok(());
     }
+    if let Some(stdout) = dry_run {
+        return write_file_list(stdout, "Would add files to git:", file_names);
+    }
     let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
     let mut index = repo.index()?;
     index.add_all(file_names, IndexAddOption::DEFAULT, None)?;
     index.write().map_err(Error::from)
 }
 
+/// Remove some files from the index, keeping their changes in the working directory, like
+/// `git reset -- <file_names>`.
+pub(crate) fn unstage_files(dry_run: DryRun, file_names: &[PathBuf]) -> Result<(), Error> {
+    if file_names.is_empty() {
+        return Ok(());
+    }
+    if let Some(stdout) = dry_run {
+        return write_file_list(stdout, "Would unstage files from git:", file_names);
+    }
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let head = repo.head()?.peel(ObjectType::Commit)?;
+    repo.reset_default(Some(&head), file_names.iter().map(PathBuf::as_path))?;
+    Ok(())
+}
+
+/// Write `header`, then each of `file_names` indented on its own line.
+fn write_file_list(
+    stdout: &mut Box<dyn std::io::Write>,
+    header: &str,
+    file_names: &[PathBuf],
+) -> Result<(), Error> {
+    writeln!(stdout, "{header}").map_err(fs::Error::Stdout)?;
+    for file_name in file_names {
+        writeln!(stdout, "  {}", file_name.display()).map_err(fs::Error::Stdout)?;
+    }
+    Ok(())
+}
+
+/// Whether anything in the index differs from `HEAD`.
+pub(crate) fn has_staged_changes() -> Result<bool, Error> {
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let head_tree = repo.head()?.peel_to_tree()?;
+    let diff = repo.diff_tree_to_index(Some(&head_tree), None, None)?;
+    let has_changes = diff.deltas().len() > 0;
+    Ok(has_changes)
+}
+
+/// Commit everything in the index with `message`, as the identity in `git_config`.
+///
+/// This runs `git commit` (like a `Command` step would), so that hooks and signing still apply.
+pub(crate) fn commit(
+    dry_run: DryRun,
+    message: &str,
+    git_config: &config::Git,
+) -> Result<(), Error> {
+    if let Some(stdout) = dry_run {
+        return writeln!(stdout, "Would commit with message: {message}")
+            .map_err(fs::Error::Stdout)
+            .map_err(Error::from);
+    }
+    let status = std::process::Command::new("git")
+        .args(["commit", "--quiet", "--message", message])
+        .envs(git_config.commit_env_vars())
+        .status()
+        .map_err(|err| ErrorKind::Commit(Box::new(err)))?;
+    if !status.success() {
+        return Err(ErrorKind::Commit(format!("git exited with {status}").into()).into());
+    }
+    Ok(())
+}
+
+/// Stage `paths` (relative to `repo_dir`, which is another checkout) and commit only them with
+/// `message`, as the identity in `git_config`.
+pub(crate) fn commit_paths_in(
+    repo_dir: &Path,
+    paths: &[PathBuf],
+    message: &str,
+    git_config: &config::Git,
+) -> Result<(), Error> {
+    let commit_error = |source| ErrorKind::CommitDownstream {
+        path: repo_dir.display().to_string(),
+        source,
+    };
+    let add = std::process::Command::new("git")
+        .arg("add")
+        .arg("--")
+        .args(paths)
+        .current_dir(repo_dir)
+        .status()
+        .map_err(|err| commit_error(Box::new(err)))?;
+    if !add.success() {
+        return Err(commit_error(format!("git add exited with {add}").into()).into());
+    }
+    let commit = std::process::Command::new("git")
+        .args(["commit", "--quiet", "--message", message, "--"])
+        .args(paths)
+        .envs(git_config.commit_env_vars())
+        .current_dir(repo_dir)
+        .status()
+        .map_err(|err| commit_error(Box::new(err)))?;
+    if !commit.success() {
+        return Err(commit_error(format!("git commit exited with {commit}").into()).into());
+    }
+    Ok(())
+}
+
+/// A commit found while looking for changes to release.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct CommitInfo {
+    pub(crate) message: String,
+    /// The name of the author (not the committer).
+    pub(crate) author: String,
+    /// Whether the commit has more than one parent.
+    pub(crate) is_merge: bool,
+}
+
+/// The commits found by [`get_commits_after_tag`] or [`get_commits_after_revision`].
+#[derive(Clone, Debug)]
+pub(crate) struct Commits {
+    /// Oldest first.
+    pub(crate) commits: Vec<CommitInfo>,
+    /// The (short) hash of the oldest commit used, if there were more than `max_commits` commits
+    /// to check.
+    pub(crate) truncated_at: Option<String>,
+}
+
 /// Find every commit that appears only _after_ a specific tag.
 ///
 /// This builds a complete set of every commit in the repository, because branching and merging
 /// means that there could be paths which jump _behind_ the target tag... and we want to exclude
 /// those as well. There's probably a way to optimize performance with some cool graph magic
 /// eventually, but this is good enough for now.
-pub(crate) fn get_commit_messages_after_tag(
+pub(crate) fn get_commits_after_tag(
     tag: Option<String>,
+    sources: ReleaseSources,
+    since_date: Option<Date>,
+    by_commit_time: bool,
+    max_commits: Option<usize>,
+    first_parent: bool,
     verbose: Verbose,
-) -> Result<Vec<String>, Error> {
+) -> Result<Commits, Error> {
     let repo = gix::open(".")?;
     if let Verbose::Yes = verbose {
         if let Some(tag) = &tag {
@@ -415,20 +837,133 @@ pub(crate) fn get_commit_messages_after_tag(
             println!("Finding ALL commits");
         }
     }
-    let commits_to_exclude = tag
-        .map(|tag| format!("refs/tags/{tag}"))
-        .as_ref()
-        .map(|reference| {
-            repo.find_reference(reference)
-                .map_err(|err| ErrorKind::FindReference {
-                    reference: reference.clone(),
-                    source: err,
-                })
-        })
-        .transpose()?
-        .map(gix::Reference::into_fully_peeled_id)
-        .transpose()?
-        .and_then(|tag_oid| repo.find_object(tag_oid).ok().map(gix::Object::into_commit))
+    let start = tag
+        .map(|tag| release_commit(&repo, &tag, sources))
+        .transpose()?;
+    commits_after(
+        &repo,
+        start,
+        since_date,
+        by_commit_time,
+        max_commits,
+        first_parent,
+    )
+}
+
+/// Where to look for the commits of releases, besides the tags in the local repository.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ReleaseSources<'a> {
+    /// The ref of the Git notes which record releases, see [`ReleaseNote`].
+    pub(crate) notes_ref: Option<&'a str>,
+    /// The release tags on a forge, which might not have been fetched.
+    pub(crate) forge_tags: &'a [ForgeTag],
+}
+
+/// A release tag found on a forge, instead of in the local repository.
+#[derive(Clone, Debug)]
+pub(crate) struct ForgeTag {
+    pub(crate) name: String,
+    /// The SHA of the commit the tag points to, if the forge said
+    pub(crate) commit: Option<String>,
+}
+
+/// The commit that the release `tag` points to, which is either a Git tag, a release recorded in
+/// a Git note, or a tag on a forge (in that order).
+fn release_commit(
+    repo: &gix::Repository,
+    tag: &str,
+    sources: ReleaseSources,
+) -> Result<ObjectId, Error> {
+    let reference = format!("refs/tags/{tag}");
+    match repo.find_reference(reference.as_str()) {
+        Ok(found) => Ok(found.into_fully_peeled_id()?.detach()),
+        Err(source) => {
+            let from_note = sources
+                .notes_ref
+                .map(tags_from_notes)
+                .transpose()?
+                .into_iter()
+                .flatten()
+                .find(|(_, tags)| tags.iter().any(|note_tag| note_tag == tag));
+            if let Some((commit, _)) = from_note {
+                return Ok(commit);
+            }
+            let forge_commit = sources
+                .forge_tags
+                .iter()
+                .find(|forge_tag| forge_tag.name == tag)
+                .and_then(|forge_tag| forge_tag.commit.as_deref());
+            if let Some(commit) = forge_commit {
+                return ObjectId::from_hex(commit.as_bytes())
+                    .ok()
+                    .filter(|id| repo.find_object(*id).is_ok())
+                    .ok_or_else(|| {
+                        ErrorKind::MissingReleaseCommit {
+                            tag: tag.to_string(),
+                            commit: commit.to_string(),
+                        }
+                        .into()
+                    });
+            }
+            Err(ErrorKind::FindReference { reference, source }.into())
+        }
+    }
+}
+
+/// Like [`get_commits_after_tag`], but starting from any Git revision (like a tag, branch,
+/// commit hash, or `HEAD~5`) instead of a release tag.
+pub(crate) fn get_commits_after_revision(
+    revision: &str,
+    since_date: Option<Date>,
+    by_commit_time: bool,
+    max_commits: Option<usize>,
+    first_parent: bool,
+    verbose: Verbose,
+) -> Result<Commits, Error> {
+    let repo = gix::open(".")?;
+    if let Verbose::Yes = verbose {
+        println!("Finding all commits since {revision}");
+    }
+    let invalid_revision = |source| ErrorKind::InvalidRevision {
+        revision: revision.to_string(),
+        source,
+    };
+    let git2_repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let commit = git2_repo
+        .revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|err| invalid_revision(Box::new(err)))?;
+    let start = ObjectId::try_from(commit.id().as_bytes())
+        .map_err(|err| invalid_revision(Box::new(err)))?;
+    commits_after(
+        &repo,
+        Some(start),
+        since_date,
+        by_commit_time,
+        max_commits,
+        first_parent,
+    )
+}
+
+/// Get every commit reachable from `HEAD` but not from `start`, oldest first.
+///
+/// If there are more than `max_commits` of them, only the first `max_commits` found walking back
+/// from `HEAD` are returned, and a warning is printed.
+///
+/// If `first_parent` is set, only the first parent of each merge commit is followed (like
+/// `git log --first-parent`), so the commits of merged branches are left out.
+///
+/// Commits made (going by their commit time, in UTC) before `since_date` are left out too.
+fn commits_after(
+    repo: &gix::Repository,
+    start: Option<ObjectId>,
+    since_date: Option<Date>,
+    by_commit_time: bool,
+    max_commits: Option<usize>,
+    first_parent: bool,
+) -> Result<Commits, Error> {
+    let commits_to_exclude = start
+        .and_then(|oid| repo.find_object(oid).ok().map(gix::Object::into_commit))
         .and_then(|commit| {
             commit.ancestors().all().ok().map(|ancestors| {
                 ancestors
@@ -440,8 +975,17 @@ pub(crate) fn get_commit_messages_after_tag(
         })
         .unwrap_or_default();
     let head_commit = repo.head_commit()?;
-    let mut reverse_commits = head_commit
-        .ancestors()
+    let sorting = if by_commit_time {
+        Sorting::ByCommitTimeNewestFirst
+    } else {
+        Sorting::BreadthFirst
+    };
+    let cutoff = since_date.map(|date| date.midnight().assume_utc().unix_timestamp());
+    let mut ancestors = head_commit.ancestors().sorting(sorting);
+    if first_parent {
+        ancestors = ancestors.first_parent_only();
+    }
+    let mut reverse_commits = ancestors
         .all()?
         .filter_map(Result::ok)
         .filter(|info| !commits_to_exclude.contains(&info.id))
@@ -450,15 +994,65 @@ pub(crate) fn get_commit_messages_after_tag(
                 commit
                     .decode()
                     .ok()
-                    .map(|commit| commit.message.to_string())
+                    .filter(|commit| {
+                        cutoff.map_or(true, |cutoff| commit.committer.time.seconds >= cutoff)
+                    })
+                    .map(|commit| {
+                        (
+                            info.id,
+                            CommitInfo {
+                                message: commit.message.to_string(),
+                                author: commit.author.name.to_string(),
+                                is_merge: commit.parents.len() > 1,
+                            },
+                        )
+                    })
             })
         })
+        // One extra, to know whether the limit was exceeded
+        .take(max_commits.map_or(usize::MAX, |max_commits| max_commits.saturating_add(1)))
         .collect_vec();
+    let truncated_at = max_commits
+        .filter(|max_commits| reverse_commits.len() > *max_commits)
+        .map(|max_commits| {
+            reverse_commits.truncate(max_commits);
+            reverse_commits
+                .last()
+                .map_or_else(String::new, |(id, _)| id.to_hex_with_len(7).to_string())
+        });
     reverse_commits.reverse();
-    Ok(reverse_commits)
+    Ok(Commits {
+        commits: reverse_commits
+            .into_iter()
+            .map(|(_, commit)| commit)
+            .collect(),
+        truncated_at,
+    })
 }
 
-pub(crate) fn create_tag(dry_run: DryRun, name: &str) -> Result<(), Error> {
+/// Check whether a tag named `name` exists in the local repository, on any branch.
+pub(crate) fn tag_exists(name: &str) -> Result<bool, Error> {
+    let repo = gix::open(current_dir().map_err(ErrorKind::CurrentDirectory)?)?;
+    let reference = format!("refs/tags/{name}");
+    let tag = repo
+        .try_find_reference(reference.as_str())
+        .map_err(|source| ErrorKind::FindTag {
+            tag: name.to_string(),
+            source,
+        })?;
+    Ok(tag.is_some())
+}
+
+/// Create a tag named `name` on the current commit, tagged by the identity in `git_config`.
+///
+/// An existing tag with the same name is moved to the current commit.
+///
+/// Anything missing from `git_config` comes from Git's own config.
+pub(crate) fn create_tag(
+    dry_run: DryRun,
+    name: &str,
+    git_config: &config::Git,
+) -> Result<(), Error> {
     if let Some(stdout) = dry_run {
         return writeln!(stdout, "Would create Git tag {name}")
             .map_err(fs::Error::Stdout)
@@ -466,16 +1060,113 @@ pub(crate) fn create_tag(dry_run: DryRun, name: &str) -> Result<(), Error> {
     }
     let repo = gix::open(current_dir().map_err(ErrorKind::CurrentDirectory)?)?;
     let head = repo.head_commit()?;
-    repo.tag(
-        name,
-        head.id,
-        Kind::Commit,
-        repo.committer()
-            .transpose()
-            .map_err(|_| ErrorKind::NoCommitter)?,
-        "",
-        PreviousValue::Any,
-    )?;
+    let committer = repo
+        .committer()
+        .transpose()
+        .map_err(|_| ErrorKind::NoCommitter)?;
+    let tagger_name = git_config
+        .user_name
+        .as_deref()
+        .map(BStr::new)
+        .or(committer.map(|committer| committer.name));
+    let tagger_email = git_config
+        .user_email
+        .as_deref()
+        .map(BStr::new)
+        .or(committer.map(|committer| committer.email));
+    let tagger = tagger_name
+        .zip(tagger_email)
+        .map(|(name, email)| SignatureRef {
+            name,
+            email,
+            time: committer.map_or_else(gix::date::Time::now_local_or_utc, |committer| {
+                committer.time
+            }),
+        });
+    repo.tag(name, head.id, Kind::Commit, tagger, "", PreviousValue::Any)?;
+    Ok(())
+}
+
+/// The names of all tags which point at the current commit.
+pub(crate) fn tags_on_head() -> Result<Vec<String>, Error> {
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let head = repo.head()?.peel_to_commit()?.id();
+    let tag_names = repo.tag_names(None)?;
+    Ok(tag_names
+        .iter()
+        .flatten()
+        .filter(|name| {
+            repo.revparse_single(&format!("refs/tags/{name}"))
+                .and_then(|object| object.peel_to_commit())
+                .is_ok_and(|commit| commit.id() == head)
+        })
+        .map(String::from)
+        .collect())
+}
+
+/// The full message of the current commit.
+pub(crate) fn head_message() -> Result<String, Error> {
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let head = repo.head()?.peel_to_commit()?;
+    Ok(String::from_utf8_lossy(head.message_bytes()).into_owned())
+}
+
+/// The paths of all files added since `HEAD` branched off of `base`, including ones which are
+/// staged or untracked but not yet committed.
+pub(crate) fn files_added_since(base: &str) -> Result<Vec<PathBuf>, Error> {
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let base_commit = repo
+        .revparse_single(base)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|err| ErrorKind::InvalidRevision {
+            revision: base.to_string(),
+            source: Box::new(err),
+        })?;
+    let head = repo.head()?.peel_to_commit()?;
+    let merge_base = repo.find_commit(repo.merge_base(base_commit.id(), head.id())?)?;
+    let mut options = git2::DiffOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let diff =
+        repo.diff_tree_to_workdir_with_index(Some(&merge_base.tree()?), Some(&mut options))?;
+    Ok(diff
+        .deltas()
+        .filter(|delta| matches!(delta.status(), git2::Delta::Added | git2::Delta::Untracked))
+        .filter_map(|delta| delta.new_file().path().map(Path::to_path_buf))
+        .collect())
+}
+
+/// Delete the local tag `name`, leaving any remote alone.
+pub(crate) fn delete_tag(dry_run: DryRun, name: &str) -> Result<(), Error> {
+    if let Some(stdout) = dry_run {
+        return writeln!(stdout, "Would delete Git tag {name}")
+            .map_err(fs::Error::Stdout)
+            .map_err(Error::from);
+    }
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    repo.tag_delete(name)?;
+    Ok(())
+}
+
+/// Add a commit which reverts the current commit, restoring anything it deleted (like change
+/// files).
+///
+/// Like [`commit`], this runs `git` as the identity in `git_config`.
+pub(crate) fn revert_head(dry_run: DryRun, git_config: &config::Git) -> Result<(), Error> {
+    if let Some(stdout) = dry_run {
+        return writeln!(stdout, "Would revert the current commit")
+            .map_err(fs::Error::Stdout)
+            .map_err(Error::from);
+    }
+    // `git revert` has no `--quiet`, so its output is only shown if it fails
+    let output = std::process::Command::new("git")
+        .args(["revert", "--no-edit", "HEAD"])
+        .envs(git_config.commit_env_vars())
+        .output()
+        .map_err(|err| ErrorKind::Revert(Box::new(err)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ErrorKind::Revert(stderr.trim().into()).into());
+    }
     Ok(())
 }
 
@@ -520,10 +1211,16 @@ pub(crate) fn get_current_versions_from_tags(
     current_versions
 }
 
-/// Get all tags on the current branch.
-pub(crate) fn all_tags_on_branch(verbose: Verbose) -> Result<Vec<String>, Error> {
+/// Get all tags on the current branch, newest first.
+///
+/// If `notes_ref` is set, the releases recorded in those Git notes count as tags too.
+pub(crate) fn all_tags_on_branch(
+    notes_ref: Option<&str>,
+    verbose: Verbose,
+) -> Result<Vec<String>, Error> {
     let repo = gix::open(current_dir().map_err(ErrorKind::CurrentDirectory)?)?;
-    let mut all_tags: HashMap<ObjectId, Vec<String>> = HashMap::new();
+    // Keeps the order tags were found in, so verbose output is the same between runs
+    let mut all_tags: IndexMap<ObjectId, Vec<String>> = IndexMap::new();
     for (id, tag) in repo
         .references()?
         .tags()?
@@ -543,6 +1240,15 @@ pub(crate) fn all_tags_on_branch(verbose: Verbose) -> Result<Vec<String>, Error>
     {
         all_tags.entry(id).or_default().push(tag);
     }
+    if let Some(notes_ref) = notes_ref {
+        let real_tags: HashSet<String> = all_tags.values().flatten().cloned().collect();
+        for (id, tags) in tags_from_notes(notes_ref)? {
+            all_tags
+                .entry(id)
+                .or_default()
+                .extend(tags.into_iter().filter(|tag| !real_tags.contains(tag)));
+        }
+    }
 
     let mut tags: Vec<String> = Vec::with_capacity(all_tags.len());
     for commit_id in repo
@@ -551,7 +1257,7 @@ pub(crate) fn all_tags_on_branch(verbose: Verbose) -> Result<Vec<String>, Error>
         .all()?
         .filter_map(|info| info.ok().map(|info| info.id))
     {
-        if let Some(tag) = all_tags.remove(&commit_id) {
+        if let Some(tag) = all_tags.shift_remove(&commit_id) {
             tags.extend(tag);
         }
     }
@@ -565,3 +1271,94 @@ pub(crate) fn all_tags_on_branch(verbose: Verbose) -> Result<Vec<String>, Error>
     }
     Ok(tags)
 }
+
+/// The content of a Git note which the `Release` step adds to the release commit.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ReleaseNote {
+    pub(crate) releases: Vec<NoteRelease>,
+}
+
+/// One package's release in a [`ReleaseNote`].
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct NoteRelease {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) package: Option<String>,
+    pub(crate) version: String,
+    /// The tag this release would have, which is how the version is found later
+    pub(crate) tag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) notes: Option<String>,
+}
+
+/// The full name of a notes ref, like `refs/notes/knope` for `knope`.
+fn full_notes_ref(notes_ref: &str) -> String {
+    if notes_ref.starts_with("refs/") {
+        notes_ref.to_string()
+    } else {
+        format!("refs/notes/{notes_ref}")
+    }
+}
+
+/// The release tags recorded in each [`ReleaseNote`] in `notes_ref`, with the commit they're on.
+///
+/// Notes which aren't release notes are ignored.
+fn tags_from_notes(notes_ref: &str) -> Result<Vec<(ObjectId, Vec<String>)>, Error> {
+    let repo = Repository::open(".").map_err(ErrorKind::OpenRepo)?;
+    let notes_ref = full_notes_ref(notes_ref);
+    let notes = match repo.notes(Some(&notes_ref)) {
+        Ok(notes) => notes,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    Ok(notes
+        .filter_map(Result::ok)
+        .filter_map(|(_, commit)| {
+            let note = repo.find_note(Some(&notes_ref), commit).ok()?;
+            let release_note: ReleaseNote = serde_json::from_str(note.message()?).ok()?;
+            let commit = ObjectId::try_from(commit.as_bytes()).ok()?;
+            let tags = release_note
+                .releases
+                .into_iter()
+                .map(|release| release.tag)
+                .collect();
+            Some((commit, tags))
+        })
+        .collect())
+}
+
+/// Attach `note` to the current commit in `notes_ref`, replacing any note already there.
+///
+/// Like [`commit`], this runs `git` as the identity in `git_config`.
+pub(crate) fn add_note(
+    dry_run: DryRun,
+    notes_ref: &str,
+    note: &ReleaseNote,
+    git_config: &config::Git,
+) -> Result<(), Error> {
+    let notes_ref = full_notes_ref(notes_ref);
+    if let Some(stdout) = dry_run {
+        let tags = note.releases.iter().map(|release| &release.tag).join(", ");
+        return writeln!(
+            stdout,
+            "Would add a Git note to the current commit in {notes_ref} for {tags}"
+        )
+        .map_err(fs::Error::Stdout)
+        .map_err(Error::from);
+    }
+    let message = serde_json::to_string_pretty(note).unwrap_or_default();
+    let add_note_error = |source| ErrorKind::AddNote {
+        notes_ref: notes_ref.clone(),
+        source,
+    };
+    let status = std::process::Command::new("git")
+        .args(["notes", "--ref", &notes_ref, "add", "--force", "--message"])
+        .arg(&message)
+        .arg("HEAD")
+        .envs(git_config.commit_env_vars())
+        .status()
+        .map_err(|err| add_note_error(Box::new(err)))?;
+    if !status.success() {
+        return Err(add_note_error(format!("git exited with {status}").into()).into());
+    }
+    Ok(())
+}
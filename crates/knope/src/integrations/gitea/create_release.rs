@@ -48,6 +48,47 @@ pub(crate) fn create_release(
     Ok(state::Gitea::Initialized { token, agent })
 }
 
+/// Check whether Gitea already has a release for `tag_name`.
+///
+/// In a dry run, this reports that it would check and assumes there is no release yet.
+pub(crate) fn release_exists(
+    tag_name: &str,
+    gitea_state: state::Gitea,
+    gitea_config: &config::Gitea,
+    dry_run_stdout: DryRun,
+) -> Result<(bool, state::Gitea), Error> {
+    if let Some(stdout) = dry_run_stdout {
+        writeln!(
+            stdout,
+            "Would check whether Gitea [{host}] already has a release for tag {tag_name}",
+            host = gitea_config.host
+        )
+        .map_err(Error::Stdout)?;
+        return Ok((false, gitea_state));
+    }
+
+    let (token, agent) = initialize_state(&gitea_config.host, gitea_state)?;
+    let exists = match agent
+        .get(&format!(
+            "{releases}/tags/{tag_name}",
+            releases = gitea_config.get_releases_url()
+        ))
+        .query("access_token", &token)
+        .call()
+    {
+        Ok(_) => true,
+        Err(ureq::Error::Status(404, _)) => false,
+        Err(source) => {
+            return Err(Error::ApiRequest {
+                err: ureq_err_to_string(source),
+                activity: format!("looking for an existing release for tag {tag_name}"),
+                host: gitea_config.host.clone(),
+            })
+        }
+    };
+    Ok((exists, state::Gitea::Initialized { token, agent }))
+}
+
 fn gitea_release_dry_run(
     name: &str,
     config: &config::Gitea,
@@ -40,8 +40,14 @@ pub(crate) fn list_issues(
         })?
         .into_iter()
         .map(|response| Issue {
+            url: Some(config.get_issue_web_url(&response.number.to_string())),
             key: response.number.to_string(),
             summary: response.title,
+            labels: response
+                .labels
+                .into_iter()
+                .map(|label| label.name)
+                .collect(),
         })
         .collect();
 
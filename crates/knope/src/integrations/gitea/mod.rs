@@ -12,7 +12,7 @@ mod list_issues;
 pub(crate) use create_pull_request::{
     create_or_update_pull_request, Error as CreatePullRequestError,
 };
-pub(crate) use create_release::{create_release, Error as CreateReleaseError};
+pub(crate) use create_release::{create_release, release_exists, Error as CreateReleaseError};
 pub(crate) use list_issues::{list_issues, Error as ListIssuesError};
 
 fn initialize_state(host: &str, state: state::Gitea) -> Result<(String, Agent), app_config::Error> {
@@ -0,0 +1,123 @@
+use itertools::Itertools;
+use miette::Diagnostic;
+use serde::{de::IgnoredAny, Deserialize};
+
+use super::graphql;
+use crate::{app_config, config, dry_run::DryRun, integrations::github::initialize_state, state};
+
+const REPOSITORY_QUERY: &str = "query($owner: String!, $repo: String!) {
+  repository(owner: $owner, name: $repo) {
+    id
+    discussionCategories(first: 100) {
+      nodes { id name }
+    }
+  }
+}";
+
+const CREATE_DISCUSSION_MUTATION: &str =
+    "mutation($repositoryId: ID!, $categoryId: ID!, $title: String!, $body: String!) {
+  createDiscussion(input: {repositoryId: $repositoryId, categoryId: $categoryId, title: $title, body: $body}) {
+    discussion { id }
+  }
+}";
+
+/// Create a discussion in the GitHub category named `category`.
+///
+/// Discussions are only available through GitHub's GraphQL API.
+pub(crate) fn create_discussion(
+    category: &str,
+    title: &str,
+    body: &str,
+    github_state: state::GitHub,
+    github_config: &config::GitHub,
+    dry_run: DryRun,
+) -> Result<state::GitHub, Error> {
+    if let Some(stdout) = dry_run {
+        writeln!(
+            stdout,
+            "Would create a discussion on GitHub in category {category} with title {title} and body:\n{body}"
+        )
+        .map_err(Error::Stdout)?;
+        return Ok(github_state);
+    }
+
+    let (token, agent) = initialize_state(github_state)?;
+    let RepositoryData { repository } = graphql::query(
+        &agent,
+        &token,
+        REPOSITORY_QUERY,
+        &ureq::json!({
+            "owner": github_config.owner,
+            "repo": github_config.repo,
+        }),
+        "finding discussion categories",
+        "Discussions: read",
+    )?;
+    let categories = repository.discussion_categories.nodes;
+    let category_id = categories
+        .iter()
+        .find(|it| it.name.eq_ignore_ascii_case(category))
+        .map(|it| it.id.clone())
+        .ok_or_else(|| Error::UnknownCategory {
+            category: category.to_string(),
+            available: categories.iter().map(|it| &it.name).join(", "),
+        })?;
+
+    let _: IgnoredAny = graphql::query(
+        &agent,
+        &token,
+        CREATE_DISCUSSION_MUTATION,
+        &ureq::json!({
+            "repositoryId": repository.id,
+            "categoryId": category_id,
+            "title": title,
+            "body": body,
+        }),
+        "creating a discussion",
+        "Discussions: write",
+    )?;
+
+    Ok(state::GitHub::Initialized { token, agent })
+}
+
+#[derive(Deserialize)]
+struct RepositoryData {
+    repository: Repository,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Repository {
+    id: String,
+    discussion_categories: Nodes<DiscussionCategory>,
+}
+
+#[derive(Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct DiscussionCategory {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    GraphQl(#[from] graphql::Error),
+    #[error("No discussion category named {category}")]
+    #[diagnostic(
+        code(github::unknown_discussion_category),
+        help("The available categories are: {available}"),
+        url("https://knope.tech/reference/config-file/steps/create-discussion/")
+    )]
+    UnknownCategory { category: String, available: String },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    AppConfig(#[from] app_config::Error),
+    #[error("Error writing to stdout: {0}")]
+    Stdout(#[source] std::io::Error),
+}
@@ -0,0 +1,217 @@
+use std::str::FromStr;
+
+use knope_versioning::Version;
+use miette::Diagnostic;
+use serde::Deserialize;
+use serde_json::json;
+use ureq::Agent;
+
+use crate::{
+    app_config, config,
+    dry_run::DryRun,
+    integrations::github::{initialize_state, request_error, PermissionError},
+    state,
+};
+
+/// The permission needed to create and close milestones.
+const ISSUES_WRITE: &str = "Issues: write";
+
+/// Create a milestone named `version`, unless one already exists, and return its number.
+///
+/// If `close_previous`, the open milestone named for the latest version before `version` is
+/// closed too.
+pub(crate) fn create_milestone(
+    version: &Version,
+    close_previous: bool,
+    github_state: state::GitHub,
+    github_config: &config::GitHub,
+    dry_run: DryRun,
+) -> Result<(Option<u64>, state::GitHub), Error> {
+    let title = version.to_string();
+    if let Some(stdout) = dry_run {
+        writeln!(
+            stdout,
+            "Would create a milestone on GitHub named {title}, if it doesn't exist"
+        )
+        .map_err(Error::Stdout)?;
+        if close_previous {
+            writeln!(
+                stdout,
+                "Would close the open milestone on GitHub for the version before {title}"
+            )
+            .map_err(Error::Stdout)?;
+        }
+        return Ok((None, github_state));
+    }
+
+    let (token, agent) = initialize_state(github_state)?;
+    let config::GitHub { owner, repo } = github_config;
+    let base_url = format!("https://api.github.com/repos/{owner}/{repo}/milestones");
+    let authorization_header = format!("Bearer {token}");
+
+    let milestones: Vec<Milestone> = agent
+        .get(&base_url)
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", &authorization_header)
+        .query("state", "all")
+        .query("per_page", "100")
+        .call()
+        .map_err(|source| {
+            request_error(
+                source,
+                "fetching milestones".to_string(),
+                "Issues: read",
+                |err, activity| Error::ApiRequest { err, activity },
+            )
+        })?
+        .into_json()
+        .map_err(|source| Error::ApiResponse {
+            source,
+            activity: "fetching milestones",
+        })?;
+
+    let number = if let Some(existing) = milestones.iter().find(|it| it.title == title) {
+        existing.number
+    } else {
+        let created: Milestone = agent
+            .post(&base_url)
+            .set("Accept", "application/vnd.github+json")
+            .set("Authorization", &authorization_header)
+            .send_json(json!({ "title": title }))
+            .map_err(|source| {
+                request_error(
+                    source,
+                    format!("creating milestone {title}"),
+                    ISSUES_WRITE,
+                    |err, activity| Error::ApiRequest { err, activity },
+                )
+            })?
+            .into_json()
+            .map_err(|source| Error::ApiResponse {
+                source,
+                activity: "creating a milestone",
+            })?;
+        created.number
+    };
+
+    if close_previous {
+        if let Some(previous) = previous_milestone(&milestones, version) {
+            close_milestone(&base_url, previous, &authorization_header, &agent)?;
+        }
+    }
+
+    Ok((Some(number), state::GitHub::Initialized { token, agent }))
+}
+
+/// The open milestone named for the latest version before `version`, if any.
+fn previous_milestone<'a>(milestones: &'a [Milestone], version: &Version) -> Option<&'a Milestone> {
+    milestones
+        .iter()
+        .filter(|it| it.state == "open")
+        .filter_map(|it| {
+            Version::from_str(&it.title)
+                .ok()
+                .filter(|milestone_version| milestone_version < version)
+                .map(|milestone_version| (milestone_version, it))
+        })
+        .max_by(|(left, _), (right, _)| left.cmp(right))
+        .map(|(_, milestone)| milestone)
+}
+
+fn close_milestone(
+    base_url: &str,
+    milestone: &Milestone,
+    auth_header: &str,
+    agent: &Agent,
+) -> Result<(), Error> {
+    agent
+        .patch(&format!("{base_url}/{}", milestone.number))
+        .set("Accept", "application/vnd.github+json")
+        .set("Authorization", auth_header)
+        .send_json(json!({ "state": "closed" }))
+        .map_err(|source| {
+            request_error(
+                source,
+                format!("closing milestone {}", milestone.title),
+                ISSUES_WRITE,
+                |err, activity| Error::ApiRequest { err, activity },
+            )
+        })?;
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct Milestone {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    state: String,
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Trouble communicating with GitHub while {activity}: {err}")]
+    #[diagnostic(
+        code(github::api_request_error),
+        help(
+            "There was a problem communicating with GitHub, this may be a network issue or a permissions issue."
+        )
+    )]
+    ApiRequest { err: String, activity: String },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Permission(#[from] PermissionError),
+    #[error("Trouble decoding the response from GitHub while {activity}: {source}")]
+    #[diagnostic(
+        code(github::api_response_error),
+        help(
+            "Failure to decode a response from GitHub is probably a bug. Please report it at https://github.com/knope-dev/knope"
+        )
+    )]
+    ApiResponse {
+        source: std::io::Error,
+        activity: &'static str,
+    },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    AppConfig(#[from] app_config::Error),
+    #[error("Error writing to stdout: {0}")]
+    Stdout(#[source] std::io::Error),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test_previous_milestone {
+    use super::*;
+
+    fn milestone(number: u64, title: &str, state: &str) -> Milestone {
+        Milestone {
+            number,
+            title: title.to_string(),
+            state: state.to_string(),
+        }
+    }
+
+    #[test]
+    fn latest_open_earlier_version() {
+        let milestones = [
+            milestone(1, "1.0.0", "open"),
+            milestone(2, "1.1.0", "open"),
+            milestone(3, "1.2.0", "closed"),
+            milestone(4, "Backlog", "open"),
+            milestone(5, "2.0.0", "open"),
+        ];
+        let previous =
+            previous_milestone(&milestones, &Version::from_str("1.3.0").unwrap()).unwrap();
+        assert_eq!(previous.number, 2);
+    }
+
+    #[test]
+    fn no_earlier_version() {
+        let milestones = [
+            milestone(1, "2.0.0", "open"),
+            milestone(2, "1.0.0", "closed"),
+        ];
+        assert!(previous_milestone(&milestones, &Version::from_str("1.3.0").unwrap()).is_none());
+    }
+}
@@ -5,11 +5,18 @@ use ureq::Agent;
 use crate::{
     app_config, config,
     dry_run::DryRun,
-    integrations::{git, github::initialize_state, ureq_err_to_string, PullRequest},
+    integrations::{
+        git,
+        github::{initialize_state, request_error, PermissionError},
+        PullRequest,
+    },
     state,
     workflow::Verbose,
 };
 
+/// The permission needed to create and update pull requests.
+const PULL_REQUESTS_WRITE: &str = "Pull requests: write";
+
 pub(crate) fn create_or_update_pull_request(
     title: &str,
     body: &str,
@@ -43,9 +50,13 @@ pub(crate) fn create_or_update_pull_request(
         .query("head", &format!("{owner}:{current_branch}"))
         .query("base", base)
         .call()
-        .map_err(|err| Error::ApiRequest {
-            err: ureq_err_to_string(err),
-            activity: "fetching existing pull requests".to_string(),
+        .map_err(|source| {
+            request_error(
+                source,
+                "fetching existing pull requests".to_string(),
+                "Pull requests: read",
+                |err, activity| Error::ApiRequest { err, activity },
+            )
         })?
         .into_json()
         .map_err(|source| Error::ApiResponse {
@@ -90,9 +101,13 @@ fn update_pull_request(
             "title": title,
             "body": body,
         }))
-        .map_err(|source| Error::ApiRequest {
-            err: ureq_err_to_string(source),
-            activity: "updating pull request".to_string(),
+        .map_err(|source| {
+            request_error(
+                source,
+                "updating pull request".to_string(),
+                PULL_REQUESTS_WRITE,
+                |err, activity| Error::ApiRequest { err, activity },
+            )
         })?;
     Ok(agent)
 }
@@ -118,9 +133,13 @@ fn create_pull_request(
             "head": current_branch,
             "base": base,
         }))
-        .map_err(|source| Error::ApiRequest {
-            err: ureq_err_to_string(source),
-            activity: "creating pull request".to_string(),
+        .map_err(|source| {
+            request_error(
+                source,
+                "creating pull request".to_string(),
+                PULL_REQUESTS_WRITE,
+                |err, activity| Error::ApiRequest { err, activity },
+            )
         })?;
     if let Verbose::Yes = verbose {
         let json_data = response
@@ -146,6 +165,9 @@ pub(crate) enum Error {
         )
     )]
     ApiRequest { err: String, activity: String },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Permission(#[from] PermissionError),
     #[error("Trouble decoding the response from GitHub while {activity}: {source}")]
     #[diagnostic(
         code(github::api_response_error),
@@ -7,12 +7,16 @@ use crate::{
     app_config, config,
     dry_run::DryRun,
     integrations::{
-        github::initialize_state, ureq_err_to_string, CreateReleaseInput, CreateReleaseResponse,
+        github::{initialize_state, request_error, PermissionError},
+        CreateReleaseInput, CreateReleaseResponse,
     },
     state,
     step::releases::package::{Asset, AssetNameError},
 };
 
+/// The permission needed to create releases and move tags.
+const CONTENTS_WRITE: &str = "Contents: write";
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn create_release(
     name: &str,
@@ -45,9 +49,13 @@ pub(crate) fn create_release(
         .post(&url)
         .set("Authorization", &token_header)
         .send_json(github_release)
-        .map_err(|source| Error::ApiRequest {
-            err: ureq_err_to_string(source),
-            activity: "creating a release".to_string(),
+        .map_err(|source| {
+            request_error(
+                source,
+                "creating a release".to_string(),
+                CONTENTS_WRITE,
+                |err, activity| Error::ApiRequest { err, activity },
+            )
         })?
         .into_json()
         .map_err(|source| Error::ApiResponse {
@@ -71,11 +79,15 @@ pub(crate) fn create_release(
                 .set("Content-Type", "application/octet-stream")
                 .set("Content-Length", &file.len().to_string())
                 .send_bytes(&file)
-                .map_err(|source| Error::ApiRequest {
-                    err: ureq_err_to_string(source),
-                    activity: format!(
-                        "uploading asset {asset_name}. Release has been created but not published!",
-                    ),
+                .map_err(|source| {
+                    request_error(
+                        source,
+                        format!(
+                            "uploading asset {asset_name}. Release has been created but not published!",
+                        ),
+                        CONTENTS_WRITE,
+                        |err, activity| Error::ApiRequest { err, activity },
+                    )
                 })?;
         }
         agent
@@ -84,15 +96,103 @@ pub(crate) fn create_release(
             .send_json(ureq::json!({
                 "draft": false
             }))
-            .map_err(|source| Error::ApiRequest {
-                err: ureq_err_to_string(source),
-                activity: "publishing release".to_string(),
+            .map_err(|source| {
+                request_error(
+                    source,
+                    "publishing release".to_string(),
+                    CONTENTS_WRITE,
+                    |err, activity| Error::ApiRequest { err, activity },
+                )
             })?;
     }
 
     Ok(state::GitHub::Initialized { token, agent })
 }
 
+/// Check whether GitHub already has a release for `tag_name`.
+///
+/// In a dry run, this reports that it would check and assumes there is no release yet.
+pub(crate) fn release_exists(
+    tag_name: &str,
+    github_state: state::GitHub,
+    github_config: &config::GitHub,
+    dry_run_stdout: DryRun,
+) -> Result<(bool, state::GitHub), Error> {
+    if let Some(stdout) = dry_run_stdout {
+        writeln!(
+            stdout,
+            "Would check whether GitHub already has a release for tag {tag_name}"
+        )
+        .map_err(Error::Stdout)?;
+        return Ok((false, github_state));
+    }
+
+    let (token, agent) = initialize_state(github_state)?;
+    let url = format!(
+        "https://api.github.com/repos/{owner}/{repo}/releases/tags/{tag_name}",
+        owner = github_config.owner,
+        repo = github_config.repo,
+    );
+    let exists = match agent
+        .get(&url)
+        .set("Authorization", &format!("token {token}"))
+        .call()
+    {
+        Ok(_) => true,
+        Err(ureq::Error::Status(404, _)) => false,
+        Err(source) => {
+            return Err(request_error(
+                source,
+                format!("looking for an existing release for tag {tag_name}"),
+                "Contents: read",
+                |err, activity| Error::ApiRequest { err, activity },
+            ))
+        }
+    };
+    Ok((exists, state::GitHub::Initialized { token, agent }))
+}
+
+/// Move the existing tag `tag_name` on GitHub so it points at the commit `sha`.
+pub(crate) fn move_tag(
+    tag_name: &str,
+    sha: &str,
+    github_state: state::GitHub,
+    github_config: &config::GitHub,
+    dry_run_stdout: DryRun,
+) -> Result<state::GitHub, Error> {
+    if let Some(stdout) = dry_run_stdout {
+        writeln!(
+            stdout,
+            "Would move tag {tag_name} on GitHub to commit {sha}"
+        )
+        .map_err(Error::Stdout)?;
+        return Ok(github_state);
+    }
+
+    let (token, agent) = initialize_state(github_state)?;
+    let url = format!(
+        "https://api.github.com/repos/{owner}/{repo}/git/refs/tags/{tag_name}",
+        owner = github_config.owner,
+        repo = github_config.repo,
+    );
+    agent
+        .patch(&url)
+        .set("Authorization", &format!("token {token}"))
+        .send_json(ureq::json!({
+            "sha": sha,
+            "force": true,
+        }))
+        .map_err(|source| {
+            request_error(
+                source,
+                format!("moving tag {tag_name}"),
+                CONTENTS_WRITE,
+                |err, activity| Error::ApiRequest { err, activity },
+            )
+        })?;
+    Ok(state::GitHub::Initialized { token, agent })
+}
+
 fn github_release_dry_run(
     name: &str,
     assets: Option<&Vec<Asset>>,
@@ -154,6 +254,9 @@ pub(crate) enum Error {
         )
     )]
     ApiRequest { err: String, activity: String },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Permission(#[from] PermissionError),
     #[error("Trouble decoding the response from GitHub while {activity}: {source}")]
     #[diagnostic(
         code(github::api_response_error),
@@ -0,0 +1,110 @@
+use miette::Diagnostic;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+use ureq::Agent;
+
+use super::{request_error, PermissionError};
+
+const GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// Run a single GraphQL `query` (or mutation) for things that GitHub's REST API doesn't cover.
+///
+/// `activity` describes what the query is for, and `permission` is what a fine-grained token needs
+/// for it, both for error messages.
+pub(super) fn query<T: DeserializeOwned>(
+    agent: &Agent,
+    token: &str,
+    query: &str,
+    variables: &Value,
+    activity: &'static str,
+    permission: &'static str,
+) -> Result<T, Error> {
+    let response: Response<T> = agent
+        .post(GRAPHQL_URL)
+        .set("Authorization", &format!("bearer {token}"))
+        .send_json(ureq::json!({
+            "query": query,
+            "variables": variables,
+        }))
+        .map_err(|source| {
+            request_error(source, activity, permission, |err, activity| {
+                Error::ApiRequest { err, activity }
+            })
+        })?
+        .into_json()
+        .map_err(|source| Error::ApiResponse { source, activity })?;
+    if let Some(data) = response.data.filter(|_| response.errors.is_empty()) {
+        return Ok(data);
+    }
+    let refused = response.errors.iter().any(|error| {
+        matches!(
+            error.kind.as_deref(),
+            Some("FORBIDDEN" | "NOT_FOUND" | "INSUFFICIENT_SCOPES")
+        )
+    });
+    let messages = response
+        .errors
+        .into_iter()
+        .map(|error| error.message)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if refused {
+        Err(PermissionError {
+            activity: activity.to_string(),
+            permission,
+            response: messages,
+        }
+        .into())
+    } else {
+        Err(Error::Query { messages, activity })
+    }
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<ResponseError>,
+}
+
+#[derive(Deserialize)]
+struct ResponseError {
+    message: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Trouble communicating with GitHub while {activity}: {err}")]
+    #[diagnostic(
+        code(github::api_request_error),
+        help(
+            "There was a problem communicating with GitHub, this may be a network issue or a permissions issue."
+        )
+    )]
+    ApiRequest { err: String, activity: &'static str },
+    #[error("Trouble decoding the response from GitHub while {activity}: {source}")]
+    #[diagnostic(
+        code(github::api_response_error),
+        help(
+            "Failure to decode a response from GitHub is probably a bug. Please report it at https://github.com/knope-dev/knope"
+        )
+    )]
+    ApiResponse {
+        source: std::io::Error,
+        activity: &'static str,
+    },
+    #[error("GitHub returned errors while {activity}: {messages}")]
+    #[diagnostic(
+        code(github::graphql_error),
+        help("Check that your GitHub token has permission to do this.")
+    )]
+    Query {
+        messages: String,
+        activity: &'static str,
+    },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Permission(#[from] PermissionError),
+}
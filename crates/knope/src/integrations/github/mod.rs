@@ -1,13 +1,29 @@
+pub(crate) use create_discussion::{create_discussion, Error as CreateDiscussionError};
+pub(crate) use create_milestone::{create_milestone, Error as CreateMilestoneError};
 pub(crate) use create_pull_request::{
     create_or_update_pull_request, Error as CreatePullRequestError,
 };
-pub(crate) use create_release::{create_release, Error as CreateReleaseError};
+pub(crate) use create_release::{
+    create_release, move_tag, release_exists, Error as CreateReleaseError,
+};
+use miette::Diagnostic;
+pub(crate) use release_tags::{release_tags, Error as ReleaseTagsError};
+pub(crate) use update_release::{update_release, Error as UpdateReleaseError};
 use ureq::Agent;
+pub(crate) use wait_for_checks::{wait_for_checks, Error as WaitForChecksError};
 
-use crate::{app_config, app_config::get_or_prompt_for_github_token, state};
+use crate::{
+    app_config, app_config::get_or_prompt_for_github_token, integrations::ureq_err_to_string, state,
+};
 
+mod create_discussion;
+mod create_milestone;
 mod create_pull_request;
 mod create_release;
+mod graphql;
+mod release_tags;
+mod update_release;
+mod wait_for_checks;
 
 fn initialize_state(state: state::GitHub) -> Result<(String, Agent), app_config::Error> {
     Ok(match state {
@@ -18,3 +34,106 @@ fn initialize_state(state: state::GitHub) -> Result<(String, Agent), app_config:
         }
     })
 }
+
+/// A request which GitHub refused, most likely because the token is missing a permission.
+#[derive(Debug, Diagnostic, thiserror::Error)]
+#[error("GitHub refused the request while {activity}: {response}")]
+#[diagnostic(
+    code(github::missing_permission),
+    help(
+        "The GitHub token probably needs the `{permission}` permission for this repository. \
+        Fine-grained tokens need each permission granted for the repository, classic tokens need \
+        the `repo` scope. GitHub also responds this way if `owner` or `repo` is wrong."
+    ),
+    url("https://knope.tech/reference/config-file/github/")
+)]
+pub(crate) struct PermissionError {
+    activity: String,
+    permission: &'static str,
+    response: String,
+}
+
+/// Turn a failed request for `activity` into a [`PermissionError`] if GitHub refused it, or into
+/// the error from `api_request` otherwise (like network errors).
+///
+/// GitHub responds with a 404 for repositories the token can't access, not just a 403.
+fn request_error<A: ToString, E: From<PermissionError>>(
+    source: ureq::Error,
+    activity: A,
+    permission: &'static str,
+    api_request: impl FnOnce(String, A) -> E,
+) -> E {
+    match source {
+        ureq::Error::Status(status @ (403 | 404), response) => {
+            let response = format!("{status}: {}", response.into_string().unwrap_or_default());
+            if response.to_lowercase().contains("rate limit") {
+                return api_request(response, activity);
+            }
+            PermissionError {
+                activity: activity.to_string(),
+                permission,
+                response,
+            }
+            .into()
+        }
+        source => api_request(ureq_err_to_string(source), activity),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test_request_error {
+    use super::*;
+
+    #[derive(Debug)]
+    enum Error {
+        ApiRequest,
+        Permission(PermissionError),
+    }
+
+    impl From<PermissionError> for Error {
+        fn from(err: PermissionError) -> Self {
+            Self::Permission(err)
+        }
+    }
+
+    fn error_for(status: u16, body: &str) -> Error {
+        let response = ureq::Response::new(status, "", body).unwrap();
+        request_error(
+            ureq::Error::Status(status, response),
+            "testing",
+            "Contents: write",
+            |_, _| Error::ApiRequest,
+        )
+    }
+
+    #[test]
+    fn forbidden_is_permission_error() {
+        let err = error_for(403, "Resource not accessible by personal access token");
+        assert!(matches!(
+            err,
+            Error::Permission(PermissionError {
+                permission: "Contents: write",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn not_found_is_permission_error() {
+        assert!(matches!(error_for(404, "Not Found"), Error::Permission(_)));
+    }
+
+    #[test]
+    fn rate_limit_is_not_permission_error() {
+        assert!(matches!(
+            error_for(403, "API rate limit exceeded"),
+            Error::ApiRequest
+        ));
+    }
+
+    #[test]
+    fn server_error_is_not_permission_error() {
+        assert!(matches!(error_for(500, "Oops"), Error::ApiRequest));
+    }
+}
@@ -0,0 +1,129 @@
+use miette::Diagnostic;
+use serde::Deserialize;
+
+use crate::{
+    app_config, config,
+    dry_run::DryRun,
+    integrations::{
+        git::ForgeTag,
+        github::{initialize_state, request_error, PermissionError},
+    },
+    state,
+};
+
+/// The tags of the (non-draft) releases on GitHub, newest first, with the commit of each tag if
+/// GitHub lists it.
+///
+/// This is `None` for a dry run, which doesn't contact GitHub.
+pub(crate) fn release_tags(
+    github_state: state::GitHub,
+    github_config: &config::GitHub,
+    dry_run: DryRun,
+) -> Result<(Option<Vec<ForgeTag>>, state::GitHub), Error> {
+    if let Some(stdout) = dry_run {
+        writeln!(
+            stdout,
+            "Would find previous versions from the releases on GitHub, using local Git tags for this dry run"
+        )
+        .map_err(Error::Stdout)?;
+        return Ok((None, github_state));
+    }
+
+    let (token, agent) = initialize_state(github_state)?;
+    let config::GitHub { owner, repo } = github_config;
+    let authorization_header = format!("Bearer {token}");
+    let get = |path: &str, activity: &'static str| {
+        agent
+            .get(&format!(
+                "https://api.github.com/repos/{owner}/{repo}/{path}"
+            ))
+            .set("Accept", "application/vnd.github+json")
+            .set("Authorization", &authorization_header)
+            .query("per_page", "100")
+            .call()
+            .map_err(|source| {
+                request_error(source, activity, "Contents: read", |err, activity| {
+                    Error::ApiRequest { err, activity }
+                })
+            })
+    };
+
+    let releases: Vec<Release> =
+        get("releases", "fetching releases")?
+            .into_json()
+            .map_err(|source| Error::ApiResponse {
+                source,
+                activity: "fetching releases",
+            })?;
+    let tags: Vec<Tag> = get("tags", "fetching tags")?
+        .into_json()
+        .map_err(|source| Error::ApiResponse {
+            source,
+            activity: "fetching tags",
+        })?;
+
+    let release_tags = releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .map(|release| ForgeTag {
+            commit: tags
+                .iter()
+                .find(|tag| tag.name == release.tag_name)
+                .map(|tag| tag.commit.sha.clone()),
+            name: release.tag_name,
+        })
+        .collect();
+    Ok((
+        Some(release_tags),
+        state::GitHub::Initialized { token, agent },
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tag {
+    name: String,
+    commit: TagCommit,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagCommit {
+    sha: String,
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Trouble communicating with GitHub while {activity}: {err}")]
+    #[diagnostic(
+        code(github::api_request_error),
+        help(
+            "There was a problem communicating with GitHub, this may be a network issue or a permissions issue."
+        )
+    )]
+    ApiRequest { err: String, activity: &'static str },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Permission(#[from] PermissionError),
+    #[error("Trouble decoding the response from GitHub while {activity}: {source}")]
+    #[diagnostic(
+        code(github::api_response_error),
+        help(
+            "Failure to decode a response from GitHub is probably a bug. Please report it at https://github.com/knope-dev/knope"
+        )
+    )]
+    ApiResponse {
+        source: std::io::Error,
+        activity: &'static str,
+    },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    AppConfig(#[from] app_config::Error),
+    #[error("Error writing to stdout: {0}")]
+    Stdout(#[source] std::io::Error),
+}
@@ -0,0 +1,139 @@
+use std::io::Write;
+
+use itertools::Itertools;
+use miette::Diagnostic;
+
+use crate::{
+    app_config, config,
+    dry_run::DryRun,
+    integrations::github::{initialize_state, request_error, PermissionError},
+    state,
+};
+
+/// Replace the body of the existing release for `tag_name`, optionally changing whether it's a
+/// draft or a prerelease.
+pub(crate) fn update_release(
+    tag_name: &str,
+    body: &str,
+    draft: Option<bool>,
+    prerelease: Option<bool>,
+    github_state: state::GitHub,
+    github_config: &config::GitHub,
+    dry_run_stdout: DryRun,
+) -> Result<state::GitHub, Error> {
+    if let Some(stdout) = dry_run_stdout {
+        let flags = [("draft", draft), ("prerelease", prerelease)]
+            .into_iter()
+            .filter_map(|(flag, value)| value.map(|value| format!("{flag} to {value}")))
+            .join(" and ");
+        let flags = if flags.is_empty() {
+            flags
+        } else {
+            format!(", setting {flags},")
+        };
+        writeln!(
+            stdout,
+            "Would update the release on GitHub for tag {tag_name}{flags} with body:\n{body}"
+        )
+        .map_err(Error::Stdout)?;
+        return Ok(github_state);
+    }
+
+    let (token, agent) = initialize_state(github_state)?;
+    let token_header = format!("token {token}");
+    let url = format!(
+        "https://api.github.com/repos/{owner}/{repo}/releases/tags/{tag_name}",
+        owner = github_config.owner,
+        repo = github_config.repo,
+    );
+    let release: ReleaseResponse = match agent.get(&url).set("Authorization", &token_header).call()
+    {
+        Ok(response) => response.into_json().map_err(|source| Error::ApiResponse {
+            source,
+            activity: "finding the release to update",
+        })?,
+        Err(ureq::Error::Status(404, _)) => {
+            return Err(Error::NoRelease {
+                tag: tag_name.to_string(),
+            })
+        }
+        Err(source) => {
+            return Err(request_error(
+                source,
+                format!("looking for the release for tag {tag_name}"),
+                "Contents: read",
+                |err, activity| Error::ApiRequest { err, activity },
+            ))
+        }
+    };
+
+    agent
+        .patch(&release.url)
+        .set("Authorization", &token_header)
+        .send_json(UpdateReleaseInput {
+            body,
+            draft,
+            prerelease,
+        })
+        .map_err(|source| {
+            request_error(
+                source,
+                format!("updating the release for tag {tag_name}"),
+                "Contents: write",
+                |err, activity| Error::ApiRequest { err, activity },
+            )
+        })?;
+    Ok(state::GitHub::Initialized { token, agent })
+}
+
+#[derive(serde::Serialize)]
+struct UpdateReleaseInput<'a> {
+    body: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    draft: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prerelease: Option<bool>,
+}
+
+#[derive(serde::Deserialize)]
+struct ReleaseResponse {
+    url: String,
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    AppConfig(#[from] app_config::Error),
+    #[error("There is no release on GitHub for tag {tag}")]
+    #[diagnostic(
+        code(github::no_release),
+        help("UpdateRelease only changes existing releases, use the Release step to create one."),
+        url("https://knope.tech/reference/config-file/steps/update-release/")
+    )]
+    NoRelease { tag: String },
+    #[error("Trouble communicating with GitHub while {activity}: {err}")]
+    #[diagnostic(
+        code(github::api_request_error),
+        help(
+            "There was a problem communicating with GitHub, this may be a network issue or a permissions issue."
+        )
+    )]
+    ApiRequest { err: String, activity: String },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Permission(#[from] PermissionError),
+    #[error("Trouble decoding the response from GitHub while {activity}: {source}")]
+    #[diagnostic(
+        code(github::api_response_error),
+        help(
+            "Failure to decode a response from GitHub is probably a bug. Please report it at https://github.com/knope-dev/knope"
+        )
+    )]
+    ApiResponse {
+        source: std::io::Error,
+        activity: &'static str,
+    },
+    #[error("Could not write to stdout")]
+    Stdout(std::io::Error),
+}
@@ -0,0 +1,458 @@
+use std::{
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+use miette::Diagnostic;
+use serde::{de::DeserializeOwned, Deserialize};
+use ureq::Agent;
+
+use crate::{
+    app_config, config,
+    dry_run::DryRun,
+    integrations::{
+        git,
+        github::{initialize_state, request_error, PermissionError},
+    },
+    state,
+    workflow::Verbose,
+};
+
+/// Poll the checks & statuses of the current commit until they all pass, one fails, or `timeout`
+/// elapses. In a dry run, only report the current status once.
+pub(crate) fn wait_for_checks(
+    required: Option<&[String]>,
+    timeout: Duration,
+    poll_interval: Duration,
+    state: state::GitHub,
+    config: &config::GitHub,
+    dry_run: DryRun,
+    verbose: Verbose,
+) -> Result<state::GitHub, Error> {
+    let sha = git::head_commit_sha()?;
+    let (token, agent) = initialize_state(state)?;
+    let config::GitHub { owner, repo } = config;
+    let base_url = format!("https://api.github.com/repos/{owner}/{repo}/commits/{sha}");
+    let authorization_header = format!("Bearer {}", &token);
+    let start = Instant::now();
+
+    loop {
+        let checks = get_checks(&agent, &base_url, &authorization_header)?;
+        let summary = Summary::new(checks, required);
+        if let Some(stdout) = dry_run {
+            writeln!(stdout, "Checks for commit {sha}: {summary}").map_err(Error::Stdout)?;
+            break;
+        }
+        if summary.all_passed(required) {
+            if let Verbose::Yes = verbose {
+                println!("All checks passed for commit {sha}");
+            }
+            break;
+        }
+        if let Some(failed) = summary.failed.first() {
+            return Err(Error::CheckFailed {
+                name: failed.name.clone(),
+                url: failed.url.clone(),
+            });
+        }
+        if start.elapsed() >= timeout {
+            return Err(Error::Timeout {
+                seconds: timeout.as_secs(),
+                pending: summary.pending_names(),
+            });
+        }
+        println!("Waiting for checks: {}", summary.pending_names());
+        // Don't sleep past the timeout, the last poll happens right at it
+        sleep(poll_interval.min(timeout.saturating_sub(start.elapsed())));
+    }
+    Ok(state::GitHub::Initialized { token, agent })
+}
+
+fn get_checks(agent: &Agent, base_url: &str, auth_header: &str) -> Result<Vec<Check>, Error> {
+    let statuses = get_all_pages(
+        agent,
+        &format!("{base_url}/status"),
+        auth_header,
+        "fetching commit statuses",
+        "Commit statuses: read",
+    )?
+    .into_iter()
+    .flat_map(|status: CombinedStatus| status.statuses)
+    .map(Check::from);
+    let check_runs = get_all_pages(
+        agent,
+        &format!("{base_url}/check-runs"),
+        auth_header,
+        "fetching check runs",
+        "Checks: read",
+    )?
+    .into_iter()
+    .flat_map(|check_runs: CheckRuns| check_runs.check_runs)
+    .map(Check::from);
+    Ok(statuses.chain(check_runs).collect())
+}
+
+/// Every page of the response from `url`, following the `Link` header that GitHub paginates
+/// with.
+fn get_all_pages<T: DeserializeOwned>(
+    agent: &Agent,
+    url: &str,
+    auth_header: &str,
+    activity: &'static str,
+    permission: &'static str,
+) -> Result<Vec<T>, Error> {
+    let mut pages = Vec::new();
+    let mut request = agent.get(url).query("per_page", "100");
+    loop {
+        let response = request
+            .set("Accept", "application/vnd.github+json")
+            .set("Authorization", auth_header)
+            .call()
+            .map_err(|source| {
+                request_error(source, activity, permission, |err, activity| {
+                    Error::ApiRequest { err, activity }
+                })
+            })?;
+        let next = response
+            .header("Link")
+            .and_then(next_page_url)
+            .map(String::from);
+        pages.push(
+            response
+                .into_json()
+                .map_err(|source| Error::ApiResponse { source, activity })?,
+        );
+        let Some(next) = next else {
+            return Ok(pages);
+        };
+        request = agent.get(&next);
+    }
+}
+
+/// The URL of the next page in a `Link` header, like `<https://api.github.com/...&page=2>;
+/// rel="next", <https://api.github.com/...&page=5>; rel="last"`.
+fn next_page_url(link: &str) -> Option<&str> {
+    link.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#)
+            .then(|| url.trim().trim_start_matches('<').trim_end_matches('>'))
+    })
+}
+
+#[derive(Deserialize)]
+struct CombinedStatus {
+    statuses: Vec<CommitStatus>,
+}
+
+#[derive(Deserialize)]
+struct CommitStatus {
+    context: String,
+    state: String,
+    target_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CheckRuns {
+    check_runs: Vec<CheckRun>,
+}
+
+#[derive(Deserialize)]
+struct CheckRun {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Outcome {
+    Pending,
+    Passed,
+    Failed,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Check {
+    name: String,
+    outcome: Outcome,
+    url: Option<String>,
+}
+
+impl From<CommitStatus> for Check {
+    fn from(status: CommitStatus) -> Self {
+        let outcome = match status.state.as_str() {
+            "success" => Outcome::Passed,
+            "pending" => Outcome::Pending,
+            _ => Outcome::Failed,
+        };
+        Self {
+            name: status.context,
+            outcome,
+            url: status.target_url,
+        }
+    }
+}
+
+impl From<CheckRun> for Check {
+    fn from(run: CheckRun) -> Self {
+        let outcome = match (run.status.as_str(), run.conclusion.as_deref()) {
+            ("completed", Some("success" | "neutral" | "skipped")) => Outcome::Passed,
+            ("completed", _) => Outcome::Failed,
+            _ => Outcome::Pending,
+        };
+        Self {
+            name: run.name,
+            outcome,
+            url: run.html_url,
+        }
+    }
+}
+
+/// The checks of a commit, grouped by outcome.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Summary {
+    passed: Vec<Check>,
+    pending: Vec<Check>,
+    failed: Vec<Check>,
+}
+
+impl Summary {
+    /// Group `checks`, only considering those in `required` (if set).
+    ///
+    /// A required check which hasn't been reported yet is pending.
+    fn new(checks: Vec<Check>, required: Option<&[String]>) -> Self {
+        let mut summary = Self::default();
+        if let Some(required) = required {
+            for name in required {
+                if !checks.iter().any(|check| &check.name == name) {
+                    summary.pending.push(Check {
+                        name: name.clone(),
+                        outcome: Outcome::Pending,
+                        url: None,
+                    });
+                }
+            }
+        }
+        for check in checks {
+            if required.is_some_and(|required| !required.contains(&check.name)) {
+                continue;
+            }
+            match check.outcome {
+                Outcome::Passed => summary.passed.push(check),
+                Outcome::Pending => summary.pending.push(check),
+                Outcome::Failed => summary.failed.push(check),
+            }
+        }
+        summary
+    }
+
+    /// Whether every check passed.
+    ///
+    /// Without `required` checks, at least one check has to be reported, since checks can take a
+    /// moment to start after a push.
+    fn all_passed(&self, required: Option<&[String]>) -> bool {
+        self.pending.is_empty()
+            && self.failed.is_empty()
+            && (required.is_some() || !self.passed.is_empty())
+    }
+
+    fn pending_names(&self) -> String {
+        if self.pending.is_empty() {
+            String::from("no checks reported yet")
+        } else {
+            self.pending.iter().map(|check| &check.name).join(", ")
+        }
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} passed, {} pending, {} failed",
+            self.passed.len(),
+            self.pending.len(),
+            self.failed.len()
+        )?;
+        for check in &self.failed {
+            write!(f, "\n\tFailed: {}", check.name)?;
+        }
+        for check in &self.pending {
+            write!(f, "\n\tPending: {}", check.name)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Trouble communicating with GitHub while {activity}: {err}")]
+    #[diagnostic(
+        code(github::api_request_error),
+        help(
+            "There was a problem communicating with GitHub, this may be a network issue or a permissions issue."
+        )
+    )]
+    ApiRequest { err: String, activity: &'static str },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Permission(#[from] PermissionError),
+    #[error("Trouble decoding the response from GitHub while {activity}: {source}")]
+    #[diagnostic(
+        code(github::api_response_error),
+        help(
+            "Failure to decode a response from GitHub is probably a bug. Please report it at https://github.com/knope-dev/knope"
+        )
+    )]
+    ApiResponse {
+        source: std::io::Error,
+        activity: &'static str,
+    },
+    #[error("Check {name} failed{}", url.as_ref().map(|url| format!(": {url}")).unwrap_or_default())]
+    #[diagnostic(
+        code(github::check_failed),
+        help("Fix the failing check, then run this workflow again."),
+        url("https://knope.tech/reference/config-file/steps/wait-for-checks/")
+    )]
+    CheckFailed { name: String, url: Option<String> },
+    #[error("Timed out after {seconds} seconds waiting for checks: {pending}")]
+    #[diagnostic(
+        code(github::checks_timeout),
+        help("Increase `timeout_seconds` if your checks take longer than this."),
+        url("https://knope.tech/reference/config-file/steps/wait-for-checks/")
+    )]
+    Timeout { seconds: u64, pending: String },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    AppConfig(#[from] app_config::Error),
+    #[error("Error writing to stdout: {0}")]
+    Stdout(#[source] std::io::Error),
+}
+
+#[cfg(test)]
+mod test_summary {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn check(name: &str, outcome: Outcome) -> Check {
+        Check {
+            name: name.to_string(),
+            outcome,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn check_run_outcomes() {
+        let run = |status: &str, conclusion: Option<&str>| {
+            Check::from(CheckRun {
+                name: "build".to_string(),
+                status: status.to_string(),
+                conclusion: conclusion.map(String::from),
+                html_url: None,
+            })
+            .outcome
+        };
+        assert_eq!(run("queued", None), Outcome::Pending);
+        assert_eq!(run("in_progress", None), Outcome::Pending);
+        assert_eq!(run("completed", Some("success")), Outcome::Passed);
+        assert_eq!(run("completed", Some("skipped")), Outcome::Passed);
+        assert_eq!(run("completed", Some("failure")), Outcome::Failed);
+        assert_eq!(run("completed", Some("cancelled")), Outcome::Failed);
+    }
+
+    #[test]
+    fn all_checks() {
+        let summary = Summary::new(
+            vec![
+                check("build", Outcome::Passed),
+                check("test", Outcome::Pending),
+                check("lint", Outcome::Failed),
+            ],
+            None,
+        );
+        assert_eq!(
+            summary,
+            Summary {
+                passed: vec![check("build", Outcome::Passed)],
+                pending: vec![check("test", Outcome::Pending)],
+                failed: vec![check("lint", Outcome::Failed)],
+            }
+        );
+    }
+
+    #[test]
+    fn no_checks_reported_yet() {
+        let summary = Summary::new(Vec::new(), None);
+        assert!(!summary.all_passed(None));
+        assert_eq!(summary.pending_names(), "no checks reported yet");
+    }
+
+    #[test]
+    fn all_passed() {
+        let summary = Summary::new(vec![check("build", Outcome::Passed)], None);
+        assert!(summary.all_passed(None));
+        let summary = Summary::new(
+            vec![
+                check("build", Outcome::Passed),
+                check("lint", Outcome::Failed),
+            ],
+            None,
+        );
+        assert!(!summary.all_passed(None));
+    }
+
+    #[test]
+    fn check_failed_without_url() {
+        let err = Error::CheckFailed {
+            name: "build".to_string(),
+            url: None,
+        };
+        assert_eq!(err.to_string(), "Check build failed");
+        let err = Error::CheckFailed {
+            name: "build".to_string(),
+            url: Some("https://example.com".to_string()),
+        };
+        assert_eq!(err.to_string(), "Check build failed: https://example.com");
+    }
+
+    #[test]
+    fn next_page() {
+        let link = r#"<https://api.github.com/repositories/1/commits/abc/check-runs?per_page=100&page=2>; rel="next", <https://api.github.com/repositories/1/commits/abc/check-runs?per_page=100&page=3>; rel="last""#;
+        assert_eq!(
+            next_page_url(link),
+            Some(
+                "https://api.github.com/repositories/1/commits/abc/check-runs?per_page=100&page=2"
+            )
+        );
+        let last_page = r#"<https://api.github.com/repositories/1/commits/abc/check-runs?per_page=100&page=1>; rel="first", <https://api.github.com/repositories/1/commits/abc/check-runs?per_page=100&page=2>; rel="prev""#;
+        assert_eq!(next_page_url(last_page), None);
+    }
+
+    #[test]
+    fn only_required_checks() {
+        let required = ["build".to_string(), "deploy-preview".to_string()];
+        let summary = Summary::new(
+            vec![
+                check("build", Outcome::Passed),
+                check("lint", Outcome::Failed),
+            ],
+            Some(&required),
+        );
+        assert_eq!(
+            summary,
+            Summary {
+                passed: vec![check("build", Outcome::Passed)],
+                pending: vec![check("deploy-preview", Outcome::Pending)],
+                failed: vec![],
+            }
+        );
+    }
+}
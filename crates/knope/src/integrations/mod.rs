@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 pub mod git;
 pub mod gitea;
 pub mod github;
+pub(crate) mod registry;
 
 #[derive(Deserialize)]
 struct PullRequest {
@@ -53,9 +54,16 @@ struct CreateReleaseResponse {
 struct ResponseIssue {
     number: usize,
     title: String,
+    #[serde(default)]
+    labels: Vec<ResponseLabel>,
 }
 
-fn ureq_err_to_string(err: ureq::Error) -> String {
+#[derive(serde::Deserialize)]
+struct ResponseLabel {
+    name: String,
+}
+
+pub(crate) fn ureq_err_to_string(err: ureq::Error) -> String {
     match err {
         ureq::Error::Status(code, response) => {
             format!("{}: {}", code, response.into_string().unwrap_or_default())
@@ -0,0 +1,108 @@
+use std::{collections::HashMap, fmt, fmt::Display, str::FromStr};
+
+use knope_versioning::Version;
+use miette::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::integrations::ureq_err_to_string;
+
+/// crates.io rejects requests without a `User-Agent` that identifies the client.
+const USER_AGENT: &str = concat!("knope/", env!("CARGO_PKG_VERSION"), " (https://knope.tech)");
+
+/// A package registry which versions can be published to.
+#[derive(Clone, Copy, Debug, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+pub(crate) enum Registry {
+    #[serde(rename = "crates.io")]
+    CratesIo,
+    #[serde(rename = "npm")]
+    Npm,
+}
+
+impl Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CratesIo => f.write_str("crates.io"),
+            Self::Npm => f.write_str("npm"),
+        }
+    }
+}
+
+impl Registry {
+    /// The highest version of the package `name` published to this registry, or `None` if it was
+    /// never published.
+    ///
+    /// Published versions which aren't valid for Knope (like `1.0.0-beta`) are ignored.
+    pub(crate) fn latest_version(self, name: &str) -> Result<Option<Version>, Error> {
+        let url = match self {
+            Self::CratesIo => format!("https://crates.io/api/v1/crates/{name}"),
+            Self::Npm => format!("https://registry.npmjs.org/{name}"),
+        };
+        let response = match ureq::get(&url).set("User-Agent", USER_AGENT).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(source) => {
+                return Err(Error::Request {
+                    registry: self,
+                    err: ureq_err_to_string(source),
+                })
+            }
+        };
+        let published = match self {
+            Self::CratesIo => response.into_json::<CratesIoResponse>().map(|response| {
+                response
+                    .versions
+                    .into_iter()
+                    .map(|version| version.num)
+                    .collect::<Vec<_>>()
+            }),
+            Self::Npm => response
+                .into_json::<NpmResponse>()
+                .map(|response| response.versions.into_keys().collect()),
+        }
+        .map_err(|source| Error::Response {
+            registry: self,
+            source,
+        })?;
+        Ok(published
+            .iter()
+            .filter_map(|version| Version::from_str(version).ok())
+            .max())
+    }
+}
+
+#[derive(Deserialize)]
+struct CratesIoResponse {
+    versions: Vec<CratesIoVersion>,
+}
+
+#[derive(Deserialize)]
+struct CratesIoVersion {
+    num: String,
+}
+
+#[derive(Deserialize)]
+struct NpmResponse {
+    versions: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("Trouble communicating with {registry}: {err}")]
+    #[diagnostic(
+        code(registry::api_request_error),
+        help("There was a problem communicating with {registry}, this may be a network issue.")
+    )]
+    Request { registry: Registry, err: String },
+    #[error("Trouble decoding the response from {registry}: {source}")]
+    #[diagnostic(
+        code(registry::api_response_error),
+        help(
+            "Failure to decode a response from {registry} is probably a bug. Please report it at https://github.com/knope-dev/knope"
+        )
+    )]
+    Response {
+        registry: Registry,
+        source: std::io::Error,
+    },
+}
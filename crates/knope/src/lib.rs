@@ -1,4 +1,12 @@
-use std::{io::stdout, str::FromStr};
+use std::{
+    env,
+    ffi::OsString,
+    fs::File,
+    io::{stdout, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+};
 
 use clap::{arg, command, value_parser, Arg, ArgAction, ArgMatches, Command};
 use itertools::Itertools;
@@ -10,7 +18,7 @@ use crate::{
     integrations::git::all_tags_on_branch,
     state::{RunType, State},
     step::{
-        releases::{Package, PackageName},
+        releases::{ChangeFileArgs, Package, PackageName},
         Step,
     },
     workflow::{Verbose, Workflow},
@@ -21,6 +29,7 @@ mod config;
 mod dry_run;
 mod fs;
 mod integrations;
+mod logging;
 mod prompt;
 mod state;
 mod step;
@@ -35,10 +44,29 @@ mod workflow;
 /// 2. `knope.toml` not valid
 /// 3. Selected workflow not found
 /// 4. Passthrough errors of selected workflow
-pub fn run() -> Result<()> {
-    let config = Config::load()?;
+pub fn run() -> Result<ExitCode> {
+    logging::init();
+    if print_schema(env::args_os()) {
+        // Printed before loading the config, so it can help fix an invalid one
+        println!("{}", config::schema());
+        return Ok(ExitCode::SUCCESS);
+    }
+    let config_path = config_path(env::args_os());
+    // Paths from arguments are relative to where Knope was run, even after moving to the project root
+    let original_dir = env::current_dir().unwrap_or_default();
+    if config_path.is_none() {
+        // An explicit config path is relative to the current directory, so don't move
+        Config::change_to_project_root()?;
+    }
+    let config = Config::load(config_path.as_deref())?;
 
     let mut matches = build_cli(&config).get_matches();
+    if let Some(format) = matches
+        .get_one::<String>(LOG_FORMAT)
+        .and_then(|format| format.parse().ok())
+    {
+        logging::set_format(format);
+    }
 
     let mut config = config.into_inner();
     let verbose = matches.get_flag(VERBOSE).into();
@@ -46,37 +74,59 @@ pub fn run() -> Result<()> {
     if let Ok(Some(true)) = matches.try_get_one("generate") {
         println!("Generating a knope.toml file");
         let config = config::generate()?;
-        return config.write_out();
+        config.write_out(config_path.as_deref())?;
+        return Ok(ExitCode::SUCCESS);
     }
 
     if let Ok(Some(true)) = matches.try_get_one("upgrade") {
         // If adding new upgrade, make a function to detect and call here.
         let upgraded = false;
-        return if upgraded {
-            config.write_out()
+        if upgraded {
+            config.write_out(config_path.as_deref())?;
         } else {
             println!("Nothing to upgrade");
-            Ok(())
-        };
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if matches.get_flag(LIST) {
+        list_workflows(&config.workflows);
+        return Ok(ExitCode::SUCCESS);
     }
 
     let (subcommand, mut sub_matches) = matches.remove_subcommand().unzip();
+    let subcommand = subcommand.or_else(|| config.default_workflow.take());
 
-    sub_matches.as_ref().and_then(|matches| {
-        matches
-            .try_get_one::<String>("prerelease-label")
-            .ok()
-            .flatten()
-            .map(|prerelease_label| {
-                config.set_prerelease_label(prerelease_label);
-            })
-    });
+    if let Some(sub_matches) = sub_matches.as_ref() {
+        apply_prepare_release_args(&mut config, sub_matches);
+        apply_create_change_file_args(&mut config, sub_matches);
+    }
 
-    let (state, workflows) = create_state(config, sub_matches.as_mut(), verbose)?;
+    let no_release_exit_code = sub_matches
+        .as_ref()
+        .and_then(|matches| {
+            matches
+                .try_get_one::<u8>(NO_RELEASE_EXIT_CODE)
+                .ok()
+                .flatten()
+        })
+        .copied();
+
+    let package_filter = matches.try_remove_one::<String>(PACKAGE).ok().flatten();
+    let (mut state, workflows) = create_state(
+        config,
+        sub_matches.as_mut(),
+        package_filter.as_deref(),
+        verbose,
+    )?;
+    state.output_file = sub_matches
+        .as_ref()
+        .and_then(|matches| matches.try_get_one::<PathBuf>(OUTPUT_FILE).ok().flatten())
+        .map(|path| original_dir.join(path));
 
     if let Ok(Some(true)) = matches.try_get_one("validate") {
         workflow::validate(workflows, state)?;
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     let subcommand = subcommand.ok_or_else(|| {
@@ -87,23 +137,76 @@ pub fn run() -> Result<()> {
         .find(|w| w.name == subcommand)
         .ok_or_else(|| miette!("No workflow named {}", subcommand))?;
 
+    if matches.get_flag(PLAN) {
+        let mut plan = String::new();
+        let result = workflow::plan(workflow, state, &mut plan);
+        print!("{plan}");
+        return result.map(|()| ExitCode::SUCCESS).map_err(Into::into);
+    }
+
     let state = if matches.get_flag("dry-run") {
         RunType::DryRun {
             state,
-            stdout: Box::new(stdout()),
+            stdout: dry_run_output(&matches, &original_dir)?,
         }
     } else {
         RunType::Real(state)
     };
 
-    workflow::run(workflow, state)?;
-    Ok(())
+    match workflow::run(workflow, state) {
+        Ok(()) => Ok(ExitCode::SUCCESS),
+        Err(err) => match no_release_exit_code {
+            Some(code) if err.is_no_release() => {
+                println!("No packages are ready to release");
+                Ok(ExitCode::from(code))
+            }
+            _ => Err(err.into()),
+        },
+    }
 }
 
 const OVERRIDE_ONE_VERSION: &str = "override-one-version";
 const OVERRIDE_MULTIPLE_VERSIONS: &str = "override-multiple-versions";
 const PRERELEASE_LABEL: &str = "prerelease-label";
+const NO_RELEASE_EXIT_CODE: &str = "no-release-exit-code";
+const KEEP_CHANGE_FILES: &str = "keep-change-files";
+const OUTPUT_FILE: &str = "output-file";
+const SINCE: &str = "since";
+const CHANGE: &str = "change";
+const SUMMARY: &str = "summary";
 const VERBOSE: &str = "verbose";
+const PACKAGE: &str = "package";
+const LIST: &str = "list";
+const LOG_FORMAT: &str = "log-format";
+const CONFIG: &str = "config";
+const DRY_RUN_OUTPUT: &str = "dry-run-output";
+const PLAN: &str = "plan";
+const PRINT_SCHEMA: &str = "print-schema";
+
+/// The `--config` argument, which has to be found before the rest of the CLI (which depends on the
+/// config) can be built.
+fn config_path(args: impl IntoIterator<Item = OsString>) -> Option<PathBuf> {
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        } else if let Some(path) = arg.to_str().and_then(|arg| arg.strip_prefix("--config=")) {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Whether the `--print-schema` argument was passed, which (like `--config`) is checked before
+/// the config is loaded.
+fn print_schema(args: impl IntoIterator<Item = OsString>) -> bool {
+    args.into_iter()
+        .skip(1)
+        .take_while(|arg| arg != "--")
+        .any(|arg| arg == "--print-schema")
+}
 
 fn build_cli(config: &ConfigSource) -> Command {
     let mut command = command!()
@@ -113,11 +216,42 @@ fn build_cli(config: &ConfigSource) -> Command {
                 .help("Pretend to run a workflow, outputting what _would_ happen without actually doing it.")
                 .action(ArgAction::SetTrue)
                 .global(true)
+        ).arg(
+            Arg::new(PLAN).long(PLAN)
+                .help("Print a plan of what every step of the workflow would do, without doing it.")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("dry-run")
+                .global(true)
+        ).arg(
+            Arg::new(DRY_RUN_OUTPUT).long(DRY_RUN_OUTPUT)
+                .help("Write the output of `--dry-run` to this file instead of stdout.")
+                .value_parser(value_parser!(PathBuf))
+                .requires("dry-run")
+                .global(true)
         ).arg(
         Arg::new(VERBOSE).long(VERBOSE).short('v')
             .help("Print extra information (for debugging)")
             .action(ArgAction::SetTrue)
             .global(true)
+    ).arg(
+        Arg::new(LIST).long(LIST)
+            .help("List the available workflows.")
+            .action(ArgAction::SetTrue)
+    ).arg(
+        Arg::new(LOG_FORMAT).long(LOG_FORMAT)
+            .help("The format of logs enabled by RUST_LOG.")
+            .value_parser(logging::LogFormat::VALUES)
+            .env(logging::LOG_FORMAT_ENV)
+            .global(true)
+    ).arg(
+        Arg::new(CONFIG).long(CONFIG)
+            .help("Use this config file instead of `knope.toml`.")
+            .value_parser(value_parser!(PathBuf))
+            .global(true)
+    ).arg(
+        Arg::new(PRINT_SCHEMA).long(PRINT_SCHEMA)
+            .help("Print the JSON Schema of `knope.toml`.")
+            .action(ArgAction::SetTrue)
     );
     let config = match config {
         ConfigSource::Default(config) => {
@@ -132,19 +266,16 @@ fn build_cli(config: &ConfigSource) -> Command {
         }
     };
 
-    let version_override_arg = if config.packages.is_empty() {
-        None
-    } else if config.packages.len() == 1 {
-        Some(Arg::new(OVERRIDE_ONE_VERSION)
-            .long("override-version")
-            .help("Override the version set by `BumpVersion` or `PrepareRelease` for the package.")
-            .value_parser(value_parser!(Version)))
-    } else {
-        Some(Arg::new(OVERRIDE_MULTIPLE_VERSIONS)
-            .long("override-version")
-            .help("Override the version set by `BumpVersion` or `PrepareRelease` for multiple packages. Format is like package_name=version, can be set multiple times.")
-            .action(ArgAction::Append).value_parser(value_parser!(VersionOverride)))
-    };
+    if config.packages.len() > 1 {
+        command = command.arg(
+            Arg::new(PACKAGE)
+                .long(PACKAGE)
+                .help("Only bump, write changelogs for, and release this package for the whole workflow.")
+                .global(true),
+        );
+    }
+
+    let version_override_arg = version_override_arg(config.packages.len());
 
     for workflow in &config.workflows {
         let mut subcommand = Command::new(workflow.name.clone());
@@ -165,15 +296,17 @@ fn build_cli(config: &ConfigSource) -> Command {
             if let Some(arg) = version_override_arg.clone() {
                 subcommand = subcommand.arg(arg);
             }
+            subcommand = subcommand.arg(output_file_arg());
         }
         if contains_prepare_release {
-            subcommand = subcommand
-                .arg(
-                    Arg::new(PRERELEASE_LABEL)
-                        .long("prerelease-label")
-                        .help("Set the `prerelease_label` attribute of any `PrepareRelease` steps at runtime.")
-                        .env("KNOPE_PRERELEASE_LABEL")
-                );
+            subcommand = add_prepare_release_args(subcommand);
+        }
+        if workflow
+            .steps
+            .iter()
+            .any(|step| matches!(*step, Step::CreateChangeFile { .. }))
+        {
+            subcommand = add_create_change_file_args(subcommand);
         }
 
         command = command.subcommand(subcommand);
@@ -181,23 +314,177 @@ fn build_cli(config: &ConfigSource) -> Command {
     command
 }
 
+/// The `--override-version` argument, which depends on how many packages there are.
+fn version_override_arg(package_count: usize) -> Option<Arg> {
+    if package_count == 0 {
+        None
+    } else if package_count == 1 {
+        Some(Arg::new(OVERRIDE_ONE_VERSION)
+            .long("override-version")
+            .help("Override the version set by `BumpVersion` or `PrepareRelease` for the package.")
+            .value_parser(value_parser!(Version)))
+    } else {
+        Some(Arg::new(OVERRIDE_MULTIPLE_VERSIONS)
+            .long("override-version")
+            .help("Override the version set by `BumpVersion` or `PrepareRelease` for multiple packages. Format is like package_name=version, can be set multiple times.")
+            .action(ArgAction::Append).value_parser(value_parser!(VersionOverride)))
+    }
+}
+
+/// The `--output-file` argument, for workflows which set new versions.
+fn output_file_arg() -> Arg {
+    Arg::new(OUTPUT_FILE)
+        .long(OUTPUT_FILE)
+        .help("Append the new versions to this file as lines like `version=1.2.3`, the format of `$GITHUB_OUTPUT`.")
+        .env("KNOPE_OUTPUT_FILE")
+        .value_parser(value_parser!(PathBuf))
+}
+
+/// The workflow arguments which override options of `PrepareRelease` steps.
+fn add_prepare_release_args(subcommand: Command) -> Command {
+    subcommand
+        .arg(
+            Arg::new(PRERELEASE_LABEL)
+                .long("prerelease-label")
+                .help("Set the `prerelease_label` attribute of any `PrepareRelease` steps at runtime.")
+                .env("KNOPE_PRERELEASE_LABEL")
+        )
+        .arg(
+            Arg::new(NO_RELEASE_EXIT_CODE)
+                .long(NO_RELEASE_EXIT_CODE)
+                .help("Exit with this code (instead of an error) if `PrepareRelease` finds nothing to release.")
+                .env("KNOPE_NO_RELEASE_EXIT_CODE")
+                .value_parser(value_parser!(u8))
+        )
+        .arg(
+            Arg::new(KEEP_CHANGE_FILES)
+                .long(KEEP_CHANGE_FILES)
+                .help("Leave change files in place instead of deleting them, like the `keep_change_files` option of `PrepareRelease`.")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(SINCE)
+                .long(SINCE)
+                .help("Leave out commits made before this date (like 2024-01-31), like the `since_date` option of `PrepareRelease`.")
+                .env("KNOPE_SINCE")
+        )
+}
+
+/// The workflow arguments which answer the prompts of `CreateChangeFile` steps.
+fn add_create_change_file_args(subcommand: Command) -> Command {
+    subcommand
+        .arg(
+            Arg::new(CHANGE)
+                .long(CHANGE)
+                .help("Create the change file without prompting, with this change type. Format is like package_name=change_type (or just change_type with one package), can be set multiple times.")
+                .action(ArgAction::Append)
+                .requires(SUMMARY),
+        )
+        .arg(
+            Arg::new(SUMMARY)
+                .long(SUMMARY)
+                .help("The summary of the change file created with `--change`.")
+                .requires(CHANGE),
+        )
+}
+
+/// Where `--dry-run` output goes: the `--dry-run-output` file (relative to `original_dir`), or stdout.
+fn dry_run_output(matches: &ArgMatches, original_dir: &Path) -> Result<Box<dyn Write>> {
+    let Some(path) = matches.get_one::<PathBuf>(DRY_RUN_OUTPUT) else {
+        return Ok(Box::new(stdout()));
+    };
+    let path = original_dir.join(path);
+    let file = File::create(&path).map_err(|source| fs::Error::Write { path, source })?;
+    Ok(Box::new(file))
+}
+
+/// Apply the workflow arguments which override options of `PrepareRelease` steps.
+fn apply_prepare_release_args(config: &mut Config, sub_matches: &ArgMatches) {
+    if let Ok(Some(prerelease_label)) = sub_matches.try_get_one::<String>(PRERELEASE_LABEL) {
+        config.set_prerelease_label(prerelease_label);
+    }
+    if let Ok(Some(true)) = sub_matches.try_get_one::<bool>(KEEP_CHANGE_FILES) {
+        config.set_keep_change_files();
+    }
+    if let Ok(Some(since_date)) = sub_matches.try_get_one::<String>(SINCE) {
+        config.set_since_date(since_date);
+    }
+}
+
+/// Apply the workflow arguments which answer the prompts of `CreateChangeFile` steps.
+fn apply_create_change_file_args(config: &mut Config, sub_matches: &ArgMatches) {
+    let Ok(Some(summary)) = sub_matches.try_get_one::<String>(SUMMARY) else {
+        return;
+    };
+    let changes = sub_matches
+        .try_get_many::<String>(CHANGE)
+        .ok()
+        .flatten()
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+    config.set_change_file_args(&ChangeFileArgs {
+        changes,
+        summary: summary.clone(),
+    });
+}
+
+/// Print the name of every workflow (and its help text, if any) to stdout.
+fn list_workflows(workflows: &[Workflow]) {
+    let width = workflows
+        .iter()
+        .map(|workflow| workflow.name.len())
+        .max()
+        .unwrap_or_default();
+    for workflow in workflows {
+        match &workflow.help_text {
+            Some(help_text) => println!("{:width$}  {help_text}", workflow.name),
+            None => println!("{}", workflow.name),
+        }
+    }
+}
+
 fn create_state(
     config: Config,
     mut sub_matches: Option<&mut ArgMatches>,
+    package_filter: Option<&str>,
     verbose: Verbose,
 ) -> Result<(State, Vec<Workflow>)> {
     let Config {
-        packages,
+        mut packages,
         workflows,
+        default_workflow: _,
         jira,
         github,
         gitea,
+        git,
+        change_directory,
     } = config;
+    if let Some(package_filter) = package_filter {
+        let available = packages
+            .iter()
+            .filter_map(|package| package.name.as_ref())
+            .join(", ");
+        packages.retain(|package| {
+            package
+                .name
+                .as_ref()
+                .is_some_and(|name| &**name == package_filter)
+        });
+        if packages.is_empty() {
+            return Err(miette!(
+                help = format!("The available packages are: {available}"),
+                "Unknown package {package_filter}"
+            ));
+        }
+    }
+    let git_config = config::Git::with_env(git);
     let git_tags = if packages.is_empty() {
         // Don't mess with Git if there aren't any packages defined
         Vec::new()
     } else {
-        all_tags_on_branch(verbose).unwrap_or_default()
+        all_tags_on_branch(git_config.notes_ref.as_deref(), verbose).unwrap_or_default()
     };
     let mut packages = Package::load(packages, &git_tags, verbose)?;
     if let Some(version_override) = sub_matches
@@ -230,11 +517,9 @@ fn create_state(
                 })
                 .map(|(index, _)| index);
 
-            let version = override_index
-                .map(|index| overrides.remove(index))
-                .map(|version_override| version_override.version);
-
-            package.override_version = version;
+            if let Some(version_override) = override_index.map(|index| overrides.remove(index)) {
+                package.override_version = Some(version_override.version);
+            }
         }
         if !overrides.is_empty() {
             return Err(miette!(
@@ -248,7 +533,10 @@ fn create_state(
         }
     }
 
-    let state = State::new(jira, github, gitea, packages, git_tags, verbose);
+    let mut state = State::new(jira, github, gitea, git_config, packages, git_tags, verbose);
+    if let Some(change_directory) = change_directory {
+        state.change_directory = change_directory;
+    }
     Ok((state, workflows))
 }
 
@@ -282,4 +570,30 @@ mod tests {
     fn verify_app() {
         build_cli(&ConfigSource::Default(config::generate().unwrap())).debug_assert();
     }
+
+    #[test]
+    fn config_path_before_and_after_workflow() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(
+            config_path(args(&["knope", "--config", "ci.toml", "release"])),
+            Some(PathBuf::from("ci.toml"))
+        );
+        assert_eq!(
+            config_path(args(&["knope", "release", "--config=ci.toml"])),
+            Some(PathBuf::from("ci.toml"))
+        );
+        assert_eq!(config_path(args(&["knope", "release"])), None);
+    }
+
+    #[test]
+    fn print_schema_only_before_separator() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert!(print_schema(args(&["knope", "--print-schema"])));
+        assert!(!print_schema(args(&[
+            "knope",
+            "release",
+            "--",
+            "--print-schema"
+        ])));
+    }
 }
@@ -0,0 +1,133 @@
+use std::{
+    env::var,
+    io::Write,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use log::LevelFilter;
+use serde_json::json;
+
+/// The environment variable which can set the log format instead of `--log-format`.
+pub(crate) const LOG_FORMAT_ENV: &str = "KNOPE_LOG_FORMAT";
+
+static JSON: AtomicBool = AtomicBool::new(false);
+static CONTEXT: Mutex<Context> = Mutex::new(Context {
+    workflow: None,
+    step: None,
+});
+
+/// How log lines (enabled with `RUST_LOG`) are printed.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum LogFormat {
+    /// The default `env_logger` output, meant for people.
+    #[default]
+    Human,
+    /// One JSON object per line, meant for log aggregators.
+    Json,
+}
+
+impl LogFormat {
+    pub(crate) const VALUES: [&'static str; 2] = ["human", "json"];
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The workflow and step currently running, included in JSON logs.
+struct Context {
+    workflow: Option<String>,
+    step: Option<&'static str>,
+}
+
+/// Start logging warnings (or whatever `RUST_LOG` enables), in the format from [`LOG_FORMAT_ENV`]
+/// until [`set_format`] is called with the command line argument.
+pub(crate) fn init() {
+    if let Some(format) = var(LOG_FORMAT_ENV)
+        .ok()
+        .and_then(|format| format.parse().ok())
+    {
+        set_format(format);
+    }
+    let human = builder().build();
+    let json = builder()
+        .format(|buf, record| {
+            let (workflow, step) = CONTEXT.lock().map_or((None, None), |context| {
+                (context.workflow.clone(), context.step)
+            });
+            let line = json!({
+                "timestamp": buf.timestamp().to_string(),
+                "level": record.level().as_str(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+                "workflow": workflow,
+                "step": step,
+            });
+            writeln!(buf, "{line}")
+        })
+        .build();
+    log::set_max_level(human.filter());
+    // If a logger was already set, there's nothing more to do.
+    log::set_boxed_logger(Box::new(Logger { human, json })).ok();
+}
+
+/// Warnings are logged even without `RUST_LOG`, which takes precedence when it's set.
+fn builder() -> env_logger::Builder {
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(LevelFilter::Warn).parse_default_env();
+    builder
+}
+
+/// Switches between the two formats based on [`set_format`].
+struct Logger {
+    human: env_logger::Logger,
+    json: env_logger::Logger,
+}
+
+impl Logger {
+    fn current(&self) -> &env_logger::Logger {
+        if JSON.load(Ordering::Relaxed) {
+            &self.json
+        } else {
+            &self.human
+        }
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.current().enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.current().log(record);
+    }
+
+    fn flush(&self) {
+        self.current().flush();
+    }
+}
+
+pub(crate) fn set_format(format: LogFormat) {
+    JSON.store(format == LogFormat::Json, Ordering::Relaxed);
+}
+
+/// Record which workflow and step are running, for JSON logs.
+pub(crate) fn set_context(workflow: Option<&str>, step: Option<&'static str>) {
+    if let Ok(mut context) = CONTEXT.lock() {
+        context.workflow = workflow.map(String::from);
+        context.step = step;
+    }
+}
@@ -1,11 +1,8 @@
-use std::env::var;
+use std::process::ExitCode;
 
 use knope::run;
 use miette::Result;
 
-fn main() -> Result<()> {
-    if var("RUST_LOG").is_ok() {
-        env_logger::init();
-    }
+fn main() -> Result<ExitCode> {
     run()
 }
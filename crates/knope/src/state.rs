@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::{io::Write, path::PathBuf};
 
 use crate::{
     config,
@@ -14,10 +14,25 @@ pub(crate) struct State {
     pub(crate) gitea: Gitea,
     pub(crate) gitea_config: Option<config::Gitea>,
     pub(crate) github_config: Option<config::GitHub>,
+    /// The identity to use for Git commits and tags, already merged with environment variables
+    pub(crate) git_config: config::Git,
     pub(crate) issue: Issue,
     pub(crate) packages: Vec<releases::Package>,
     pub(crate) verbose: Verbose,
     pub(crate) all_git_tags: Vec<String>,
+    /// The names of the packages released by [`crate::step::Step::PrepareRelease`].
+    pub(crate) released_packages: Vec<String>,
+    /// The packages released by [`crate::step::Step::Release`], each with the release it created.
+    pub(crate) released: Vec<releases::Package>,
+    /// The branch created by [`crate::step::Step::CreateReleaseBranch`].
+    pub(crate) release_branch: Option<String>,
+    /// The number of the GitHub milestone created (or found) by
+    /// [`crate::step::Step::CreateMilestone`].
+    pub(crate) milestone: Option<u64>,
+    /// Where `BumpVersion` and `PrepareRelease` append the new versions, from `--output-file`
+    pub(crate) output_file: Option<PathBuf>,
+    /// Where change files are created and read from, from `change_directory` in `knope.toml`
+    pub(crate) change_directory: PathBuf,
 }
 
 impl State {
@@ -26,6 +41,7 @@ impl State {
         jira_config: Option<config::Jira>,
         github_config: Option<config::GitHub>,
         gitea_config: Option<config::Gitea>,
+        git_config: config::Git,
         packages: Vec<releases::Package>,
         all_git_tags: Vec<String>,
         verbose: Verbose,
@@ -36,10 +52,17 @@ impl State {
             gitea_config,
             github: GitHub::New,
             github_config,
+            git_config,
             issue: Issue::Initial,
             packages,
             verbose,
             all_git_tags,
+            released_packages: Vec::new(),
+            released: Vec::new(),
+            release_branch: None,
+            milestone: None,
+            output_file: None,
+            change_directory: PathBuf::from(releases::DEFAULT_CHANGE_DIRECTORY),
         }
     }
 }
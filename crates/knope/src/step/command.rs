@@ -1,5 +1,7 @@
 use indexmap::IndexMap;
 use miette::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     variables,
@@ -7,14 +9,33 @@ use crate::{
     RunType,
 };
 
+/// The inner content of a [`super::Step::Command`] step.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct RunCommand {
+    /// The command to run, with any variable keys you wish to replace.
+    pub(crate) command: String,
+    /// A map of value-to-replace to [Variable][`crate::command::Variable`] to replace
+    /// it with.
+    pub(crate) variables: Option<IndexMap<String, Variable>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Whether to run the command in the platform's shell or not
+    pub(crate) shell: Option<bool>,
+    /// If set to true, any Git commits the command makes use the identity from `[git]`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) use_git_identity: bool,
+}
+
 /// Run the command string `command` in the current shell after replacing the keys of `variables`
 /// with the values that the [`Variable`]s represent.
-pub(crate) fn run_command(
-    mut run_type: RunType,
-    mut command: String,
-    shell: bool,
-    variables: Option<IndexMap<String, Variable>>,
-) -> Result<RunType, Error> {
+///
+/// If `use_git_identity` is set, Git commits made by the command use the identity from `[git]`.
+pub(crate) fn run_command(mut run_type: RunType, command: RunCommand) -> Result<RunType, Error> {
+    let RunCommand {
+        mut command,
+        variables,
+        shell,
+        use_git_identity,
+    } = command;
     let (state, dry_run_stdout) = match &mut run_type {
         RunType::DryRun { state, stdout } => (state, Some(stdout)),
         RunType::Real(state) => (state, None),
@@ -32,11 +53,15 @@ pub(crate) fn run_command(
         writeln!(stdout, "Would run {command}")?;
         return Ok(run_type);
     }
-    let status = if shell {
-        execute::shell(command).status()?
+    let mut command = if shell.is_some_and(|it| it) {
+        execute::shell(command)
     } else {
-        execute::command(command).status()?
+        execute::command(command)
     };
+    if use_git_identity {
+        command.envs(state.git_config.commit_env_vars());
+    }
+    let status = command.status()?;
     if status.success() {
         return Ok(run_type);
     }
@@ -64,7 +89,7 @@ pub(crate) enum Error {
 mod test_run_command {
 
     use super::*;
-    use crate::{workflow::Verbose, State};
+    use crate::{config::Git, workflow::Verbose, State};
 
     #[test]
     fn test() {
@@ -74,13 +99,17 @@ mod test_run_command {
                 None,
                 None,
                 None,
+                Git::default(),
                 Vec::new(),
                 Vec::new(),
                 Verbose::No,
             )),
-            command.to_string(),
-            false,
-            None,
+            RunCommand {
+                command: command.to_string(),
+                variables: None,
+                shell: None,
+                use_git_identity: false,
+            },
         );
 
         assert!(result.is_ok());
@@ -90,13 +119,17 @@ mod test_run_command {
                 None,
                 None,
                 None,
+                Git::default(),
                 Vec::new(),
                 Vec::new(),
                 Verbose::No,
             )),
-            String::from("exit 1"),
-            false,
-            None,
+            RunCommand {
+                command: String::from("exit 1"),
+                variables: None,
+                shell: None,
+                use_git_identity: false,
+            },
         );
         assert!(result.is_err());
     }
@@ -0,0 +1,48 @@
+use miette::Diagnostic;
+
+use crate::{
+    integrations::github,
+    state::RunType,
+    variables,
+    variables::{replace_variables, Template},
+};
+
+pub(super) fn run(
+    category: &str,
+    title: Template,
+    body: Template,
+    run_type: RunType,
+) -> Result<RunType, Error> {
+    let (mut state, mut dry_run) = run_type.decompose();
+    let title = replace_variables(title, &state)?;
+    let body = replace_variables(body, &state)?;
+    let Some(github_config) = &state.github_config else {
+        return Err(Error::NotConfigured);
+    };
+    state.github = github::create_discussion(
+        category,
+        &title,
+        &body,
+        state.github,
+        github_config,
+        &mut dry_run,
+    )?;
+    Ok(RunType::recompose(state, dry_run))
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Variables(#[from] variables::Error),
+    #[error("GitHub is not configured")]
+    #[diagnostic(
+        code(create_discussion::github::not_configured),
+        help("GitHub must be configured in order to use the CreateDiscussion step"),
+        url("https://knope.tech/reference/config-file/github/")
+    )]
+    NotConfigured,
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    GitHub(#[from] github::CreateDiscussionError),
+}
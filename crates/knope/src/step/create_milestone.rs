@@ -0,0 +1,61 @@
+use miette::Diagnostic;
+
+use crate::{integrations::github, state::RunType, step::releases::package};
+
+pub(super) fn run(close_previous: bool, run_type: RunType) -> Result<RunType, Error> {
+    let (mut state, mut dry_run) = run_type.decompose();
+    let Some(github_config) = &state.github_config else {
+        return Err(Error::NotConfigured);
+    };
+    let package = match state.packages.as_slice() {
+        [package] => package,
+        [] => return Err(package::Error::NoDefinedPackages.into()),
+        _ => return Err(Error::TooManyPackages),
+    };
+    let version = &package
+        .prepared_release
+        .as_ref()
+        .ok_or(Error::NoPreparedRelease)?
+        .version;
+    let (milestone, github) = github::create_milestone(
+        version,
+        close_previous,
+        state.github,
+        github_config,
+        &mut dry_run,
+    )?;
+    state.github = github;
+    state.milestone = milestone;
+    Ok(RunType::recompose(state, dry_run))
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("GitHub is not configured")]
+    #[diagnostic(
+        code(create_milestone::github::not_configured),
+        help("GitHub must be configured in order to use the CreateMilestone step"),
+        url("https://knope.tech/reference/config-file/github/")
+    )]
+    NotConfigured,
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Package(#[from] package::Error),
+    #[error("Too many packages defined")]
+    #[diagnostic(
+        code(create_milestone::too_many_packages),
+        help("CreateMilestone names the milestone for the version of a single [package]."),
+        url("https://knope.tech/reference/config-file/steps/create-milestone/")
+    )]
+    TooManyPackages,
+    #[error("There is no prepared release to create a milestone for")]
+    #[diagnostic(
+        code(create_milestone::no_prepared_release),
+        help("Run PrepareRelease before CreateMilestone, which names the milestone for the new version."),
+        url("https://knope.tech/reference/config-file/steps/create-milestone/")
+    )]
+    NoPreparedRelease,
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    GitHub(#[from] github::CreateMilestoneError),
+}
@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use miette::Diagnostic;
+
+use crate::{
+    fs,
+    integrations::git,
+    state::RunType,
+    step::releases::{self, package},
+};
+
+/// Replaced with the next version in the branch name.
+const VERSION_PLACEHOLDER: &str = "$version";
+
+/// The branch name used when none is configured.
+const DEFAULT_BRANCH: &str = "release/$version";
+
+pub(super) fn run(branch: Option<&str>, run_type: RunType) -> Result<RunType, Error> {
+    let (mut state, mut dry_run) = run_type.decompose();
+    let branch = branch.unwrap_or(DEFAULT_BRANCH);
+    let branch = if branch.contains(VERSION_PLACEHOLDER) {
+        let package = match state.packages.as_slice() {
+            [package] => package,
+            [] => return Err(package::Error::NoDefinedPackages.into()),
+            _ => return Err(Error::TooManyPackages),
+        };
+        let version = releases::next_version(
+            package,
+            &state.all_git_tags,
+            git::ReleaseSources {
+                notes_ref: state.git_config.notes_ref.as_deref(),
+                forge_tags: &[],
+            },
+            &state.change_directory,
+            state.verbose,
+        )?
+        .ok_or(Error::NothingToRelease)?;
+        branch.replace(VERSION_PLACEHOLDER, &version.to_string())
+    } else {
+        branch.to_string()
+    };
+    if git::branch_exists(&branch)? {
+        return Err(Error::BranchExists { branch });
+    }
+
+    if let Some(stdout) = dry_run.as_mut() {
+        writeln!(stdout, "Would create and switch to the branch {branch}")
+            .map_err(fs::Error::Stdout)?;
+    } else {
+        git::create_and_switch_to_branch(&branch)?;
+        println!("Switched to a new branch {branch}");
+    }
+    state.release_branch = Some(branch);
+    Ok(RunType::recompose(state, dry_run))
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fs(#[from] fs::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Release(#[from] releases::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Package(#[from] package::Error),
+    #[error("The branch {branch} already exists")]
+    #[diagnostic(
+        code(create_release_branch::branch_exists),
+        help("Delete the branch, or finish the release on it instead of creating it again."),
+        url("https://knope.tech/reference/config-file/steps/create-release-branch/")
+    )]
+    BranchExists { branch: String },
+    #[error("There are no changes to release, so there's no version for the branch name")]
+    #[diagnostic(
+        code(create_release_branch::nothing_to_release),
+        help("Add a change file or a conventional commit, or leave `$version` out of `branch`."),
+        url("https://knope.tech/reference/config-file/steps/create-release-branch/")
+    )]
+    NothingToRelease,
+    #[error("Too many packages defined")]
+    #[diagnostic(
+        code(create_release_branch::too_many_packages),
+        help("`$version` can only be used in `branch` with a single [package]."),
+        url("https://knope.tech/reference/config-file/steps/create-release-branch/")
+    )]
+    TooManyPackages,
+}
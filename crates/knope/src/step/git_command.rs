@@ -0,0 +1,155 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    process::Stdio,
+};
+
+use indexmap::IndexMap;
+use miette::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fs,
+    integrations::git,
+    state::RunType,
+    variables,
+    variables::{replace_variables, Template, Variable},
+};
+
+/// The Git subcommands that a [`super::Step::Git`] step can run.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Subcommand {
+    Fetch,
+    Push,
+    Tag,
+    Switch,
+}
+
+impl Subcommand {
+    /// Whether the first argument of this subcommand is a remote.
+    const fn takes_remote(self) -> bool {
+        matches!(self, Self::Fetch | Self::Push)
+    }
+
+    /// Whether this subcommand can change the tags that versions are found from.
+    const fn changes_tags(self) -> bool {
+        !matches!(self, Self::Push)
+    }
+}
+
+impl Display for Subcommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fetch => write!(f, "fetch"),
+            Self::Push => write!(f, "push"),
+            Self::Tag => write!(f, "tag"),
+            Self::Switch => write!(f, "switch"),
+        }
+    }
+}
+
+/// The remote that `fetch` and `push` use when none is configured.
+const DEFAULT_REMOTE: &str = "origin";
+
+/// Run `git {subcommand}` with `args` (after replacing `variables` in each of them).
+///
+/// `fetch` and `push` are sent to `remote` (or `origin`). Afterward, the tags used to find
+/// versions are reloaded, since the command may have changed them.
+pub(super) fn run(
+    subcommand: Subcommand,
+    remote: Option<&str>,
+    args: Vec<String>,
+    variables: &IndexMap<String, Variable>,
+    run_type: RunType,
+) -> Result<RunType, Error> {
+    let (mut state, mut dry_run) = run_type.decompose();
+    let mut full_args = vec![subcommand.to_string()];
+    if subcommand.takes_remote() {
+        full_args.push(remote.unwrap_or(DEFAULT_REMOTE).to_string());
+    } else if remote.is_some() {
+        return Err(Error::RemoteNotSupported { subcommand });
+    }
+    for template in args {
+        full_args.push(replace_variables(
+            Template {
+                template,
+                variables: variables.clone(),
+            },
+            &state,
+        )?);
+    }
+
+    if let Some(stdout) = dry_run.as_mut() {
+        writeln!(stdout, "Would run git {}", full_args.join(" ")).map_err(fs::Error::Stdout)?;
+        return Ok(RunType::recompose(state, dry_run));
+    }
+
+    let mut command = std::process::Command::new("git");
+    command.args(&full_args);
+    let status = if subcommand.takes_remote() {
+        // Git's errors tell whether it couldn't authenticate, otherwise they're passed through
+        let output = command
+            .stdout(Stdio::inherit())
+            .output()
+            .map_err(|source| Error::Io { subcommand, source })?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if let (false, Some(err)) = (
+            output.status.success(),
+            git::remote_auth_error(remote.unwrap_or(DEFAULT_REMOTE), &stderr),
+        ) {
+            return Err(err.into());
+        }
+        eprint!("{stderr}");
+        output.status
+    } else {
+        command
+            .status()
+            .map_err(|source| Error::Io { subcommand, source })?
+    };
+    if !status.success() {
+        return Err(Error::Failed { subcommand, status });
+    }
+    if subcommand.changes_tags() {
+        state.all_git_tags =
+            git::all_tags_on_branch(state.git_config.notes_ref.as_deref(), state.verbose)?;
+    }
+    Ok(RunType::recompose(state, dry_run))
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("git {subcommand} does not take a remote")]
+    #[diagnostic(
+        code(git_command::remote_not_supported),
+        help("Only `fetch` and `push` use the `remote` option, put anything else in `args`."),
+        url("https://knope.tech/reference/config-file/steps/git/")
+    )]
+    RemoteNotSupported { subcommand: Subcommand },
+    #[error("git {subcommand} exited with {status}")]
+    #[diagnostic(
+        code(git_command::failed),
+        help("Try running the same Git command manually to get more information."),
+        url("https://knope.tech/reference/config-file/steps/git/")
+    )]
+    Failed {
+        subcommand: Subcommand,
+        status: std::process::ExitStatus,
+    },
+    #[error("Could not run git {subcommand}: {source}")]
+    #[diagnostic(code(git_command::io), help("Make sure Git is installed."))]
+    Io {
+        subcommand: Subcommand,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fs(#[from] fs::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Variables(#[from] variables::Error),
+}
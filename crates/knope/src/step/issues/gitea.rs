@@ -2,12 +2,12 @@ use super::Issue;
 pub(crate) use crate::integrations::gitea::ListIssuesError as Error;
 use crate::{
     integrations::gitea::{list_issues, ListIssuesError},
-    prompt,
     state::{self, RunType, State},
 };
 
 pub(crate) fn select_issue(
     labels: Option<&[String]>,
+    display: Option<&str>,
     run_type: RunType,
 ) -> Result<RunType, ListIssuesError> {
     match run_type {
@@ -36,6 +36,11 @@ pub(crate) fn select_issue(
             state.issue = state::Issue::Selected(Issue {
                 key: String::from("123"),
                 summary: String::from("Test issue"),
+                labels: Vec::new(),
+                url: state
+                    .gitea_config
+                    .as_ref()
+                    .map(|config| config.get_issue_web_url("123")),
             });
 
             Ok(RunType::DryRun { state, stdout })
@@ -44,7 +49,7 @@ pub(crate) fn select_issue(
         RunType::Real(state) => {
             let config = state.gitea_config;
             let (gitea, issues) = list_issues(&config, state.gitea, labels)?;
-            let issue = prompt::select(issues, "Select an Issue")?;
+            let issue = super::select(issues, display)?;
             println!("Selected item: {issue}");
             Ok(RunType::Real(State {
                 gitea,
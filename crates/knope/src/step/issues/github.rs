@@ -5,9 +5,7 @@ use super::Issue;
 use crate::{
     app_config,
     app_config::get_or_prompt_for_github_token,
-    config, prompt,
-    prompt::select,
-    state,
+    config, prompt, state,
     state::{RunType, State},
 };
 
@@ -17,7 +15,12 @@ query($repo: String!, $owner: String!, $labels: [String!]) {
     issues(states:OPEN, first: 30, labels: $labels) {
       nodes {
         number,
-        title
+        title,
+        labels(first: 20) {
+          nodes {
+            name
+          }
+        }
       }
     }
   }
@@ -28,9 +31,25 @@ query($repo: String!, $owner: String!, $labels: [String!]) {
 struct ResponseIssue {
     number: usize,
     title: String,
+    #[serde(default)]
+    labels: ResponseLabels,
+}
+
+#[derive(Default, serde::Deserialize)]
+struct ResponseLabels {
+    nodes: Vec<ResponseLabel>,
 }
 
-pub(crate) fn select_issue(labels: Option<&[String]>, run_type: RunType) -> Result<RunType, Error> {
+#[derive(serde::Deserialize)]
+struct ResponseLabel {
+    name: String,
+}
+
+pub(crate) fn select_issue(
+    labels: Option<&[String]>,
+    display: Option<&str>,
+    run_type: RunType,
+) -> Result<RunType, Error> {
     match run_type {
         RunType::DryRun {
             mut state,
@@ -61,13 +80,18 @@ pub(crate) fn select_issue(labels: Option<&[String]>, run_type: RunType) -> Resu
             state.issue = state::Issue::Selected(Issue {
                 key: String::from("123"),
                 summary: String::from("Test issue"),
+                labels: Vec::new(),
+                url: state
+                    .github_config
+                    .as_ref()
+                    .map(|config| config.get_issue_url("123")),
             });
             Ok(RunType::DryRun { state, stdout })
         }
         RunType::Real(state) => {
             let github_config = state.github_config.as_ref().ok_or(Error::NotConfigured)?;
             let (github, issues) = list_issues(github_config, state.github, labels)?;
-            let issue = select(issues, "Select an Issue")?;
+            let issue = super::select(issues, display)?;
             println!("Selected item : {}", &issue);
             Ok(RunType::Real(State {
                 github,
@@ -148,8 +172,15 @@ fn list_issues(
     let issues = gh_issues
         .into_iter()
         .map(|gh_issue| Issue {
+            url: Some(github_config.get_issue_url(&gh_issue.number.to_string())),
             key: gh_issue.number.to_string(),
             summary: gh_issue.title,
+            labels: gh_issue
+                .labels
+                .nodes
+                .into_iter()
+                .map(|label| label.name)
+                .collect(),
         })
         .collect();
 
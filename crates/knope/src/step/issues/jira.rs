@@ -1,4 +1,5 @@
 use base64::{prelude::BASE64_STANDARD as base64, Engine};
+use itertools::Itertools;
 use miette::Diagnostic;
 use serde::{Deserialize, Serialize};
 
@@ -7,13 +8,15 @@ use crate::{
     app_config,
     app_config::{get_or_prompt_for_email, get_or_prompt_for_jira_token},
     config::Jira,
-    prompt,
-    prompt::select,
-    state,
+    prompt, state,
     state::RunType,
 };
 
-pub(crate) fn select_issue(status: &str, run_type: RunType) -> Result<RunType, Error> {
+pub(crate) fn select_issue(
+    status: &str,
+    display: Option<&str>,
+    run_type: RunType,
+) -> Result<RunType, Error> {
     let (mut state, dry_run_stdout) = run_type.decompose();
     let jira_config = state.jira_config.as_ref().ok_or(Error::NotConfigured)?;
 
@@ -29,12 +32,14 @@ pub(crate) fn select_issue(status: &str, run_type: RunType) -> Result<RunType, E
         state.issue = state::Issue::Selected(Issue {
             key: "FAKE-123".to_string(),
             summary: "Test issue".to_string(),
+            labels: Vec::new(),
+            url: Some(jira_config.get_issue_url("FAKE-123")),
         });
         return Ok(RunType::DryRun { state, stdout });
     }
 
     let issues = get_issues(jira_config, status)?;
-    let issue = select(issues, "Select an Issue")?;
+    let issue = super::select(issues, display)?;
     println!("Selected item : {}", &issue);
     state.issue = state::Issue::Selected(issue);
     Ok(RunType::Real(state))
@@ -62,6 +67,32 @@ pub(crate) fn transition_issue(status: &str, run_type: RunType) -> Result<RunTyp
     Ok(RunType::Real(state))
 }
 
+pub(crate) fn assign_issue(account_id: Option<&str>, run_type: RunType) -> Result<RunType, Error> {
+    let (state, dry_run_stdout) = run_type.decompose();
+    let issue = match &state.issue {
+        state::Issue::Selected(issue) => issue,
+        state::Issue::Initial => return Err(Error::NoIssueSelected),
+    };
+    let jira_config = state.jira_config.as_ref().ok_or(Error::NotConfigured)?;
+    let assignee = account_id.map_or_else(
+        || String::from("the current user"),
+        |account_id| format!("account {account_id}"),
+    );
+
+    if let Some(mut stdout) = dry_run_stdout {
+        writeln!(
+            stdout,
+            "Would assign currently selected issue to {assignee}"
+        )?;
+        return Ok(RunType::DryRun { state, stdout });
+    }
+
+    run_assign(jira_config, &issue.key, account_id)?;
+    let key = &issue.key;
+    println!("{key} assigned to {assignee}");
+    Ok(RunType::Real(state))
+}
+
 #[derive(Debug, Diagnostic, thiserror::Error)]
 pub(crate) enum Error {
     #[error("Jira is not configured")]
@@ -79,18 +110,21 @@ pub(crate) enum Error {
         #[source]
         inner: Box<ureq::Error>,
     },
-    #[error("The specified transition name was not found in the Jira project")]
+    #[error("The transition {status} was not found in the Jira project")]
     #[diagnostic(
         code(issues::jira::transition),
-        help("The `transition` field in TransitionJiraIssue must correspond to a valid transition in the Jira project"),
+        help("The `status` field in TransitionJiraIssue must correspond to a valid transition for the issue, available transitions are: {}", available.join(", ")),
         url("https://knope.tech/reference/config-file/jira/")
     )]
-    Transition,
+    Transition {
+        status: String,
+        available: Vec<String>,
+    },
     #[error("No issue selected")]
     #[diagnostic(
         code(issues::jira::no_issue_selected),
         help(
-            "You must use the SelectJiraIssue step before TransitionJiraIssue in the same workflow"
+            "You must use the SelectJiraIssue step before TransitionJiraIssue or AssignJiraIssue in the same workflow"
         )
     )]
     NoIssueSelected,
@@ -105,6 +139,13 @@ pub(crate) enum Error {
 #[derive(Deserialize, Debug)]
 struct IssueFields {
     summary: String,
+    issuetype: Option<Named>,
+    status: Option<Named>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Named {
+    name: String,
 }
 
 #[derive(Deserialize, Debug)]
@@ -134,7 +175,7 @@ pub(crate) fn get_issues(jira_config: &Jira, status: &str) -> Result<Vec<Issue>,
     let url = format!("{}/rest/api/3/search", jira_config.url);
     Ok(ureq::post(&url)
         .set("Authorization", &auth)
-        .send_json(ureq::json!({"jql": jql, "fields": ["summary"]}))
+        .send_json(ureq::json!({"jql": jql, "fields": ["summary", "issuetype", "status"]}))
         .map_err(|inner| Error::Api {
             inner: Box::new(inner),
             activity: "querying for issues",
@@ -143,8 +184,14 @@ pub(crate) fn get_issues(jira_config: &Jira, status: &str) -> Result<Vec<Issue>,
         .issues
         .into_iter()
         .map(|jira_issue| Issue {
+            url: Some(jira_config.get_issue_url(&jira_issue.key)),
             key: jira_issue.key,
             summary: jira_issue.fields.summary,
+            labels: [jira_issue.fields.issuetype, jira_issue.fields.status]
+                .into_iter()
+                .flatten()
+                .map(|named| named.name)
+                .collect(),
         })
         .collect())
 }
@@ -163,11 +210,7 @@ fn run_transition(jira_config: &Jira, issue_key: &str, status: &str) -> Result<(
             activity: "getting transitions",
         })?;
     let response = response.into_json::<GetTransitionResponse>()?;
-    let transition = response
-        .transitions
-        .into_iter()
-        .find(|transition| transition.name == status)
-        .ok_or(Error::Transition)?;
+    let transition = find_transition(response.transitions, status)?;
     let _response = agent
         .post(&url)
         .set("Authorization", &auth)
@@ -179,6 +222,66 @@ fn run_transition(jira_config: &Jira, issue_key: &str, status: &str) -> Result<(
     Ok(())
 }
 
+/// Assign the issue to `account_id`, or to the user that Knope is authenticated as.
+fn run_assign(jira_config: &Jira, issue_key: &str, account_id: Option<&str>) -> Result<(), Error> {
+    let auth = get_auth()?;
+    let base_url = &jira_config.url;
+    let agent = ureq::Agent::new();
+    let account_id = if let Some(account_id) = account_id {
+        account_id.to_string()
+    } else {
+        agent
+            .get(&format!("{base_url}/rest/api/3/myself"))
+            .set("Authorization", &auth)
+            .call()
+            .map_err(|inner| Error::Api {
+                inner: Box::new(inner),
+                activity: "getting the current user",
+            })?
+            .into_json::<User>()?
+            .account_id
+    };
+    let _response = agent
+        .put(&format!("{base_url}/rest/api/3/issue/{issue_key}/assignee"))
+        .set("Authorization", &auth)
+        .send_json(ureq::json!({ "accountId": account_id }))
+        .map_err(|inner| Error::Api {
+            inner: Box::new(inner),
+            activity: "assigning issue",
+        })?;
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    account_id: String,
+}
+
+/// Find the transition named `status`, preferring an exact match but falling back to one that only
+/// differs by case or whitespace.
+fn find_transition(transitions: Vec<Transition>, status: &str) -> Result<Transition, Error> {
+    let normalize = |name: &str| name.split_whitespace().join(" ").to_lowercase();
+    let normalized_status = normalize(status);
+    let (exact, others): (Vec<_>, Vec<_>) = transitions
+        .into_iter()
+        .partition(|transition| transition.name == status);
+    if let Some(transition) = exact.into_iter().next() {
+        return Ok(transition);
+    }
+    let available = others
+        .iter()
+        .map(|transition| transition.name.clone())
+        .collect_vec();
+    others
+        .into_iter()
+        .find(|transition| normalize(&transition.name) == normalized_status)
+        .ok_or_else(|| Error::Transition {
+            status: status.to_string(),
+            available,
+        })
+}
+
 #[derive(Debug, Deserialize)]
 struct GetTransitionResponse {
     transitions: Vec<Transition>,
@@ -189,3 +292,47 @@ struct Transition {
     id: String,
     name: String,
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test_find_transition {
+    use super::*;
+
+    fn transitions() -> Vec<Transition> {
+        ["In Progress", "in progress", "Done"]
+            .into_iter()
+            .enumerate()
+            .map(|(id, name)| Transition {
+                id: id.to_string(),
+                name: name.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn exact_match_first() {
+        assert_eq!(
+            find_transition(transitions(), "in progress").unwrap().id,
+            "1"
+        );
+    }
+
+    #[test]
+    fn ignores_case_and_whitespace() {
+        assert_eq!(find_transition(transitions(), " done").unwrap().id, "2");
+        assert_eq!(
+            find_transition(transitions(), "IN  PROGRESS").unwrap().id,
+            "0"
+        );
+    }
+
+    #[test]
+    fn lists_available_transitions() {
+        let err = find_transition(transitions(), "Closed").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Transition { status, available }
+                if status == "Closed" && available == ["In Progress", "in progress", "Done"]
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::prompt;
+
 pub(crate) mod gitea;
 pub(crate) mod github;
 pub(crate) mod jira;
@@ -8,6 +10,39 @@ pub(crate) mod jira;
 pub(crate) struct Issue {
     pub(crate) key: String,
     pub(crate) summary: String,
+    /// The labels of a GitHub or Gitea issue, or the type and status of a Jira issue.
+    pub(crate) labels: Vec<String>,
+    /// Where to view the issue in a browser, unless it was only parsed from a branch name.
+    pub(crate) url: Option<String>,
+}
+
+impl Issue {
+    /// Fill in the placeholders of a `display` template (`$key`, `$summary`, `$labels`, and
+    /// `$url`) with the fields of this issue.
+    fn render(&self, display: &str) -> String {
+        let mut parts = display.split('$');
+        let mut rendered = parts.next().unwrap_or_default().to_string();
+        for part in parts {
+            let labels = self.labels.join(", ");
+            let fields = [
+                ("key", self.key.as_str()),
+                ("summary", self.summary.as_str()),
+                ("labels", labels.as_str()),
+                ("url", self.url.as_deref().unwrap_or_default()),
+            ];
+            let replaced = fields.into_iter().find_map(|(placeholder, value)| {
+                part.strip_prefix(placeholder).map(|rest| (value, rest))
+            });
+            if let Some((value, rest)) = replaced {
+                rendered.push_str(value);
+                rendered.push_str(rest);
+            } else {
+                rendered.push('$');
+                rendered.push_str(part);
+            }
+        }
+        rendered
+    }
 }
 
 impl fmt::Display for Issue {
@@ -15,3 +50,52 @@ impl fmt::Display for Issue {
         write!(f, "{}: {}", self.key, self.summary)
     }
 }
+
+/// An issue shown in the selection list with a custom template.
+struct DisplayedIssue {
+    issue: Issue,
+    text: String,
+}
+
+impl fmt::Display for DisplayedIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Prompt the user to select one of `issues`, each shown using the `display` template (if any)
+/// instead of as `key: summary`.
+fn select(issues: Vec<Issue>, display: Option<&str>) -> Result<Issue, prompt::Error> {
+    let Some(display) = display else {
+        return prompt::select(issues, "Select an Issue");
+    };
+    let issues = issues
+        .into_iter()
+        .map(|issue| DisplayedIssue {
+            text: issue.render(display),
+            issue,
+        })
+        .collect();
+    prompt::select(issues, "Select an Issue").map(|selected| selected.issue)
+}
+
+#[cfg(test)]
+mod test_render {
+    use pretty_assertions::assert_eq;
+
+    use super::Issue;
+
+    #[test]
+    fn all_placeholders() {
+        let issue = Issue {
+            key: "PROJ-12".to_string(),
+            summary: "Fix the $5 bug".to_string(),
+            labels: vec!["Bug".to_string(), "In Progress".to_string()],
+            url: Some("https://example.com/PROJ-12".to_string()),
+        };
+        assert_eq!(
+            issue.render("$key [$labels] $summary ($url) costs $$"),
+            "PROJ-12 [Bug, In Progress] Fix the $5 bug (https://example.com/PROJ-12) costs $$"
+        );
+    }
+}
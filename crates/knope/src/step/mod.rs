@@ -2,6 +2,7 @@ use indexmap::IndexMap;
 use knope_versioning::Label;
 use log::error;
 use miette::Diagnostic;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -13,13 +14,27 @@ use crate::{
 };
 
 pub mod command;
+mod create_discussion;
+mod create_milestone;
 mod create_pull_request;
+mod create_release_branch;
+mod git_command;
 pub mod issues;
 pub mod releases;
+mod require_change_file;
+mod rollback;
+mod sync_downstream;
+mod update_release;
+mod verify_branch;
+mod verify_changelog_entry;
+mod verify_registry_version;
+mod wait_for_checks;
+mod webhook;
+mod write_release_notes;
 
 /// Each variant describes an action you can take using knope, they are used when defining your
 /// [`crate::Workflow`] via whatever config format is being utilized.
-#[derive(Deserialize, Debug, Serialize)]
+#[derive(Deserialize, JsonSchema, Debug, Serialize)]
 #[serde(tag = "type")]
 pub(crate) enum Step {
     /// Search for Jira issues by status and display the list of them in the terminal.
@@ -28,18 +43,32 @@ pub(crate) enum Step {
     SelectJiraIssue {
         /// Issues with this status in Jira will be listed for the user to select.
         status: String,
+        /// How each issue is shown in the list, with `$key`, `$summary`, `$labels`, and `$url`
+        /// replaced by its fields. Defaults to `$key: $summary`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display: Option<String>,
     },
     /// Transition a Jira issue to a new status.
     TransitionJiraIssue {
         /// The status to transition the current issue to.
         status: String,
     },
+    /// Assign the selected Jira issue to a user.
+    AssignJiraIssue {
+        /// The Jira `accountId` of the user to assign, defaults to the current user.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        account_id: Option<String>,
+    },
     /// Search for GitHub issues by status and display the list of them in the terminal.
     /// User is allowed to select one issue which will then change the workflow's state to
     /// [`State::IssueSelected`].
     SelectGitHubIssue {
         /// If provided, only issues with this label will be included
         labels: Option<Vec<String>>,
+        /// How each issue is shown in the list, with `$key`, `$summary`, `$labels`, and `$url`
+        /// replaced by its fields. Defaults to `$key: $summary`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display: Option<String>,
     },
     /// Search for Gitea issues by status and display the list of them in the terminal.
     /// User is allowed to select one issue which will then change the workflow's state to
@@ -47,6 +76,10 @@ pub(crate) enum Step {
     SelectGiteaIssue {
         /// If provided, only issues with this label will be included
         labels: Option<Vec<String>>,
+        /// How each issue is shown in the list, with `$key`, `$summary`, `$labels`, and `$url`
+        /// replaced by its fields. Defaults to `$key: $summary`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        display: Option<String>,
     },
     /// Attempt to parse issue info from the current branch name and change the workflow's state to
     /// [`State::IssueSelected`].
@@ -55,87 +88,331 @@ pub(crate) enum Step {
     /// branch for development. If an existing branch is not found, the user will be prompted to
     /// select an existing local branch to base the new branch off of. Remote branches are not
     /// shown.
-    SwitchBranches,
+    SwitchBranches {
+        /// Prefixes for the new branch name, by label (or Jira issue type or status) of the issue.
+        #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+        branch_prefixes: IndexMap<String, String>,
+    },
     /// Rebase the current branch onto the branch defined by `to`.
     RebaseBranch {
         /// The branch to rebase onto.
         to: String,
     },
+    /// Create a branch for the next release (like `release/1.2.0`) and switch to it.
+    CreateReleaseBranch {
+        /// The name of the branch, where `$version` is the next version. `release/$version` by default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        branch: Option<String>,
+    },
+    /// Fetch all tags from a remote, so versions can be found from tags in shallow clones (like in CI).
+    FetchTags {
+        /// The remote to fetch from, `origin` by default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        remote: Option<String>,
+    },
+    /// Run one of a few Git subcommands (`fetch`, `push`, `tag`, or `switch`).
+    Git {
+        /// The Git subcommand to run.
+        command: git_command::Subcommand,
+        /// The remote for `fetch` and `push`, `origin` by default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        remote: Option<String>,
+        /// The arguments to pass to the subcommand, with any variable keys you wish to replace.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        /// A map of value-to-replace to [Variable][`crate::command::Variable`] to replace
+        /// it with in each of the `args`.
+        #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+        variables: IndexMap<String, Variable>,
+    },
     /// Bump the version of the project in any supported formats found using a
     /// [Semantic Versioning](https://semver.org) rule.
     BumpVersion(releases::Rule),
     /// Run a command in your current shell after optionally replacing some variables.
-    Command {
-        /// The command to run, with any variable keys you wish to replace.
-        command: String,
-        /// A map of value-to-replace to [Variable][`crate::command::Variable`] to replace
-        /// it with.
-        variables: Option<IndexMap<String, Variable>>,
-        #[serde(default, skip_serializing_if = "Option::is_none")]
-        /// Whether to run the command in the platform's shell or not
-        shell: Option<bool>,
-    },
+    Command(command::RunCommand),
     /// This will look through all commits since the last tag and parse any
     /// [Conventional Commits](https://www.conventionalcommits.org/en/v1.0.0/) it finds. It will
     /// then bump the project version (depending on the rule determined from the commits) and add
     /// a new Changelog entry using the [Keep A Changelog](https://keepachangelog.com/en/1.0.0/)
     /// format.
-    PrepareRelease(PrepareRelease),
+    PrepareRelease(Box<PrepareRelease>),
     /// This will create a new release on GitHub using the current project version.
     ///
     /// Requires that GitHub details be configured.
-    Release,
+    Release(Release),
+    /// Create the release tags for the current version of every package, without bumping or
+    /// creating a forge release.
+    Tag(Tag),
     /// Create a new change file to be included in the next release.
     ///
     /// This step is interactive and will prompt the user for the information needed to create the
-    /// change file. Do not try to run in a non-interactive environment.
-    CreateChangeFile,
+    /// change file, unless the `--change` and `--summary` arguments provide it.
+    CreateChangeFile {
+        /// Set from the command line, never in the config file
+        #[serde(skip)]
+        args: Option<releases::ChangeFileArgs>,
+    },
+    /// Print the changelog sections that the current change files would produce, leaving the
+    /// change files in place.
+    PreviewChangelog,
+    /// Overwrite the version in every versioned file of a package to match its `source_of_truth`.
+    SyncVersions,
+    /// Fail if a package's changelog has no entry for the version being released.
+    VerifyChangelogEntry,
+    /// Fail unless `HEAD` is on one of the allowed branches.
+    VerifyBranch {
+        /// The branches to allow, where `*` matches anything (like `release/*`). Only `main` by
+        /// default.
+        #[serde(default = "verify_branch::default_allowed")]
+        allowed: Vec<String>,
+    },
+    /// Fail unless the version of a package is greater than every version already published to a
+    /// registry (like crates.io or npm), in case the local tags are behind it.
+    VerifyRegistryVersion(verify_registry_version::VerifyRegistryVersion),
+    /// Undo the release on the current commit by deleting its local release tags and reverting
+    /// the commit, which restores the change files it consumed.
+    Rollback(rollback::Rollback),
+    /// Write the changelog section of the release prepared by `PrepareRelease` to its own file,
+    /// like for attaching the release notes somewhere else.
+    WriteReleaseNotes(write_release_notes::WriteReleaseNotes),
+    /// Set the version in files of another local checkout (like a docs repository) to the version
+    /// of a package, optionally committing them there.
+    SyncDownstream(sync_downstream::SyncDownstream),
+    /// Fail if there are commits since `base` which need a change file, but no change file was
+    /// added. Meant for checking pull requests in CI.
+    RequireChangeFile {
+        /// The branch the changes will be merged into, `main` by default.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base: Option<String>,
+    },
+    /// Replace the notes of an existing GitHub release with the notes from the changelog, like
+    /// after fixing a mistake in them.
+    ///
+    /// Requires that GitHub details be configured.
+    UpdateRelease(update_release::UpdateRelease),
     CreatePullRequest {
         base: String,
         title: Template,
         body: Template,
     },
+    /// Create a GitHub discussion, like an announcement of a release.
+    ///
+    /// Requires that GitHub details be configured.
+    CreateDiscussion {
+        /// The name of the discussion category to post in.
+        category: String,
+        title: Template,
+        body: Template,
+    },
+    /// Create a GitHub milestone named for the version prepared by `PrepareRelease`, if it doesn't
+    /// exist yet.
+    ///
+    /// Requires that GitHub details be configured.
+    CreateMilestone {
+        /// If set to true, the open milestone for the previous version is closed.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        close_previous: bool,
+    },
+    /// Send a JSON payload to a webhook, like a Slack incoming webhook.
+    Webhook {
+        /// Where to send the payload.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        /// An environment variable to read the URL from, since it's often a secret.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url_env_var: Option<String>,
+        /// The JSON to send, with `variables` replaced in every string.
+        payload: serde_json::Value,
+        #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+        variables: IndexMap<String, Variable>,
+    },
+    /// Wait for the GitHub checks and statuses of the current commit to pass.
+    ///
+    /// Requires that GitHub details be configured.
+    WaitForChecks {
+        /// If provided, only checks with these names are waited for.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        checks: Option<Vec<String>>,
+        /// How long to wait for checks before giving up.
+        #[serde(default = "default_checks_timeout_seconds")]
+        timeout_seconds: u64,
+        /// How long to wait between checking the status again, at least 1 second.
+        #[serde(default = "default_checks_poll_interval_seconds")]
+        poll_interval_seconds: u64,
+    },
+}
+
+const fn default_checks_timeout_seconds() -> u64 {
+    30 * 60
+}
+
+const fn default_checks_poll_interval_seconds() -> u64 {
+    30
 }
 
 impl Step {
     pub(crate) fn run(self, run_type: RunType) -> Result<RunType, Error> {
         Ok(match self {
-            Step::SelectJiraIssue { status } => issues::jira::select_issue(&status, run_type)?,
+            Step::SelectJiraIssue { status, display } => {
+                issues::jira::select_issue(&status, display.as_deref(), run_type)?
+            }
             Step::TransitionJiraIssue { status } => {
                 issues::jira::transition_issue(&status, run_type)?
             }
-            Step::SelectGitHubIssue { labels } => {
-                issues::github::select_issue(labels.as_deref(), run_type)?
+            Step::AssignJiraIssue { account_id } => {
+                issues::jira::assign_issue(account_id.as_deref(), run_type)?
+            }
+            Step::SelectGitHubIssue { labels, display } => {
+                issues::github::select_issue(labels.as_deref(), display.as_deref(), run_type)?
+            }
+            Step::SelectGiteaIssue { labels, display } => {
+                issues::gitea::select_issue(labels.as_deref(), display.as_deref(), run_type)?
             }
-            Step::SelectGiteaIssue { labels } => {
-                issues::gitea::select_issue(labels.as_deref(), run_type)?
+            Step::SwitchBranches { branch_prefixes } => {
+                git::switch_branches(run_type, &branch_prefixes)?
             }
-            Step::SwitchBranches => git::switch_branches(run_type)?,
             Step::RebaseBranch { to } => git::rebase_branch(&to, run_type)?,
-            Step::BumpVersion(rule) => releases::bump_version(run_type, &rule)?,
-            Step::Command {
+            Step::CreateReleaseBranch { branch } => {
+                create_release_branch::run(branch.as_deref(), run_type)?
+            }
+            Step::FetchTags { remote } => git::fetch_tags(run_type, remote.as_deref())?,
+            Step::Git {
                 command,
+                remote,
+                args,
                 variables,
-                shell,
-            } => command::run_command(run_type, command, shell.is_some_and(|it| it), variables)?,
+            } => git_command::run(command, remote.as_deref(), args, &variables, run_type)?,
+            Step::BumpVersion(rule) => releases::bump_version(run_type, &rule)?,
+            Step::Command(command) => command::run_command(run_type, command)?,
             Step::PrepareRelease(prepare_release) => {
                 releases::prepare_release(run_type, &prepare_release)?
             }
             Step::SelectIssueFromBranch => git::select_issue_from_current_branch(run_type)?,
-            Step::Release => releases::release(run_type)?,
-            Step::CreateChangeFile => releases::create_change_file(run_type)?,
+            Step::Release(Release {
+                existing_tag,
+                release_notes,
+                forges,
+                from_changelog,
+            }) => releases::release(
+                run_type,
+                existing_tag,
+                &release_notes,
+                forges.as_deref(),
+                from_changelog,
+            )?,
+            Step::Tag(Tag { existing_tag }) => releases::tag(run_type, existing_tag)?,
+            Step::CreateChangeFile { args } => releases::create_change_file(args, run_type)?,
+            Step::PreviewChangelog => releases::preview_changelog(run_type)?,
+            Step::SyncVersions => releases::sync_versions(run_type)?,
+            Step::VerifyChangelogEntry => verify_changelog_entry::run(run_type)?,
+            Step::VerifyBranch { allowed } => verify_branch::run(&allowed, run_type)?,
+            Step::VerifyRegistryVersion(verify) => verify_registry_version::run(&verify, run_type)?,
+            Step::Rollback(rollback) => rollback::run(&rollback, run_type)?,
+            Step::SyncDownstream(sync) => sync_downstream::run(&sync, run_type)?,
+            Step::WriteReleaseNotes(write) => write_release_notes::run(&write, run_type)?,
+            Step::RequireChangeFile { base } => {
+                require_change_file::run(base.as_deref(), run_type)?
+            }
+            Step::UpdateRelease(update) => update_release::run(&update, run_type)?,
             Step::CreatePullRequest { base, title, body } => {
                 create_pull_request::run(&base, title, body, run_type)?
             }
+            Step::CreateDiscussion {
+                category,
+                title,
+                body,
+            } => create_discussion::run(&category, title, body, run_type)?,
+            Step::CreateMilestone { close_previous } => {
+                create_milestone::run(close_previous, run_type)?
+            }
+            Step::Webhook {
+                url,
+                url_env_var,
+                payload,
+                variables,
+            } => webhook::run(
+                url.as_deref(),
+                url_env_var.as_deref(),
+                payload,
+                &variables,
+                run_type,
+            )?,
+            Step::WaitForChecks {
+                checks,
+                timeout_seconds,
+                poll_interval_seconds,
+            } => wait_for_checks::run(
+                checks.as_deref(),
+                timeout_seconds,
+                poll_interval_seconds,
+                run_type,
+            )?,
         })
     }
 
+    /// The `type` of this step, as written in `knope.toml`.
+    pub(crate) const fn name(&self) -> &'static str {
+        match self {
+            Step::SelectJiraIssue { .. } => "SelectJiraIssue",
+            Step::TransitionJiraIssue { .. } => "TransitionJiraIssue",
+            Step::AssignJiraIssue { .. } => "AssignJiraIssue",
+            Step::SelectGitHubIssue { .. } => "SelectGitHubIssue",
+            Step::SelectGiteaIssue { .. } => "SelectGiteaIssue",
+            Step::SelectIssueFromBranch => "SelectIssueFromBranch",
+            Step::SwitchBranches { .. } => "SwitchBranches",
+            Step::RebaseBranch { .. } => "RebaseBranch",
+            Step::CreateReleaseBranch { .. } => "CreateReleaseBranch",
+            Step::FetchTags { .. } => "FetchTags",
+            Step::Git { .. } => "Git",
+            Step::BumpVersion(_) => "BumpVersion",
+            Step::Command(_) => "Command",
+            Step::PrepareRelease(_) => "PrepareRelease",
+            Step::Release(_) => "Release",
+            Step::Tag(_) => "Tag",
+            Step::CreateChangeFile { .. } => "CreateChangeFile",
+            Step::PreviewChangelog => "PreviewChangelog",
+            Step::SyncVersions => "SyncVersions",
+            Step::VerifyChangelogEntry => "VerifyChangelogEntry",
+            Step::VerifyBranch { .. } => "VerifyBranch",
+            Step::VerifyRegistryVersion(_) => "VerifyRegistryVersion",
+            Step::Rollback(_) => "Rollback",
+            Step::SyncDownstream(_) => "SyncDownstream",
+            Step::WriteReleaseNotes(_) => "WriteReleaseNotes",
+            Step::RequireChangeFile { .. } => "RequireChangeFile",
+            Step::UpdateRelease(_) => "UpdateRelease",
+            Step::CreatePullRequest { .. } => "CreatePullRequest",
+            Step::CreateDiscussion { .. } => "CreateDiscussion",
+            Step::CreateMilestone { .. } => "CreateMilestone",
+            Step::Webhook { .. } => "Webhook",
+            Step::WaitForChecks { .. } => "WaitForChecks",
+        }
+    }
+
     /// Set `prerelease_label` if `self` is `PrepareRelease`.
     pub(crate) fn set_prerelease_label(&mut self, prerelease_label: &str) {
         if let Step::PrepareRelease(prepare_release) = self {
             prepare_release.prerelease_label = Some(Label::from(prerelease_label));
         }
     }
+
+    pub(crate) fn set_keep_change_files(&mut self) {
+        if let Step::PrepareRelease(prepare_release) = self {
+            prepare_release.keep_change_files = true;
+        }
+    }
+
+    pub(crate) fn set_since_date(&mut self, since_date: &str) {
+        if let Step::PrepareRelease(prepare_release) = self {
+            prepare_release.since_date = Some(since_date.to_string());
+        }
+    }
+
+    /// Set `args` if `self` is `CreateChangeFile`.
+    pub(crate) fn set_change_file_args(&mut self, change_file_args: &releases::ChangeFileArgs) {
+        if let Step::CreateChangeFile { args } = self {
+            *args = Some(change_file_args.clone());
+        }
+    }
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -167,13 +444,84 @@ pub(super) enum Error {
     #[error(transparent)]
     #[diagnostic(transparent)]
     CreatePullRequest(#[from] create_pull_request::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    CreateReleaseBranch(#[from] create_release_branch::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    GitCommand(#[from] git_command::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    VerifyChangelogEntry(#[from] verify_changelog_entry::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    VerifyBranch(#[from] verify_branch::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    VerifyRegistryVersion(#[from] verify_registry_version::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Rollback(#[from] rollback::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    SyncDownstream(#[from] sync_downstream::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    WriteReleaseNotes(#[from] write_release_notes::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    RequireChangeFile(#[from] require_change_file::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    UpdateRelease(#[from] update_release::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    CreateDiscussion(#[from] create_discussion::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    CreateMilestone(#[from] create_milestone::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    WaitForChecks(#[from] wait_for_checks::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Webhook(#[from] webhook::Error),
+}
+
+/// The inner content of a [`Step::Release`] step.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
+#[allow(clippy::struct_field_names)] // The names come from the config file
+pub(crate) struct Release {
+    /// What to do if a tag this release would create already exists.
+    #[serde(default, skip_serializing_if = "releases::ExistingTag::is_default")]
+    pub(crate) existing_tag: releases::ExistingTag,
+    /// How to shorten the release notes of forge releases.
+    #[serde(default, skip_serializing_if = "releases::ReleaseNotes::is_default")]
+    pub(crate) release_notes: releases::ReleaseNotes,
+    /// Which of the configured forges to create releases on, all of them by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) forges: Option<Vec<releases::Forge>>,
+    /// If set to true, the latest version in each changelog is released with its notes, for
+    /// changelogs maintained by hand.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) from_changelog: bool,
+}
+
+/// The inner content of a [`Step::Tag`] step.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct Tag {
+    /// What to do if a tag this step would create already exists.
+    #[serde(default, skip_serializing_if = "releases::ExistingTag::is_default")]
+    pub(crate) existing_tag: releases::ExistingTag,
 }
 
 /// The inner content of a [`Step::PrepareRelease`] step.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
+#[allow(clippy::struct_excessive_bools)] // They're independent options from the config file
 pub(crate) struct PrepareRelease {
     /// If set, the user wants to create a pre-release version using the selected label.
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
     pub(crate) prerelease_label: Option<Label>,
     /// Should this step continue if there are no changes to release? If not, it causes an error.
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
@@ -181,4 +529,124 @@ pub(crate) struct PrepareRelease {
     /// If set to true, conventional commits are ignored
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub(crate) ignore_conventional_commits: bool,
+    /// If set, only commits after this Git revision are considered, instead of those after each
+    /// package's last release tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) since: Option<String>,
+    /// If set (like `2024-01-31`), commits made before this date are left out, in addition to
+    /// those before each package's last release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) since_date: Option<String>,
+    /// If set, at most this many commits are checked for conventional commits, with a warning if
+    /// there were more.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_commits: Option<usize>,
+    /// If set to true, only the first parent of each merge commit is followed when looking for
+    /// commits (like `git log --first-parent`), so commits from merged branches are left out.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) first_parent: bool,
+    /// A regex for the first line of release commits made by earlier runs (like
+    /// `chore: prepare release 1.2.3`), which are skipped when reading commits. An empty string
+    /// skips nothing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) release_commit_pattern: Option<String>,
+    /// If set to true, the authors of released commits are listed in a "Contributors" section
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) contributors: bool,
+    /// Authors (like bots) to leave out of the "Contributors" section
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) ignore_contributors: Vec<String>,
+    /// If set to true, change files are left in place instead of being deleted (like they are for
+    /// pre-releases)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) keep_change_files: bool,
+    /// If set, the changes are committed (changelogs separately from everything else) instead of
+    /// only being staged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) separate_commits: Option<SeparateCommits>,
+    /// What to do with commits which aren't conventional commits, they're ignored by default.
+    #[serde(
+        default,
+        skip_serializing_if = "releases::NonConventionalCommits::is_default"
+    )]
+    pub(crate) non_conventional_commits: releases::NonConventionalCommits,
+    /// If set, every feature and fix commit must reference an issue with a footer, or this step
+    /// fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) require_issue_reference: Option<RequireIssueReference>,
+    /// The token of a footer (like `Affects`) which lists more packages that a scoped commit
+    /// applies to, by scope or by name, separated by commas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) scope_footer: Option<String>,
+    /// If set to true, only the number of the current pre-release is bumped (like `1.2.0-rc.3` to
+    /// `1.2.0-rc.4`), no matter which rule the changes imply
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) prerelease_only: bool,
+    /// What to do if a changelog already has an entry for the new version.
+    #[serde(default, skip_serializing_if = "releases::ExistingEntry::is_default")]
+    pub(crate) existing_changelog_entry: releases::ExistingEntry,
+    /// The order to release packages in, which is the order they're declared in by default.
+    #[serde(default, skip_serializing_if = "releases::PackageOrder::is_default")]
+    pub(crate) package_order: releases::PackageOrder,
+    /// If set to true, a leading emoji (like `✨` or `:sparkles:`) is removed from commit messages
+    /// before parsing them as conventional commits
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) strip_emoji: bool,
+    /// Where to find the previous version of each package, local Git tags by default.
+    #[serde(
+        default,
+        skip_serializing_if = "releases::PreviousVersionSource::is_default"
+    )]
+    pub(crate) previous_version_source: releases::PreviousVersionSource,
+    /// Build metadata to add to new versions (like `1.2.3+20240501`) in versioned files and tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) build_metadata: Option<releases::BuildMetadataSource>,
+}
+
+/// Which footers of a commit reference an issue, for [`PrepareRelease::require_issue_reference`].
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct RequireIssueReference {
+    /// The tokens of footers which reference an issue, compared ignoring case
+    #[serde(default = "RequireIssueReference::default_footers")]
+    pub(crate) footers: Vec<String>,
+    /// A regex which the value of one of those footers must match. By default, the value must
+    /// contain an issue number (like `#123`), an issue key (like `PROJ-123`), or a URL. An empty
+    /// string accepts any value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) value_pattern: Option<String>,
+}
+
+impl RequireIssueReference {
+    fn default_footers() -> Vec<String> {
+        ["Closes", "Fixes", "Resolves", "Refs"]
+            .map(String::from)
+            .to_vec()
+    }
+}
+
+/// The messages of the commits a [`Step::PrepareRelease`] step makes with `separate_commits`.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct SeparateCommits {
+    /// The message of the commit with the versioned files (and everything else that was staged)
+    #[serde(default = "SeparateCommits::default_version_message")]
+    pub(crate) version_message: Template,
+    /// The message of the commit with only the changelogs
+    #[serde(default = "SeparateCommits::default_changelog_message")]
+    pub(crate) changelog_message: Template,
+}
+
+impl SeparateCommits {
+    fn default_version_message() -> Template {
+        Template {
+            template: String::from("chore: Bump versions"),
+            variables: IndexMap::new(),
+        }
+    }
+
+    fn default_changelog_message() -> Template {
+        Template {
+            template: String::from("docs: Update changelog"),
+            variables: IndexMap::new(),
+        }
+    }
 }
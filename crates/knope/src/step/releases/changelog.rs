@@ -1,8 +1,10 @@
-use std::{cmp::Ordering, fmt::Display, mem::swap, path::PathBuf, str::FromStr};
+use std::{cmp::Ordering, fmt::Display, iter, mem::swap, path::PathBuf, str::FromStr};
 
 use itertools::Itertools;
-use knope_versioning::{GoVersioning, Version};
+use knope_versioning::{BuildMetadata, GoVersioning, Version};
 use miette::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use time::{macros::format_description, Date, OffsetDateTime};
 
@@ -16,6 +18,10 @@ pub(crate) struct Changelog {
     /// The content that has been written to `path`
     pub(crate) content: String,
     section_header_level: HeaderLevel,
+    /// The line which new releases are added right after, if it's in `content`
+    anchor: Option<String>,
+    /// The column that new list items are wrapped at, if any
+    pub(crate) wrap_width: Option<usize>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -43,8 +49,25 @@ impl TryFrom<PathBuf> for Changelog {
     type Error = Error;
 
     fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
+        Self::load(path, None, None, None)
+    }
+}
+
+impl Changelog {
+    /// Load the changelog at `path`. If it doesn't exist yet, it will start with `header`.
+    ///
+    /// New releases are added right after the `anchor` line, if the changelog has one. Their list
+    /// items are wrapped at `wrap_width` columns, if set.
+    pub(crate) fn load(
+        path: PathBuf,
+        header: Option<&str>,
+        anchor: Option<&str>,
+        wrap_width: Option<usize>,
+    ) -> Result<Self, Error> {
         let content = if path.exists() {
             fs::read_to_string(&path)?
+        } else if let Some(header) = header {
+            format!("{}\n\n", header.trim_end())
         } else {
             String::new()
         };
@@ -66,11 +89,11 @@ impl TryFrom<PathBuf> for Changelog {
             path,
             content,
             section_header_level,
+            anchor: anchor.map(|anchor| anchor.trim().to_string()),
+            wrap_width,
         })
     }
-}
 
-impl Changelog {
     pub(crate) fn get_release(
         &self,
         version: &Version,
@@ -89,15 +112,26 @@ impl Changelog {
         };
         let (header_level, version, date) = Release::parse_title(&title)?;
 
-        let release_sections = content_starting_with_first_release.take_while(
-            |line| !line.starts_with(&format!("{section_header_level} ")), // Next version
-        );
+        let release_lines = content_starting_with_first_release
+            .take_while(
+                |line| !line.starts_with(&format!("{section_header_level} ")), // Next version
+            )
+            .collect_vec();
+        let subsection_header_start = format!("{section_header_level}# ");
+        let notes = release_lines
+            .iter()
+            .take_while(|line| !line.starts_with(&subsection_header_start))
+            .join("\n");
+        let notes = Some(notes.trim().to_string()).filter(|notes| !notes.is_empty());
         let sections = Some(Section::from_lines(
-            release_sections,
+            release_lines.into_iter(),
             &format!("{section_header_level}#"),
         ));
         let additional_tags = package
-            .map(|pkg| pkg.set_version(&version, go_versioning).unwrap_or_default())
+            .map(|pkg| {
+                pkg.set_version(&version, None, go_versioning)
+                    .unwrap_or_default()
+            })
             .unwrap_or_default()
             .into_iter()
             .filter_map(|action| match action {
@@ -108,15 +142,62 @@ impl Changelog {
         Ok(Some(Release {
             version,
             date,
+            notes,
             sections,
             header_level,
             additional_tags,
+            build_metadata: None,
         }))
     }
 
+    /// The most recent release in the changelog, which is the first one from the top.
+    pub(crate) fn latest_release(
+        &self,
+        package: Option<knope_versioning::Package>,
+        go_versioning: GoVersioning,
+    ) -> Result<Option<Release>, ParseError> {
+        let header_start = format!("{} ", self.section_header_level.as_str());
+        let Some((_, version, _)) = self
+            .content
+            .lines()
+            .filter(|line| line.starts_with(&header_start))
+            .find_map(|line| Release::parse_title(line).ok())
+        else {
+            return Ok(None);
+        };
+        self.get_release(&version, package, go_versioning)
+    }
+
+    /// Whether `line` is the title of an entry for `version`.
+    fn is_entry_title(&self, line: &str, version: &Version) -> bool {
+        Release::parse_title(line).is_ok_and(|(header_level, line_version, _)| {
+            header_level == self.section_header_level && line_version == *version
+        })
+    }
+
+    /// Make sure that adding a release for `version` won't duplicate an entry, unless
+    /// `existing_entry` says to replace it.
+    pub(crate) fn check_existing_entry(
+        &self,
+        version: &Version,
+        existing_entry: ExistingEntry,
+    ) -> Result<(), Error> {
+        let has_entry = self
+            .content
+            .lines()
+            .any(|line| self.is_entry_title(line, version));
+        if has_entry && existing_entry == ExistingEntry::Fail {
+            return Err(Error::EntryExists {
+                version: version.clone(),
+                path: self.path.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Add `release` to the changelog, replacing any existing entry for the same version.
     fn add_release(&mut self, release: &Release, dry_run: DryRun) -> Result<(), Error> {
         let mut changelog = String::new();
-        let mut not_written = true;
         let Some(new_changes) = release.body() else {
             return Ok(());
         };
@@ -125,8 +206,44 @@ impl Changelog {
             title = release.title(true, true)?,
         );
 
+        let is_release_title = |line: &str| {
+            Release::parse_title(line)
+                .is_ok_and(|(header_level, _, _)| header_level == self.section_header_level)
+        };
+        let is_existing_entry = |line: &str| self.is_entry_title(line, &release.version);
+        let has_existing_entry = self.content.lines().any(is_existing_entry);
+        // An existing entry is replaced where it is, instead of adding the new one anywhere else
+        let mut not_written = !has_existing_entry;
+        let mut replacing = false;
+
+        let anchor = self
+            .anchor
+            .as_deref()
+            .filter(|anchor| self.content.lines().any(|line| line.trim() == *anchor));
+        // Blank lines after the anchor are replaced by one between the new changes and the rest
+        let mut after_anchor = false;
+
         for line in self.content.lines() {
-            if not_written && Release::parse_title(line).is_ok() {
+            if replacing {
+                if !is_release_title(line) {
+                    continue;
+                }
+                replacing = false;
+            }
+            if has_existing_entry && is_existing_entry(line) {
+                changelog.push_str(&new_changes);
+                changelog.push_str("\n\n");
+                replacing = true;
+                continue;
+            }
+            if after_anchor {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                changelog.push('\n');
+                after_anchor = false;
+            }
+            if not_written && anchor.is_none() && Release::parse_title(line).is_ok() {
                 // Insert new changes before the next release in the changelog
                 changelog.push_str(&new_changes);
                 changelog.push_str("\n\n");
@@ -134,11 +251,22 @@ impl Changelog {
             }
             changelog.push_str(line);
             changelog.push('\n');
+            if not_written && anchor == Some(line.trim()) {
+                changelog.push('\n');
+                changelog.push_str(&new_changes);
+                changelog.push('\n');
+                not_written = false;
+                after_anchor = true;
+            }
         }
 
         if not_written {
             changelog.push_str(&new_changes);
         }
+        if replacing {
+            // The replaced entry was the last thing in the changelog
+            changelog.truncate(changelog.trim_end().len());
+        }
 
         if (self.content.ends_with('\n') || self.content.is_empty()) && !changelog.ends_with('\n') {
             // Preserve white space at end of file
@@ -156,10 +284,67 @@ impl Changelog {
     }
 }
 
+/// How to order the entries within each section of a changelog.
+#[derive(Clone, Copy, Debug, Default, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Sort {
+    /// The order Knope found the changes in: commits first, then change files by file name.
+    #[default]
+    AsIs,
+    /// Alphabetically by summary, ignoring case.
+    Alphabetical,
+    /// Commits sorted by commit time (oldest first), then change files.
+    Chronological,
+}
+
+impl Sort {
+    /// Whether commits must be sorted by their commit time when walking the history.
+    pub(crate) const fn by_commit_time(self) -> bool {
+        matches!(self, Self::Chronological)
+    }
+}
+
+/// What `PrepareRelease` does when the changelog already has an entry for the new version.
+#[derive(Clone, Copy, Debug, Default, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ExistingEntry {
+    /// Stop with an error.
+    #[default]
+    Fail,
+    /// Replace the existing entry with the new one.
+    Replace,
+}
+
+impl ExistingEntry {
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Required by serde's `skip_serializing_if`
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// How to shorten the release notes of forge releases (the changelog always gets everything).
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+pub(crate) struct ReleaseNotes {
+    /// If set, only the changelog sections with these titles are included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) sections: Option<Vec<String>>,
+    /// If set, longer release notes are cut short with a link to the full changelog.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_length: Option<usize>,
+}
+
+impl ReleaseNotes {
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct Release {
     pub(crate) version: Version,
     pub(crate) date: Option<Date>,
+    /// Content which comes before any sections, like the `no_changes_entry` of a forced release.
+    pub(crate) notes: Option<String>,
     pub(crate) sections: Option<Vec<Section>>,
     /// The expected header level of the release title (# or ##).
     ///
@@ -169,13 +354,17 @@ pub(crate) struct Release {
     /// This doesn't include the package-level tags, since those will get added by GitHub/Gitea
     /// sometimes.
     pub(crate) additional_tags: Vec<String>,
+    /// Build metadata added to the version in versioned files and the tag, but not the changelog.
+    pub(crate) build_metadata: Option<BuildMetadata>,
 }
 impl Release {
     pub(crate) fn new(
         version: Version,
         changes: &[Change],
         changelog_sections: &ChangelogSections,
+        sort: Sort,
         header_level: HeaderLevel,
+        wrap_width: Option<usize>,
         additional_tags: Vec<String>,
     ) -> Self {
         let sections = changelog_sections
@@ -190,14 +379,25 @@ impl Release {
                             None
                         }
                     })
-                    .sorted()
+                    .sorted_by(|first, second| {
+                        let by_kind = first.cmp(second);
+                        match sort {
+                            Sort::AsIs | Sort::Chronological => by_kind,
+                            Sort::Alphabetical => by_kind.then_with(|| {
+                                first
+                                    .summary()
+                                    .to_lowercase()
+                                    .cmp(&second.summary().to_lowercase())
+                            }),
+                        }
+                    })
                     .collect_vec();
                 if changes.is_empty() {
                     None
                 } else {
                     Some(Section {
                         title: section_name.to_string(),
-                        body: build_body(changes, header_level),
+                        body: build_body(changes, header_level, wrap_width),
                     })
                 }
             })
@@ -208,9 +408,11 @@ impl Release {
         Self {
             version,
             date,
+            notes: None,
             sections,
             header_level,
             additional_tags,
+            build_metadata: None,
         }
     }
 
@@ -218,9 +420,11 @@ impl Release {
         Self {
             version,
             date: Some(OffsetDateTime::now_utc().date()),
+            notes: None,
             sections: None,
             header_level: HeaderLevel::H2,
             additional_tags,
+            build_metadata: None,
         }
     }
 
@@ -291,9 +495,11 @@ impl Release {
     /// GitHub releases _always_ use the [`HeaderLevel::H1`] format, so they call [`Self::body_at_h1`]
     /// which is like this function, but with optional conversion.
     pub(crate) fn body(&self) -> Option<String> {
-        let sections = self.sections.as_ref()?;
-        let mut res = String::new();
-        for section in sections {
+        if self.notes.is_none() && self.sections.is_none() {
+            return None;
+        }
+        let mut res = self.notes.clone().unwrap_or_default();
+        for section in self.sections.iter().flatten() {
             res.push_str(&format!(
                 "\n\n{header_level}# {title}\n\n{body}",
                 header_level = self.header_level,
@@ -337,6 +543,57 @@ impl Release {
         adjusted.body()
     }
 
+    /// Like [`Self::body_at_h1`], but shortened as `release_notes` says. `changelog_url` is linked
+    /// to when the body had to be cut short.
+    pub(crate) fn summarized_body_at_h1(
+        &self,
+        release_notes: &ReleaseNotes,
+        changelog_url: Option<&str>,
+    ) -> Option<String> {
+        let mut summarized = self.clone();
+        if let Some(titles) = release_notes.sections.as_ref() {
+            summarized.sections = summarized.sections.map(|sections| {
+                sections
+                    .into_iter()
+                    .filter(|section| {
+                        titles
+                            .iter()
+                            .any(|title| title.eq_ignore_ascii_case(&section.title))
+                    })
+                    .collect()
+            });
+        }
+        let body = summarized.body_at_h1()?;
+        let Some(max_length) = release_notes.max_length else {
+            return Some(body);
+        };
+        if body.chars().count() <= max_length {
+            return Some(body);
+        }
+        let suffix = changelog_url.map_or_else(
+            || String::from("\n\n…"),
+            |url| format!("\n\n[See the full changelog]({url})"),
+        );
+        let budget = max_length.saturating_sub(suffix.chars().count());
+        let mut length = 0;
+        // Only whole lines are kept, so no Markdown is left half-written
+        let mut lines = body
+            .lines()
+            .take_while(|line| {
+                length += line.chars().count() + 1;
+                length <= budget
+            })
+            .collect_vec();
+        // A header without anything under it would be misleading
+        while lines
+            .last()
+            .is_some_and(|line| line.trim().is_empty() || line.starts_with('#'))
+        {
+            lines.pop();
+        }
+        Some(format!("{}{suffix}", lines.join("\n")).trim().to_string())
+    }
+
     /// The title of the release, which is either the version number or the version number and date.
     ///
     /// If `markdown` is true, the title will be formatted as a Markdown header using `self.header_level`
@@ -378,6 +635,14 @@ impl Ord for ChangeDescription {
     }
 }
 
+impl ChangeDescription {
+    fn summary(&self) -> &str {
+        match self {
+            Self::Simple(summary) | Self::Complex(summary, _) => summary,
+        }
+    }
+}
+
 impl PartialOrd for ChangeDescription {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -411,13 +676,21 @@ impl From<&Change> for ChangeDescription {
     }
 }
 
-fn build_body(changes: Vec<ChangeDescription>, header_level: HeaderLevel) -> String {
+fn build_body(
+    changes: Vec<ChangeDescription>,
+    header_level: HeaderLevel,
+    wrap_width: Option<usize>,
+) -> String {
     let mut body = String::new();
     let mut changes = changes.into_iter().peekable();
     while let Some(change) = changes.next() {
         match change {
             ChangeDescription::Simple(summary) => {
-                body.push_str(&format!("- {summary}"));
+                let item = format!("- {}", indent_continuation_lines(&summary));
+                match wrap_width {
+                    Some(width) => body.push_str(&wrap_list_item(&item, width)),
+                    None => body.push_str(&item),
+                }
             }
             ChangeDescription::Complex(summary, details) => {
                 body.push_str(&format!("{header_level}## {summary}\n\n{details}"));
@@ -432,6 +705,81 @@ fn build_body(changes: Vec<ChangeDescription>, header_level: HeaderLevel) -> Str
     body
 }
 
+/// Indent every line of a multi-line list item after the first (like a wrapped `BREAKING CHANGE`
+/// footer) so that they all stay in the item, keeping their indentation relative to each other.
+fn indent_continuation_lines(summary: &str) -> String {
+    let mut lines = summary.lines();
+    let first = lines.next().unwrap_or_default();
+    let rest = lines.collect_vec();
+    let common_indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+    iter::once(first.to_string())
+        .chain(rest.into_iter().map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                let line = line
+                    .get(common_indent..)
+                    .unwrap_or_else(|| line.trim_start());
+                format!("  {line}")
+            }
+        }))
+        .join("\n")
+}
+
+/// Wrap every line of a list item which is longer than `width` at spaces. Continuation lines are
+/// indented to line up with the text of the line they came from, so nested lists stay nested.
+///
+/// Words are never split, so a line with a long word (like a URL) may still exceed `width`.
+fn wrap_list_item(item: &str, width: usize) -> String {
+    item.lines().map(|line| wrap_line(line, width)).join("\n")
+}
+
+fn wrap_line(line: &str, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line.to_string();
+    }
+    let text = line.trim_start();
+    let marker_len = ["- ", "* "]
+        .iter()
+        .find(|marker| text.starts_with(**marker))
+        .map_or(0, |marker| marker.len());
+    let prefix_len = line.len() - text.len() + marker_len;
+    let continuation = " ".repeat(prefix_len);
+
+    let mut wrapped = String::new();
+    let mut current = line.get(..prefix_len).unwrap_or_default().to_string();
+    let mut current_width = prefix_len;
+    let mut has_words = false;
+    for word in text
+        .get(marker_len..)
+        .unwrap_or_default()
+        .split_whitespace()
+    {
+        let word_width = word.chars().count();
+        if has_words && current_width + 1 + word_width > width {
+            wrapped.push_str(&current);
+            wrapped.push('\n');
+            current.clone_from(&continuation);
+            current_width = prefix_len;
+            has_words = false;
+        }
+        if has_words {
+            current.push(' ');
+            current_width += 1;
+        }
+        current.push_str(word);
+        current_width += word_width;
+        has_words = true;
+    }
+    wrapped.push_str(&current);
+    wrapped
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test_parse_title {
@@ -481,6 +829,52 @@ mod test_parse_title {
     }
 }
 
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test_get_release {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn notes_before_sections() {
+        let changelog = Changelog {
+            path: PathBuf::from("CHANGELOG.md"),
+            content:
+                "## 1.0.1\n\n- Maintenance release\n\n### Fixes\n\n- A fix\n\n## 1.0.0\n\n- Older\n"
+                    .to_string(),
+            section_header_level: HeaderLevel::H2,
+            anchor: None,
+            wrap_width: None,
+        };
+        let release = changelog
+            .get_release(&Version::new(1, 0, 1, None), None, GoVersioning::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(release.notes.as_deref(), Some("- Maintenance release"));
+        assert_eq!(
+            release.body().unwrap(),
+            "- Maintenance release\n\n### Fixes\n\n- A fix"
+        );
+    }
+
+    #[test]
+    fn only_notes() {
+        let changelog = Changelog {
+            path: PathBuf::from("CHANGELOG.md"),
+            content: "## 1.0.1\n\n- Maintenance release\n".to_string(),
+            section_header_level: HeaderLevel::H2,
+            anchor: None,
+            wrap_width: None,
+        };
+        let release = changelog
+            .get_release(&Version::new(1, 0, 1, None), None, GoVersioning::default())
+            .unwrap()
+            .unwrap();
+        assert_eq!(release.body().unwrap(), "- Maintenance release");
+    }
+}
+
 #[cfg(test)]
 mod test_change_description {
     use changesets::{PackageChange, UniqueId};
@@ -535,6 +929,148 @@ mod test_change_description {
     }
 }
 
+#[cfg(test)]
+mod test_indent_continuation_lines {
+    use pretty_assertions::assert_eq;
+
+    use super::indent_continuation_lines;
+
+    #[test]
+    fn single_line() {
+        assert_eq!(indent_continuation_lines("a change"), "a change");
+    }
+
+    #[test]
+    fn wrapped() {
+        assert_eq!(
+            indent_continuation_lines("something broke\nand more about it"),
+            "something broke\n  and more about it"
+        );
+    }
+
+    #[test]
+    fn paragraphs() {
+        assert_eq!(
+            indent_continuation_lines("something broke\n  in two lines\n\n  - with\n    - a list"),
+            "something broke\n  in two lines\n\n  - with\n    - a list"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_wrap_list_item {
+    use pretty_assertions::assert_eq;
+
+    use super::wrap_list_item;
+
+    #[test]
+    fn short_enough() {
+        assert_eq!(wrap_list_item("- a change", 10), "- a change");
+    }
+
+    #[test]
+    fn wraps_at_width() {
+        assert_eq!(
+            wrap_list_item("- this change has a long description", 20),
+            "- this change has a\n  long description"
+        );
+    }
+
+    #[test]
+    fn nested_list() {
+        assert_eq!(
+            wrap_list_item("- something broke\n\n  - with a nested list item", 16),
+            "- something\n  broke\n\n  - with a\n    nested list\n    item"
+        );
+    }
+
+    #[test]
+    fn long_words_are_not_split() {
+        assert_eq!(
+            wrap_list_item(
+                "- see https://knope.tech/reference/config-file/packages",
+                20
+            ),
+            "- see\n  https://knope.tech/reference/config-file/packages"
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_sort {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::step::releases::{conventional_commits::ConventionalCommit, ChangeType};
+
+    fn release(sort: Sort) -> Release {
+        let changes = ["second", "Third", "first"].map(|message| {
+            Change::ConventionalCommit(ConventionalCommit {
+                change_type: ChangeType::Feature,
+                original_source: String::new(),
+                message: message.to_string(),
+            })
+        });
+        Release::new(
+            Version::new(1, 0, 0, None),
+            &changes,
+            &ChangelogSections::default(),
+            sort,
+            HeaderLevel::H2,
+            None,
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn as_is() {
+        assert_eq!(
+            release(Sort::AsIs).body(),
+            Some("### Features\n\n- second\n- Third\n- first".to_string())
+        );
+    }
+
+    #[test]
+    fn alphabetical() {
+        assert_eq!(
+            release(Sort::Alphabetical).body(),
+            Some("### Features\n\n- first\n- second\n- Third".to_string())
+        );
+    }
+
+    #[test]
+    fn alphabetical_keeps_simple_changes_first() {
+        let changes = [
+            Change::ChangeSet(changesets::PackageChange {
+                unique_id: changesets::UniqueId::from("a"),
+                change_type: changesets::ChangeType::Minor,
+                summary: "# A detailed feature\n\nwith details".to_string(),
+            }),
+            Change::ConventionalCommit(ConventionalCommit {
+                change_type: ChangeType::Feature,
+                original_source: String::new(),
+                message: "b simple feature".to_string(),
+            }),
+        ];
+        let release = Release::new(
+            Version::new(1, 0, 0, None),
+            &changes,
+            &ChangelogSections::default(),
+            Sort::Alphabetical,
+            HeaderLevel::H2,
+            None,
+            Vec::new(),
+        );
+        assert_eq!(
+            release.body(),
+            Some(
+                "### Features\n\n- b simple feature\n\n#### A detailed feature\n\nwith details"
+                    .to_string()
+            )
+        );
+    }
+}
+
 #[derive(Clone, Debug, Diagnostic, Eq, PartialEq, thiserror::Error)]
 pub(crate) enum ParseError {
     #[error("Missing version")]
@@ -553,6 +1089,62 @@ pub(crate) enum ParseError {
     HeaderLevel,
 }
 
+#[cfg(test)]
+mod test_summarized_body {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::step::releases::{conventional_commits::ConventionalCommit, ChangeType};
+
+    fn release() -> Release {
+        let changes = [
+            (ChangeType::Breaking, "a breaking change"),
+            (ChangeType::Feature, "a feature"),
+            (ChangeType::Fix, "a fix"),
+        ]
+        .map(|(change_type, message)| {
+            Change::ConventionalCommit(ConventionalCommit {
+                change_type,
+                original_source: String::new(),
+                message: message.to_string(),
+            })
+        });
+        Release::new(
+            Version::new(1, 0, 0, None),
+            &changes,
+            &ChangelogSections::default(),
+            Sort::AsIs,
+            HeaderLevel::H2,
+            None,
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn sections() {
+        let release_notes = ReleaseNotes {
+            sections: Some(vec!["breaking changes".to_string(), "Fixes".to_string()]),
+            max_length: None,
+        };
+        assert_eq!(
+            release().summarized_body_at_h1(&release_notes, None),
+            Some("## Breaking Changes\n\n- a breaking change\n\n## Fixes\n\n- a fix".to_string())
+        );
+    }
+
+    #[test]
+    fn max_length_without_changelog() {
+        let release_notes = ReleaseNotes {
+            sections: None,
+            max_length: Some(50),
+        };
+        assert_eq!(
+            release().summarized_body_at_h1(&release_notes, None),
+            Some("## Breaking Changes\n\n- a breaking change\n\n…".to_string())
+        );
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct Section {
     /// The title of the section _without_ any header level (e.g., "Breaking changes" not "### Breaking changes")
@@ -591,30 +1183,74 @@ pub(crate) enum Error {
     Fs(#[from] fs::Error),
     #[error(transparent)]
     #[diagnostic(transparent)]
-    TimeError(#[from] TimeError),
+    Time(#[from] TimeError),
+    #[error("{path} already has an entry for {version}")]
+    #[diagnostic(
+        code(changelog::entry_exists),
+        help(
+            "Remove the existing entry, or set `existing_changelog_entry = \"replace\"` on the \
+            PrepareRelease step to replace it."
+        ),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#options")
+    )]
+    EntryExists { version: Version, path: PathBuf },
 }
 
 impl Package {
     /// Adds content from `release` to `Self::changelog` if it exists.
-    pub(crate) fn write_changelog(
-        &mut self,
+    /// The release (and its changelog section) that `pending_changes` make up for `version`.
+    pub(crate) fn release_from_pending_changes(
+        &self,
         version: Version,
-        dry_run: DryRun,
-    ) -> Result<Release, Error> {
-        let mut additional_tags = Vec::new();
-        swap(&mut self.pending_tags, &mut additional_tags);
-        let release = Release::new(
+        additional_tags: Vec<String>,
+    ) -> Release {
+        let mut release = Release::new(
             version,
             &self.pending_changes,
             &self.changelog_sections,
+            self.changelog_sort,
             self.changelog
                 .as_ref()
                 .map_or(HeaderLevel::H2, |it| it.section_header_level),
+            self.changelog.as_ref().and_then(|it| it.wrap_width),
             additional_tags,
         );
+        if !self.contributors.is_empty() {
+            release.sections.get_or_insert_with(Vec::new).push(Section {
+                title: String::from("Contributors"),
+                body: self
+                    .contributors
+                    .iter()
+                    .map(|contributor| format!("- {contributor}"))
+                    .join("\n"),
+            });
+        }
+        release
+    }
+
+    pub(crate) fn write_changelog(
+        &mut self,
+        version: Version,
+        dry_run: DryRun,
+    ) -> Result<Release, Error> {
+        let mut additional_tags = Vec::new();
+        swap(&mut self.pending_tags, &mut additional_tags);
+        let mut release = self.release_from_pending_changes(version, additional_tags);
+        if self.pending_changes.is_empty() {
+            // Only possible when the release was forced by overriding the version
+            release.notes.clone_from(&self.no_changes_entry);
+        }
 
         if let Some(changelog) = self.changelog.as_mut() {
             changelog.add_release(&release, dry_run)?;
+        } else if let (Some(stdout), Some(body)) = (dry_run, release.body()) {
+            // Without a changelog, these notes are still used by later steps (like `Release`)
+            writeln!(
+                stdout,
+                "Would use the following release notes: \n{title}\n\n{body}\n",
+                title = release.title(true, true)?,
+            )
+            .map_err(fs::Error::Stdout)?;
         }
 
         Ok(release)
@@ -1,4 +1,4 @@
-use std::{collections::HashSet, io::Write, path::PathBuf};
+use std::{collections::HashSet, io::Write, path::Path};
 
 use changesets::{ChangeSet, UniqueId, Versioning};
 use inquire::{MultiSelect, Select};
@@ -8,51 +8,37 @@ use miette::Diagnostic;
 use super::{package::ChangelogSectionSource, Change, Package};
 use crate::{dry_run::DryRun, fs, prompt, state::RunType};
 
-pub(crate) fn create_change_file(run_type: RunType) -> Result<RunType, Error> {
-    let state = match run_type {
-        RunType::DryRun { state, mut stdout } => {
+/// The `--change` and `--summary` arguments, which create a change file without prompting.
+#[derive(Clone, Debug)]
+pub(crate) struct ChangeFileArgs {
+    /// Each formatted like `package_name=change_type`, where `package_name=` is optional if
+    /// there's only one package.
+    pub(crate) changes: Vec<String>,
+    pub(crate) summary: String,
+}
+
+pub(crate) fn create_change_file(
+    args: Option<ChangeFileArgs>,
+    run_type: RunType,
+) -> Result<RunType, Error> {
+    let (state, dry_run) = match run_type {
+        RunType::DryRun { state, mut stdout } if args.is_none() => {
             write!(&mut stdout, "Would create a new change file").map_err(fs::Error::Stdout)?;
             return Ok(RunType::DryRun { state, stdout });
         }
-        RunType::Real(state) => state,
+        RunType::DryRun { state, stdout } => (state, Some(stdout)),
+        RunType::Real(state) => (state, None),
     };
-
-    let packages = if state.packages.len() == 1 {
-        state.packages.clone()
+    let (versioning, summary) = if let Some(ChangeFileArgs { changes, summary }) = args {
+        (versioning_from_args(&changes, &state.packages)?, summary)
     } else {
-        MultiSelect::new(
-            "Which packages does this change affect?",
-            state.packages.clone(),
-        )
-        .prompt()
-        .map_err(prompt::Error::from)?
+        let versioning = prompt_for_versioning(&state.packages)?;
+        let summary = inquire::Text::new("What is a short summary of this change?")
+            .with_help_message("This will be used as a header in the changelog")
+            .prompt()
+            .map_err(prompt::Error::from)?;
+        (versioning, summary)
     };
-
-    let versioning = packages
-        .into_iter()
-        .map(|package| {
-            let package_name = package.name;
-            let change_types = package
-                .changelog_sections
-                .iter()
-                .flat_map(|(_, sources)| sources.iter().filter_map(ChangeType::to_changeset_type))
-                .collect_vec();
-            let prompt = if let Some(package_name) = package_name.as_ref() {
-                format!("What type of change is this for {package_name}?")
-            } else {
-                "What type of change is this?".to_string()
-            };
-            Select::new(&prompt, change_types)
-                .prompt()
-                .map_err(prompt::Error::from)
-                .map_err(Error::from)
-                .map(|change_type| (package_name.unwrap_or_default().to_string(), change_type))
-        })
-        .collect::<Result<Versioning, Error>>()?;
-    let summary = inquire::Text::new("What is a short summary of this change?")
-        .with_help_message("This will be used as a header in the changelog")
-        .prompt()
-        .map_err(prompt::Error::from)?;
     let unique_id = UniqueId::from(&summary);
     let summary = format!("# {summary}");
     let change = changesets::Change {
@@ -61,12 +47,23 @@ pub(crate) fn create_change_file(run_type: RunType) -> Result<RunType, Error> {
         summary,
     };
 
-    let changeset_path = PathBuf::from(".changeset");
+    let changeset_path = &state.change_directory;
+    if let Some(mut stdout) = dry_run {
+        writeln!(
+            &mut stdout,
+            "Would create {}",
+            changeset_path
+                .join(change.unique_id.to_file_name())
+                .display()
+        )
+        .map_err(fs::Error::Stdout)?;
+        return Ok(RunType::DryRun { state, stdout });
+    }
     if !changeset_path.exists() {
-        fs::create_dir(&mut None, &changeset_path)?;
+        fs::create_dir(&mut None, changeset_path)?;
     }
     change
-        .write_to_directory(&changeset_path)
+        .write_to_directory(changeset_path)
         .map_err(|source| {
             let file_name = change.unique_id.to_file_name();
             fs::Error::Write {
@@ -77,6 +74,84 @@ pub(crate) fn create_change_file(run_type: RunType) -> Result<RunType, Error> {
     Ok(RunType::Real(state))
 }
 
+/// The change types that `package` can have in a change file.
+fn change_types(package: &Package) -> Vec<changesets::ChangeType> {
+    package
+        .changelog_sections
+        .iter()
+        .flat_map(|(_, sources)| sources.iter().filter_map(ChangeType::to_changeset_type))
+        .collect_vec()
+}
+
+/// Ask which packages are affected, then ask for the change type of each one.
+fn prompt_for_versioning(packages: &[Package]) -> Result<Versioning, Error> {
+    let packages = if packages.len() == 1 {
+        packages.to_vec()
+    } else {
+        MultiSelect::new("Which packages does this change affect?", packages.to_vec())
+            .prompt()
+            .map_err(prompt::Error::from)?
+    };
+
+    packages
+        .into_iter()
+        .map(|package| {
+            let change_types = change_types(&package);
+            let package_name = package.name;
+            let prompt = if let Some(package_name) = package_name.as_ref() {
+                format!("What type of change is this for {package_name}?")
+            } else {
+                "What type of change is this?".to_string()
+            };
+            Select::new(&prompt, change_types)
+                .prompt()
+                .map_err(prompt::Error::from)
+                .map_err(Error::from)
+                .map(|change_type| (package_name.unwrap_or_default().to_string(), change_type))
+        })
+        .collect()
+}
+
+/// Parse `--change` arguments, checking that each package and change type exists.
+fn versioning_from_args(changes: &[String], packages: &[Package]) -> Result<Versioning, Error> {
+    changes
+        .iter()
+        .map(|change| {
+            let (package, change_type) = match (change.split_once('='), packages) {
+                (Some((package_name, change_type)), _) => (
+                    packages
+                        .iter()
+                        .find(|package| package.name.as_deref() == Some(package_name))
+                        .ok_or_else(|| Error::UnknownPackage {
+                            package: package_name.to_string(),
+                            available: packages.iter().join(", "),
+                        })?,
+                    change_type,
+                ),
+                (None, [package]) => (package, change.as_str()),
+                (None, _) => {
+                    return Err(Error::MissingPackage {
+                        change: change.clone(),
+                    })
+                }
+            };
+            let change_type = changesets::ChangeType::from(change_type);
+            let available = change_types(package);
+            if !available.contains(&change_type) {
+                return Err(Error::UnknownChangeType {
+                    change_type: change_type.to_string(),
+                    package: package.to_string(),
+                    available: available.iter().join(", "),
+                });
+            }
+            Ok((
+                package.name.clone().unwrap_or_default().to_string(),
+                change_type,
+            ))
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub(crate) enum ChangeType {
     Breaking,
@@ -94,7 +169,11 @@ impl ChangeType {
             Self::Custom(ChangelogSectionSource::CustomChangeType(custom)) => {
                 Some(changesets::ChangeType::Custom(custom.to_string()))
             }
-            Self::Custom(ChangelogSectionSource::CommitFooter(_)) => None,
+            Self::Custom(
+                ChangelogSectionSource::CommitFooter(_)
+                | ChangelogSectionSource::CommitType(_)
+                | ChangelogSectionSource::DependencyUpdate,
+            ) => None,
         }
     }
 }
@@ -131,31 +210,39 @@ impl From<ChangelogSectionSource> for ChangeType {
 
 pub(crate) const DEFAULT_CHANGESET_PACKAGE_NAME: &str = "default";
 
+/// Where change files are kept, unless `change_directory` is set in `knope.toml`.
+pub(crate) const DEFAULT_CHANGE_DIRECTORY: &str = ".changeset";
+
 pub(crate) fn add_releases_from_changeset(
     packages: Vec<Package>,
-    is_prerelease: bool,
+    keep_change_files: bool,
+    changeset_path: &Path,
     dry_run: DryRun,
 ) -> Result<Vec<Package>, Error> {
-    let changeset_path = PathBuf::from(".changeset");
     if !changeset_path.exists() {
         return Ok(packages);
     }
-    let mut changeset = ChangeSet::from_directory(&changeset_path)?;
+    let mut changeset = ChangeSet::from_directory(changeset_path)?;
     let mut changesets_deleted = HashSet::new();
     Ok(packages
         .into_iter()
         .map(|mut package| {
-            if let Some(release_changes) = changeset.releases.remove(
+            if let Some(mut release_changes) = changeset.releases.remove(
                 package
                     .name
                     .as_deref()
                     .unwrap_or(DEFAULT_CHANGESET_PACKAGE_NAME),
             ) {
+                // The directory is read in whatever order the file system likes, so sort by file
+                // name to keep changelogs and output the same between runs
+                release_changes
+                    .changes
+                    .sort_by_cached_key(|change| change.unique_id.to_file_name());
                 package
                     .pending_changes
                     .extend(release_changes.changes.into_iter().map(|change| {
                         let file_name = change.unique_id.to_file_name();
-                        if !changesets_deleted.contains(&file_name) && !is_prerelease {
+                        if !changesets_deleted.contains(&file_name) && !keep_change_files {
                             if let Some(dry_run) = dry_run {
                                 writeln!(
                                     dry_run,
@@ -192,4 +279,29 @@ pub(crate) enum Error {
     #[error(transparent)]
     #[diagnostic(transparent)]
     Prompt(#[from] prompt::Error),
+    #[error("Unknown package {package} in `--change`")]
+    #[diagnostic(
+        code(changesets::unknown_package),
+        help("The available packages are: {available}"),
+        url("https://knope.tech/reference/config-file/steps/create-change-file/")
+    )]
+    UnknownPackage { package: String, available: String },
+    #[error("`--change {change}` doesn't say which package it's for")]
+    #[diagnostic(
+        code(changesets::missing_package),
+        help("With multiple packages, format each `--change` like `package_name=change_type`"),
+        url("https://knope.tech/reference/config-file/steps/create-change-file/")
+    )]
+    MissingPackage { change: String },
+    #[error("Unknown change type {change_type} for {package}")]
+    #[diagnostic(
+        code(changesets::unknown_change_type),
+        help("The available change types are: {available}"),
+        url("https://knope.tech/reference/config-file/steps/create-change-file/")
+    )]
+    UnknownChangeType {
+        change_type: String,
+        package: String,
+        available: String,
+    },
 }
@@ -1,14 +1,21 @@
 use std::fmt::Display;
 
-use git_conventional::{Commit, Footer, Type};
-use log::debug;
+use git_conventional::{Commit, Footer, FooterSeparator, Type};
+use indexmap::IndexMap;
+use log::{debug, warn};
 use miette::Diagnostic;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use time::Date;
 
-use super::{Change, ChangeType, Package};
+use super::{package::ChangelogSectionSource, Change, ChangeType, Package};
 use crate::{
-    config::CommitFooter,
-    integrations::git::{self, get_commit_messages_after_tag, get_current_versions_from_tags},
-    step::releases::tag_name,
+    integrations::git::{
+        self, get_commits_after_revision, get_commits_after_tag, get_current_versions_from_tags,
+        CommitInfo, Commits, ReleaseSources,
+    },
+    step::{releases::tag_name, RequireIssueReference},
     workflow::Verbose,
 };
 
@@ -22,22 +29,19 @@ pub(crate) struct ConventionalCommit {
 impl ConventionalCommit {
     fn from_commit_messages(
         commit_messages: &[String],
-        consider_scopes: bool,
+        scope_filter: ScopeFilter,
         package: &Package,
     ) -> Vec<Self> {
+        // Messages written on Windows can have `\r\n` line endings, which would leave a stray `\r` in
+        // multi-line footers (like `BREAKING CHANGE`)
+        let commit_messages = commit_messages
+            .iter()
+            .map(|message| message.replace("\r\n", "\n"))
+            .collect::<Vec<_>>();
         let commits = commit_messages
             .iter()
             .filter_map(|message| Commit::parse(message.trim()).ok())
-            .filter(|commit| {
-                if !consider_scopes {
-                    return true;
-                }
-                match (commit.scope(), &package.scopes) {
-                    (None, _) => true,
-                    (Some(_), None) => false,
-                    (Some(scope), Some(scopes)) => scopes.contains(&scope.to_string()),
-                }
-            })
+            .filter(|commit| scope_filter.applies_to_package(commit, package))
             .collect();
         debug!("Selected commits: {:?}", commits);
         Self::from_commits(package, commits)
@@ -49,11 +53,21 @@ impl ConventionalCommit {
 
         for commit in commits {
             let commit_summary = format_commit_summary(&commit);
+            let commit_type = resolve_alias(package, commit.type_());
             for footer in commit.footers() {
-                let source = CommitFooter::from(footer.token());
-                if relevant_footers.contains(&source) {
+                if footer.breaking() {
+                    // Handled below, so there is only ever one breaking change per commit
+                    continue;
+                }
+                // Footer tokens are case-insensitive, but the configured spelling is what
+                // changelog sections are keyed by.
+                let token = footer.token();
+                if let Some(source) = relevant_footers
+                    .iter()
+                    .find(|relevant| token == relevant.as_ref())
+                {
                     conventional_commits.push(Self {
-                        change_type: source.into(),
+                        change_type: source.clone().into(),
                         message: footer.value().to_string(),
                         original_source: format_commit_footer(&commit_summary, footer),
                     });
@@ -80,22 +94,178 @@ impl ConventionalCommit {
                 }
             }
 
-            if commit.type_() == Type::FEAT {
+            if is_dependency_update(&commit, package) {
+                conventional_commits.push(Self {
+                    change_type: ChangelogSectionSource::DependencyUpdate.into(),
+                    message: commit.description().to_string(),
+                    original_source: commit_summary,
+                });
+            } else if commit_type == Type::FEAT {
                 conventional_commits.push(Self {
                     change_type: ChangeType::Feature,
                     message: commit.description().to_string(),
                     original_source: commit_summary,
                 });
-            } else if commit.type_() == Type::FIX {
+            } else if commit_type == Type::FIX {
                 conventional_commits.push(Self {
                     change_type: ChangeType::Fix,
                     message: commit.description().to_string(),
                     original_source: commit_summary,
                 });
+            } else if let Some(commit_type) = package
+                .commit_types
+                .iter()
+                .find(|custom_type| custom_type.matches(commit_type.as_str()))
+            {
+                conventional_commits.push(Self {
+                    change_type: ChangelogSectionSource::CommitType(commit_type.name.clone())
+                        .into(),
+                    message: commit.description().to_string(),
+                    original_source: commit_summary,
+                });
             }
         }
         conventional_commits
     }
+
+    /// Every commit by a `dependency_updates` author is a dependency update, described by its
+    /// summary since bots don't always write conventional commits.
+    fn from_dependency_update_authors(
+        commits: &[CommitInfo],
+        scope_filter: ScopeFilter,
+        package: &Package,
+    ) -> Vec<Self> {
+        commits
+            .iter()
+            .filter_map(|commit| {
+                let message = commit.message.replace("\r\n", "\n");
+                let summary = message.trim().lines().next()?.trim().to_string();
+                let description = match Commit::parse(message.trim()) {
+                    Ok(parsed) if !scope_filter.applies_to_package(&parsed, package) => {
+                        return None
+                    }
+                    Ok(parsed) => parsed.description().to_string(),
+                    Err(_) => summary.clone(),
+                };
+                Some(Self {
+                    change_type: ChangelogSectionSource::DependencyUpdate.into(),
+                    message: description,
+                    original_source: summary,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether `commit` has one of the scopes which `dependency_updates` recognizes.
+fn is_dependency_update(commit: &Commit, package: &Package) -> bool {
+    package
+        .dependency_updates
+        .as_ref()
+        .zip(commit.scope())
+        .is_some_and(|(dependency_updates, scope)| dependency_updates.matches_scope(&scope))
+}
+
+/// The conventional commit type that `commit_type` is an alias of (ignoring case, like commit types
+/// always are) in `commit_type_aliases`, or `commit_type` itself if it isn't an alias.
+fn resolve_alias<'a>(package: &'a Package, commit_type: Type<'a>) -> Type<'a> {
+    package
+        .commit_type_aliases
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(commit_type.as_str()))
+        .map_or(commit_type, |(_, canonical)| {
+            Type::new_unchecked(canonical.as_str())
+        })
+}
+
+/// Decides which packages a commit applies to, based on its scope.
+#[derive(Clone, Copy, Debug, Default)]
+struct ScopeFilter<'a> {
+    /// Scopes only matter if any package has `scopes`, otherwise every commit applies to every
+    /// package.
+    enabled: bool,
+    /// The token of a footer (like `Affects`) which lists more packages that a commit applies to,
+    /// by scope or by name.
+    footer: Option<&'a str>,
+}
+
+impl ScopeFilter<'_> {
+    fn applies_to_package(self, commit: &Commit, package: &Package) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        let Some(scope) = commit.scope() else {
+            return true;
+        };
+        // `feat(api,cli)` applies to both the `api` and `cli` scopes
+        let in_scope = package.scopes.as_ref().is_some_and(|scopes| {
+            scope
+                .split(',')
+                .map(str::trim)
+                .any(|scope| scopes.iter().any(|expected| expected == scope))
+        });
+        in_scope || self.footer_lists(commit, package)
+    }
+
+    /// Whether the footer of `commit` (if any) lists one of the scopes of `package`, or its name.
+    fn footer_lists(self, commit: &Commit, package: &Package) -> bool {
+        let Some(token) = self.footer else {
+            return false;
+        };
+        commit
+            .footers()
+            .iter()
+            // Footer tokens are case-insensitive
+            .filter(|footer| footer.token() == token)
+            .flat_map(|footer| footer.value().split(','))
+            .map(str::trim)
+            .any(|listed| {
+                package.name.as_deref().is_some_and(|name| name == listed)
+                    || package
+                        .scopes
+                        .as_ref()
+                        .is_some_and(|scopes| scopes.iter().any(|scope| scope == listed))
+            })
+    }
+}
+
+/// The unique authors and `Co-authored-by` co-authors of the `commits` which apply to `package`,
+/// in the order they're first found. Names are compared ignoring case, and anyone in `ignore` is
+/// left out (like bots).
+fn contributors(
+    commits: &[CommitInfo],
+    scope_filter: ScopeFilter,
+    package: &Package,
+    ignore: &[String],
+) -> Vec<String> {
+    let mut contributors = IndexMap::new();
+    for commit in commits {
+        let message = commit.message.replace("\r\n", "\n");
+        if Commit::parse(message.trim())
+            .is_ok_and(|parsed| !scope_filter.applies_to_package(&parsed, package))
+        {
+            continue;
+        }
+        let co_authors = message.lines().filter_map(|line| {
+            let (token, value) = line.split_once(':')?;
+            token
+                .trim()
+                .eq_ignore_ascii_case("Co-authored-by")
+                .then(|| value.split('<').next().unwrap_or_default().trim())
+        });
+        for name in [commit.author.trim()].into_iter().chain(co_authors) {
+            if !name.is_empty()
+                && !ignore
+                    .iter()
+                    .any(|ignored| ignored.eq_ignore_ascii_case(name))
+            {
+                contributors
+                    .entry(name.to_lowercase())
+                    .or_insert_with(|| name.to_string());
+            }
+        }
+    }
+    contributors.into_values().collect()
 }
 
 fn format_commit_summary(commit: &Commit) -> String {
@@ -142,7 +312,7 @@ mod test_conventional_commits {
 
     use super::*;
     use crate::{
-        config::ChangelogSection,
+        config::{ChangelogSection, DependencyUpdates},
         step::releases::package::{ChangelogSectionSource, ChangelogSections},
     };
 
@@ -218,6 +388,97 @@ mod test_conventional_commits {
         );
     }
 
+    #[test]
+    fn breaking_combinations() {
+        let commits = vec![
+            Commit::parse("feat: no breaking change").unwrap(),
+            Commit::parse("feat!: only a bang").unwrap(),
+            Commit::parse("feat: only a footer\n\nBREAKING CHANGE: footer text").unwrap(),
+            Commit::parse("feat!: bang and footer\n\nBREAKING CHANGE: preferred footer text")
+                .unwrap(),
+        ];
+        let changelog_sections = ChangelogSections::from(vec![ChangelogSection {
+            name: "Should not duplicate breaking changes".into(),
+            footers: vec!["BREAKING CHANGE".into()],
+            types: vec![],
+        }]);
+        let package = Package {
+            changelog_sections,
+            ..Package::default()
+        };
+        let conventional_commits = ConventionalCommit::from_commits(&package, commits);
+        assert_eq!(
+            conventional_commits,
+            vec![
+                ConventionalCommit {
+                    change_type: ChangeType::Feature,
+                    message: String::from("no breaking change"),
+                    original_source: String::from("feat: no breaking change"),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Breaking,
+                    message: String::from("only a bang"),
+                    original_source: String::from("feat!: only a bang"),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Breaking,
+                    message: String::from("footer text"),
+                    original_source: String::from(
+                        "feat: only a footer\n\tContaining footer BREAKING CHANGE: footer text"
+                    ),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Feature,
+                    message: String::from("only a footer"),
+                    original_source: String::from("feat: only a footer"),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Breaking,
+                    message: String::from("preferred footer text"),
+                    original_source: String::from(
+                        "feat: bang and footer\n\tContaining footer BREAKING CHANGE: preferred footer text"
+                    ),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Feature,
+                    message: String::from("bang and footer"),
+                    original_source: String::from("feat: bang and footer"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn mixed_case_commit_types() {
+        let commits = vec![
+            Commit::parse("Feat: a capitalized feature").unwrap(),
+            Commit::parse("FIX: a shouted fix").unwrap(),
+            Commit::parse("fEaT!: a breaking feature").unwrap(),
+        ];
+        let package = Package::default();
+        let conventional_commits = ConventionalCommit::from_commits(&package, commits);
+        assert_eq!(
+            conventional_commits,
+            vec![
+                ConventionalCommit {
+                    change_type: ChangeType::Feature,
+                    message: String::from("a capitalized feature"),
+                    original_source: String::from("Feat: a capitalized feature")
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Fix,
+                    message: String::from("a shouted fix"),
+                    original_source: String::from("FIX: a shouted fix")
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Breaking,
+                    message: String::from("a breaking feature"),
+                    original_source: String::from("fEaT!: a breaking feature")
+                },
+            ]
+        );
+    }
+
     #[test]
     fn no_commits() {
         let commits = Vec::<Commit>::new();
@@ -235,7 +496,7 @@ mod test_conventional_commits {
         .map(String::from);
         let conventional_commits = ConventionalCommit::from_commit_messages(
             &commits,
-            false,
+            ScopeFilter::default(),
             &Package {
                 scopes: Some(vec![String::from("scope")]),
                 ..Package::default()
@@ -267,8 +528,14 @@ mod test_conventional_commits {
             "fix: No scope",
         ]
         .map(String::from);
-        let conventional_commits =
-            ConventionalCommit::from_commit_messages(&commits, true, &Package::default());
+        let conventional_commits = ConventionalCommit::from_commit_messages(
+            &commits,
+            ScopeFilter {
+                enabled: true,
+                footer: None,
+            },
+            &Package::default(),
+        );
         assert_eq!(
             conventional_commits,
             vec![ConventionalCommit {
@@ -289,7 +556,10 @@ mod test_conventional_commits {
         .map(String::from);
         let conventional_commits = ConventionalCommit::from_commit_messages(
             &commits,
-            true,
+            ScopeFilter {
+                enabled: true,
+                footer: None,
+            },
             &Package {
                 scopes: Some(vec![String::from("scope")]),
                 ..Package::default()
@@ -312,6 +582,379 @@ mod test_conventional_commits {
         );
     }
 
+    #[test]
+    fn scope_footer() {
+        let commits = [
+            "feat(web): Only for the web\n\nAffects: api, cli",
+            "fix(web): Not for the API",
+            "fix(web): Lists the package by name\n\naffects: server",
+        ]
+        .map(String::from);
+        let conventional_commits = ConventionalCommit::from_commit_messages(
+            &commits,
+            ScopeFilter {
+                enabled: true,
+                footer: Some("Affects"),
+            },
+            &Package {
+                name: Some("server".into()),
+                scopes: Some(vec![String::from("api")]),
+                ..Package::default()
+            },
+        );
+        assert_eq!(
+            conventional_commits,
+            vec![
+                ConventionalCommit {
+                    change_type: ChangeType::Feature,
+                    message: String::from("Only for the web"),
+                    original_source: String::from("feat(web): Only for the web"),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Fix,
+                    message: String::from("Lists the package by name"),
+                    original_source: String::from("fix(web): Lists the package by name"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn scoped_breaking_change() {
+        let commits = [
+            "feat(api)!: Breaking API change",
+            "fix(api)!: Breaking fix\n\nBREAKING CHANGE: Footer text",
+            "feat(wrong_scope)!: Wrong scope breaking change",
+        ]
+        .map(String::from);
+        let conventional_commits = ConventionalCommit::from_commit_messages(
+            &commits,
+            ScopeFilter {
+                enabled: true,
+                footer: None,
+            },
+            &Package {
+                scopes: Some(vec![String::from("api")]),
+                ..Package::default()
+            },
+        );
+        assert_eq!(
+            conventional_commits,
+            vec![
+                ConventionalCommit {
+                    change_type: ChangeType::Breaking,
+                    message: String::from("Breaking API change"),
+                    original_source: String::from("feat(api)!: Breaking API change"),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Breaking,
+                    message: String::from("Footer text"),
+                    original_source: String::from(
+                        "fix(api): Breaking fix\n\tContaining footer BREAKING CHANGE: Footer text"
+                    ),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Fix,
+                    message: String::from("Breaking fix"),
+                    original_source: String::from("fix(api): Breaking fix"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn multiple_scopes() {
+        let commits = [
+            "feat(api,cli): Feature for both",
+            "fix(cli, docs): Fix with a space after the comma",
+            "fix(docs,web): Fix for neither",
+        ]
+        .map(String::from);
+        let conventional_commits = ConventionalCommit::from_commit_messages(
+            &commits,
+            ScopeFilter {
+                enabled: true,
+                footer: None,
+            },
+            &Package {
+                scopes: Some(vec![String::from("cli")]),
+                ..Package::default()
+            },
+        );
+        assert_eq!(
+            conventional_commits,
+            vec![
+                ConventionalCommit {
+                    change_type: ChangeType::Feature,
+                    message: String::from("Feature for both"),
+                    original_source: String::from("feat(api,cli): Feature for both"),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Fix,
+                    message: String::from("Fix with a space after the comma"),
+                    original_source: String::from(
+                        "fix(cli, docs): Fix with a space after the comma"
+                    ),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let commits = [String::from(
+            "fix: a bug\r\n\r\nSome details\r\n\r\nBREAKING CHANGE: something broke\r\n  in two lines\r\nRefs: #1\r\n",
+        )];
+        let conventional_commits = ConventionalCommit::from_commit_messages(
+            &commits,
+            ScopeFilter::default(),
+            &Package::default(),
+        );
+        assert_eq!(
+            conventional_commits,
+            vec![
+                ConventionalCommit {
+                    change_type: ChangeType::Breaking,
+                    message: String::from("something broke\n  in two lines"),
+                    original_source: String::from(
+                        "fix: a bug\n\tContaining footer BREAKING CHANGE: something broke\n  in two lines"
+                    ),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Fix,
+                    message: String::from("a bug"),
+                    original_source: String::from("fix: a bug"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn contributors_from_matching_scopes() {
+        let commits = [
+            (
+                "feat(cli): A feature\r\n\r\nCo-Authored-By: Co Author <co@example.com>\r\n",
+                "Author",
+            ),
+            ("fix(docs): Not for this package", "Docs writer"),
+            ("Not a conventional commit", "Bot"),
+            (
+                "chore: Bump\n\nCo-authored-by: AUTHOR <author@example.com>",
+                "Someone",
+            ),
+        ]
+        .map(|(message, author)| CommitInfo {
+            message: String::from(message),
+            author: String::from(author),
+            is_merge: false,
+        });
+        let package = Package {
+            scopes: Some(vec![String::from("cli")]),
+            ..Package::default()
+        };
+        assert_eq!(
+            contributors(
+                &commits,
+                ScopeFilter {
+                    enabled: true,
+                    footer: None,
+                },
+                &package,
+                &[String::from("bot")],
+            ),
+            vec!["Author", "Co Author", "Someone"]
+        );
+    }
+
+    #[test]
+    fn dependency_update_scopes() {
+        let commits = vec![
+            Commit::parse("chore(deps): bump serde from 1.0.0 to 1.0.1").unwrap(),
+            Commit::parse("fix(deps-dev,cli): bump insta from 1.0.0 to 2.0.0").unwrap(),
+            Commit::parse("fix(cli): a bug").unwrap(),
+        ];
+        let package = Package {
+            dependency_updates: Some(DependencyUpdates::default()),
+            ..Package::default()
+        };
+        let conventional_commits = ConventionalCommit::from_commits(&package, commits);
+        assert_eq!(
+            conventional_commits,
+            vec![
+                ConventionalCommit {
+                    change_type: ChangelogSectionSource::DependencyUpdate.into(),
+                    message: String::from("bump serde from 1.0.0 to 1.0.1"),
+                    original_source: String::from("chore(deps): bump serde from 1.0.0 to 1.0.1"),
+                },
+                ConventionalCommit {
+                    change_type: ChangelogSectionSource::DependencyUpdate.into(),
+                    message: String::from("bump insta from 1.0.0 to 2.0.0"),
+                    original_source: String::from(
+                        "fix(deps-dev,cli): bump insta from 1.0.0 to 2.0.0"
+                    ),
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Fix,
+                    message: String::from("a bug"),
+                    original_source: String::from("fix(cli): a bug"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn dependency_update_authors() {
+        let commits = [
+            "Bump serde from 1.0.0 to 1.0.1\r\n\r\nBumps serde.",
+            "build(docs): bump astro from 4.0.0 to 4.1.0",
+        ]
+        .map(|message| CommitInfo {
+            message: String::from(message),
+            author: String::from("Dependabot[bot]"),
+            is_merge: false,
+        });
+        let package = Package {
+            scopes: Some(vec![String::from("cli")]),
+            dependency_updates: Some(DependencyUpdates::default()),
+            ..Package::default()
+        };
+        assert_eq!(
+            ConventionalCommit::from_dependency_update_authors(
+                &commits,
+                ScopeFilter {
+                    enabled: true,
+                    footer: None,
+                },
+                &package,
+            ),
+            vec![ConventionalCommit {
+                change_type: ChangelogSectionSource::DependencyUpdate.into(),
+                message: String::from("Bump serde from 1.0.0 to 1.0.1"),
+                original_source: String::from("Bump serde from 1.0.0 to 1.0.1"),
+            }]
+        );
+    }
+
+    #[test]
+    fn non_conventional_summaries_skip_merges() {
+        let commits = [
+            ("feat: A feature", false),
+            ("Fixed a bug\r\n\r\nWith details", false),
+            ("Merge branch 'main' into feature", true),
+            ("\n", false),
+        ]
+        .map(|(message, is_merge)| CommitInfo {
+            message: String::from(message),
+            author: String::from("Author"),
+            is_merge,
+        });
+        assert_eq!(
+            non_conventional_summaries(&commits),
+            vec![String::from("Fixed a bug")]
+        );
+    }
+
+    fn issue_footers(value_pattern: Option<&str>) -> RequireIssueReference {
+        RequireIssueReference {
+            footers: vec![String::from("Closes"), String::from("Jira")],
+            value_pattern: value_pattern.map(String::from),
+        }
+    }
+
+    #[test]
+    fn commits_without_issue_footer() {
+        let commits = [
+            "feat: Referenced\n\nCloses #1",
+            "fix: Referenced differently\r\n\r\nJIRA: PROJ-2",
+            "fix: Not referenced\n\nReviewed-by: Someone",
+            "fix: Not an issue\n\nCloses: TODO",
+            "feat(api)!: Not referenced either",
+            "docs: Doesn't need a reference",
+            "Not a conventional commit",
+        ]
+        .map(String::from);
+        let config = issue_footers(None);
+        assert_eq!(
+            super::commits_without_issue_footer(
+                &commits,
+                super::ScopeFilter::default(),
+                &Package::default(),
+                &IssueFooters::new(&config).unwrap()
+            ),
+            vec![
+                String::from("fix: Not referenced"),
+                String::from("fix: Not an issue"),
+                String::from("feat(api)!: Not referenced either"),
+            ]
+        );
+    }
+
+    #[test]
+    fn issue_footer_value_pattern() {
+        let commits = [
+            "feat: Matching\n\nCloses #1",
+            "fix: Not matching\n\nJira: PROJ-2",
+        ]
+        .map(String::from);
+        let config = issue_footers(Some(r"^#\d+$"));
+        assert_eq!(
+            super::commits_without_issue_footer(
+                &commits,
+                super::ScopeFilter::default(),
+                &Package::default(),
+                &IssueFooters::new(&config).unwrap()
+            ),
+            vec![String::from("fix: Not matching")]
+        );
+
+        let config = issue_footers(Some(""));
+        assert!(super::commits_without_issue_footer(
+            &["fix: Anything goes\n\nCloses: TODO".to_string()],
+            super::ScopeFilter::default(),
+            &Package::default(),
+            &IssueFooters::new(&config).unwrap()
+        )
+        .is_empty());
+
+        let config = issue_footers(Some("("));
+        assert!(matches!(
+            IssueFooters::new(&config),
+            Err(Error::InvalidIssueValuePattern { .. })
+        ));
+    }
+
+    #[test]
+    fn commit_type_aliases() {
+        let commits = vec![
+            Commit::parse("bug: a bug").unwrap(),
+            Commit::parse("FEATURE!: a breaking feature").unwrap(),
+            Commit::parse("unknown: not an alias").unwrap(),
+        ];
+        let package = Package {
+            commit_type_aliases: [("bug", "fix"), ("feature", "feat")]
+                .into_iter()
+                .map(|(alias, canonical)| (alias.to_string(), canonical.to_string()))
+                .collect(),
+            ..Package::default()
+        };
+        let conventional_commits = ConventionalCommit::from_commits(&package, commits);
+        assert_eq!(
+            conventional_commits,
+            vec![
+                ConventionalCommit {
+                    change_type: ChangeType::Fix,
+                    message: String::from("a bug"),
+                    original_source: String::from("bug: a bug")
+                },
+                ConventionalCommit {
+                    change_type: ChangeType::Breaking,
+                    message: String::from("a breaking feature"),
+                    original_source: String::from("FEATURE!: a breaking feature")
+                },
+            ]
+        );
+    }
+
     #[test]
     fn custom_footers() {
         let commits = [String::from(
@@ -324,7 +967,7 @@ mod test_conventional_commits {
         }]);
         let conventional_commits = ConventionalCommit::from_commit_messages(
             &commits,
-            false,
+            ScopeFilter::default(),
             &Package {
                 changelog_sections,
                 ..Package::default()
@@ -343,34 +986,101 @@ mod test_conventional_commits {
             },]
         );
     }
+
+    #[test]
+    fn mixed_case_custom_footers() {
+        let commits = [String::from("chore: ignored type\n\nCustom-Footer: hello")];
+        let changelog_sections = ChangelogSections::from(vec![ChangelogSection {
+            name: "custom section".into(),
+            footers: vec!["custom-footer".into()],
+            types: vec![],
+        }]);
+        let conventional_commits = ConventionalCommit::from_commit_messages(
+            &commits,
+            ScopeFilter::default(),
+            &Package {
+                changelog_sections,
+                ..Package::default()
+            },
+        );
+        assert_eq!(
+            conventional_commits,
+            vec![ConventionalCommit {
+                change_type: ChangeType::Custom(ChangelogSectionSource::CommitFooter(
+                    "custom-footer".into()
+                )),
+                message: String::from("hello"),
+                original_source: String::from(
+                    "chore: ignored type\n\tContaining footer Custom-Footer: hello"
+                ),
+            },]
+        );
+    }
 }
 
-fn get_conventional_commits_after_last_stable_version(
+fn get_commits_after_last_stable_version(
     package: &Package,
-    consider_scopes: bool,
+    scope_filter: ScopeFilter,
     verbose: Verbose,
     all_tags: &[String],
-) -> Result<Vec<ConventionalCommit>, Error> {
+    sources: ReleaseSources,
+    options: &CommitOptions,
+) -> Result<Commits, Error> {
+    let CommitOptions {
+        since,
+        since_date,
+        max_commits,
+        first_parent,
+        ..
+    } = *options;
     if let Verbose::Yes = verbose {
         println!(
             "Getting conventional commits since last release of package {}",
             package.name.as_deref().unwrap_or_default()
         );
-        if consider_scopes {
+        if scope_filter.enabled {
             if let Some(scopes) = &package.scopes {
                 println!("Only checking commits with scopes: {scopes:?}");
             }
         }
     }
-    let target_version =
-        get_current_versions_from_tags(package.name.as_deref(), verbose, all_tags).stable;
-    let tag = target_version.map(|version| tag_name(&version.into(), &package.name));
-    let commit_messages = get_commit_messages_after_tag(tag, verbose).map_err(git::Error::from)?;
-    Ok(ConventionalCommit::from_commit_messages(
-        &commit_messages,
-        consider_scopes,
-        package,
-    ))
+    let by_commit_time = package.changelog_sort.by_commit_time();
+    if let Some(revision) = since {
+        get_commits_after_revision(
+            revision,
+            since_date,
+            by_commit_time,
+            max_commits,
+            first_parent,
+            verbose,
+        )
+    } else {
+        let target_version =
+            get_current_versions_from_tags(package.name.as_deref(), verbose, all_tags).stable;
+        let tag = target_version.map(|version| {
+            let tag = tag_name(&version.into(), None, &package.name);
+            // The tag may have build metadata, which isn't part of the version
+            all_tags
+                .iter()
+                .find(|existing| {
+                    existing
+                        .strip_prefix(&tag)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('+'))
+                })
+                .cloned()
+                .unwrap_or(tag)
+        });
+        get_commits_after_tag(
+            tag,
+            sources,
+            since_date,
+            by_commit_time,
+            max_commits,
+            first_parent,
+            verbose,
+        )
+    }
+    .map_err(Error::from)
 }
 
 #[derive(Debug, Diagnostic, thiserror::Error)]
@@ -378,36 +1088,455 @@ pub(crate) enum Error {
     #[error(transparent)]
     #[diagnostic(transparent)]
     Git(#[from] git::Error),
+    #[error("Found commits which aren't conventional commits:\n{}", commits.iter().map(|commit| format!("- {commit}")).collect::<Vec<_>>().join("\n"))]
+    #[diagnostic(
+        code(conventional_commits::non_conventional_commits),
+        help("Set `non_conventional_commits` on `PrepareRelease` to \"ignore\" or \"patch\" to release anyway."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#non-conventional-commits")
+    )]
+    NonConventionalCommits { commits: Vec<String> },
+    #[error("Found feature or fix commits which don't reference an issue:\n{}", commits.iter().map(|commit| format!("- {commit}")).collect::<Vec<_>>().join("\n"))]
+    #[diagnostic(
+        code(conventional_commits::missing_issue_reference),
+        help("Add a footer which references an issue, like `Closes #123`, to each of these commits. The footer must be one of: {}", footers.join(", ")),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#require-issue-reference")
+    )]
+    MissingIssueReference {
+        commits: Vec<String>,
+        footers: Vec<String>,
+    },
+    #[error("Invalid `value_pattern` {pattern}")]
+    #[diagnostic(
+        code(conventional_commits::invalid_issue_value_pattern),
+        help("The pattern must be a valid regular expression, like `^#\\d+$`."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#require-issue-reference")
+    )]
+    InvalidIssueValuePattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+    #[error("Invalid `release_commit_pattern` {pattern}")]
+    #[diagnostic(
+        code(conventional_commits::invalid_release_commit_pattern),
+        help("The pattern must be a valid regular expression, like `^chore: release`."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#release-commits")
+    )]
+    InvalidReleaseCommitPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// The commits that earlier releases made (like `chore: prepare release 1.2.3`), which are skipped
+/// so that they don't end up in the next release.
+#[derive(Clone, Debug)]
+pub(crate) struct ReleaseCommits(Option<Regex>);
+
+impl ReleaseCommits {
+    /// Matches the commits made by the default workflows, like `chore: prepare release 1.2.3` or
+    /// `chore: prepare releases`, and similar ones like `chore(release): release v1.2.3`.
+    const DEFAULT_PATTERN: &'static str =
+        r"^chore(\(release\))?: (prepare )?(releases?$|release v?\d)";
+
+    /// Release commits matching `pattern`, the default pattern if it's `None`, or none at all if
+    /// it's empty.
+    pub(crate) fn new(pattern: Option<&str>) -> Result<Self, Error> {
+        match pattern {
+            Some("") => Ok(Self(None)),
+            Some(pattern) => Regex::new(pattern)
+                .map(|regex| Self(Some(regex)))
+                .map_err(|source| Error::InvalidReleaseCommitPattern {
+                    pattern: pattern.to_string(),
+                    source,
+                }),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Whether the first line of `message` is a release commit.
+    pub(crate) fn matches(&self, message: &str) -> bool {
+        self.0
+            .as_ref()
+            .is_some_and(|regex| regex.is_match(message.lines().next().unwrap_or_default().trim()))
+    }
 }
 
+impl Default for ReleaseCommits {
+    fn default() -> Self {
+        Self(Regex::new(Self::DEFAULT_PATTERN).ok())
+    }
+}
+
+/// The footers which reference an issue, like `Closes #123` or `Refs: PROJ-123`.
+#[derive(Clone, Debug)]
+pub(crate) struct IssueFooters<'a> {
+    /// The tokens of the footers, compared ignoring case
+    tokens: &'a [String],
+    /// What the value of one of those footers must match, any value if `None`
+    value_pattern: Option<Regex>,
+}
+
+impl<'a> IssueFooters<'a> {
+    /// Matches values which contain an issue number (`#123`), an issue key (`PROJ-123`), or a URL.
+    const DEFAULT_VALUE_PATTERN: &'static str = r"#\d+|\b[A-Z][A-Z0-9_]*-\d+\b|https?://\S+";
+
+    /// Footers with one of the configured tokens whose value matches `value_pattern`, the default
+    /// pattern if it's `None`, or any value if it's empty.
+    pub(crate) fn new(config: &'a RequireIssueReference) -> Result<Self, Error> {
+        let value_pattern = match config
+            .value_pattern
+            .as_deref()
+            .unwrap_or(Self::DEFAULT_VALUE_PATTERN)
+        {
+            "" => None,
+            pattern => {
+                Some(
+                    Regex::new(pattern).map_err(|source| Error::InvalidIssueValuePattern {
+                        pattern: pattern.to_string(),
+                        source,
+                    })?,
+                )
+            }
+        };
+        Ok(Self {
+            tokens: &config.footers,
+            value_pattern,
+        })
+    }
+
+    fn matches(&self, footer: &Footer) -> bool {
+        // Footer tokens are case-insensitive
+        if !self
+            .tokens
+            .iter()
+            .any(|token| footer.token() == token.as_str())
+        {
+            return false;
+        }
+        // The `#` of a footer like `Closes #123` is parsed as part of the separator
+        let value = match footer.separator() {
+            FooterSeparator::Ref => format!("#{}", footer.value()),
+            _ => footer.value().to_string(),
+        };
+        self.value_pattern
+            .as_ref()
+            .map_or(true, |pattern| pattern.is_match(value.trim()))
+    }
+}
+
+/// What `PrepareRelease` does with commits which aren't conventional commits.
+///
+/// Merge commits are always ignored.
+#[derive(Clone, Copy, Debug, Default, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum NonConventionalCommits {
+    /// Leave them out of the release.
+    #[default]
+    Ignore,
+    /// Stop with an error which lists them.
+    Fail,
+    /// Release each of them as a fix, using the first line of the message.
+    Patch,
+}
+
+impl NonConventionalCommits {
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Required by serde's `skip_serializing_if`
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The options of `PrepareRelease` which decide which commits are released and how they're read.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CommitOptions<'a> {
+    /// Use the commits after this revision instead of after each package's last stable release.
+    pub(crate) since: Option<&'a str>,
+    /// Leave out commits made before this date.
+    pub(crate) since_date: Option<Date>,
+    /// Check at most this many commits for each package.
+    pub(crate) max_commits: Option<usize>,
+    /// Only follow the first parent of each merge commit.
+    pub(crate) first_parent: bool,
+    /// Commits made by earlier releases, which are left out.
+    pub(crate) release_commits: ReleaseCommits,
+    /// If set, the authors of the commits (except these) are recorded as contributors.
+    pub(crate) ignore_contributors: Option<&'a [String]>,
+    /// What to do with commits which aren't conventional commits.
+    pub(crate) non_conventional_commits: NonConventionalCommits,
+    /// If set, every feature and fix commit must have one of these footers.
+    pub(crate) issue_footers: Option<IssueFooters<'a>>,
+    /// A footer which lists more packages that a commit applies to.
+    pub(crate) scope_footer: Option<&'a str>,
+    /// Remove a leading emoji (like gitmoji) from each commit message before parsing it.
+    pub(crate) strip_emoji: bool,
+}
+
+/// Add the conventional commits since each package's last stable release (or since
+/// `options.since`) to its pending changes.
+///
+/// The commits of releases in `tags` which aren't local tags are found in `sources`.
+///
+/// If any package has more than `options.max_commits` commits to check, a single warning is logged.
 pub(crate) fn add_releases_from_conventional_commits(
     packages: Vec<Package>,
     tags: &[String],
+    sources: ReleaseSources,
+    options: &CommitOptions,
     verbose: Verbose,
 ) -> Result<Vec<Package>, Error> {
-    let consider_scopes = packages.iter().any(|package| package.scopes.is_some());
-    packages
+    let scope_filter = ScopeFilter {
+        enabled: packages.iter().any(|package| package.scopes.is_some()),
+        footer: options.scope_footer,
+    };
+    let mut truncated_at: Vec<String> = Vec::new();
+    let packages = packages
         .into_iter()
-        .map(|package| add_release_for_package(package, consider_scopes, tags, verbose))
+        .map(|package| {
+            let Commits {
+                commits,
+                truncated_at: boundary,
+            } = get_commits_after_last_stable_version(
+                &package,
+                scope_filter,
+                verbose,
+                tags,
+                sources,
+                options,
+            )?;
+            if let Some(boundary) = boundary.filter(|boundary| !truncated_at.contains(boundary)) {
+                truncated_at.push(boundary);
+            }
+            add_release_for_package(package, commits, scope_filter, options)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if let (Some(max_commits), false) = (options.max_commits, truncated_at.is_empty()) {
+        warn!(
+            "Found more than {max_commits} commits to check, only using the commits back to {}. \
+            If this is unexpected, make sure the last release was tagged.",
+            truncated_at.join(", ")
+        );
+    }
+    Ok(packages)
+}
+
+/// Remove one leading emoji, either a Unicode one (like `✨`) or a `:shortcode:` (like
+/// `:sparkles:`), and the whitespace after it.
+///
+/// Messages which don't start with an emoji are returned as-is.
+fn strip_leading_emoji(message: &str) -> &str {
+    let trimmed = message.trim_start();
+    let after_emoji = if let Some(rest) = trimmed.strip_prefix(':') {
+        rest.find(':')
+            .filter(|&end| {
+                end > 0
+                    && rest.get(..end).is_some_and(|shortcode| {
+                        shortcode.chars().all(|char| {
+                            char.is_ascii_alphanumeric() || matches!(char, '_' | '-' | '+')
+                        })
+                    })
+            })
+            .and_then(|end| rest.get(end + 1..))
+    } else {
+        let rest = trimmed.trim_start_matches(is_emoji_char);
+        (rest.len() < trimmed.len()).then_some(rest)
+    };
+    after_emoji.map_or(message, str::trim_start)
+}
+
+/// Whether `char` is part of a Unicode emoji, including the joiners and modifiers between them.
+fn is_emoji_char(char: char) -> bool {
+    matches!(
+        u32::from(char),
+        0x200D // Zero width joiner
+            | 0x20E3 // Combining enclosing keycap
+            | 0x2300..=0x23FF // Miscellaneous technical, like ⏪
+            | 0x2600..=0x27BF // Miscellaneous symbols and dingbats, like ✨
+            | 0x2B00..=0x2BFF // Arrows, like ⬆
+            | 0xFE0F // Variation selector
+            | 0x1F000..=0x1FAFF // Most emoji
+    )
+}
+
+#[cfg(test)]
+mod test_strip_leading_emoji {
+    use super::strip_leading_emoji;
+
+    #[test]
+    fn unicode() {
+        assert_eq!(strip_leading_emoji("✨ feat: Add thing"), "feat: Add thing");
+        assert_eq!(strip_leading_emoji("⬆️ fix: Upgrade"), "fix: Upgrade");
+        assert_eq!(strip_leading_emoji("🧑‍💻feat: Joined"), "feat: Joined");
+    }
+
+    #[test]
+    fn shortcode() {
+        assert_eq!(
+            strip_leading_emoji(":sparkles: feat: Add thing"),
+            "feat: Add thing"
+        );
+        assert_eq!(strip_leading_emoji(":+1: fix: Thanks"), "fix: Thanks");
+    }
+
+    #[test]
+    fn no_emoji() {
+        assert_eq!(strip_leading_emoji("feat: Add thing"), "feat: Add thing");
+        assert_eq!(
+            strip_leading_emoji(":not a shortcode: feat"),
+            ":not a shortcode: feat"
+        );
+        assert_eq!(strip_leading_emoji("feat: ✨"), "feat: ✨");
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test_release_commits {
+    use super::ReleaseCommits;
+
+    #[test]
+    fn default_pattern() {
+        let release_commits = ReleaseCommits::new(None).unwrap();
+        assert!(release_commits.matches("chore: prepare release 1.2.3"));
+        assert!(release_commits.matches("chore: prepare releases\n\nWith a body"));
+        assert!(release_commits.matches("chore(release): release v1.2.3"));
+        assert!(release_commits.matches("chore: prepare release"));
+        assert!(!release_commits.matches("chore: release notes are nicer now"));
+        assert!(!release_commits.matches("feat: prepare release branches"));
+    }
+
+    #[test]
+    fn custom_pattern() {
+        let release_commits = ReleaseCommits::new(Some("^release: ")).unwrap();
+        assert!(release_commits.matches("release: 1.2.3"));
+        assert!(!release_commits.matches("chore: prepare release 1.2.3"));
+    }
+
+    #[test]
+    fn empty_pattern() {
+        let release_commits = ReleaseCommits::new(Some("")).unwrap();
+        assert!(!release_commits.matches("chore: prepare release 1.2.3"));
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(ReleaseCommits::new(Some("(")).is_err());
+    }
+}
+
+/// The first line of each commit (other than merges) which isn't a conventional commit.
+fn non_conventional_summaries(commits: &[CommitInfo]) -> Vec<String> {
+    commits
+        .iter()
+        .filter(|commit| !commit.is_merge)
+        .map(|commit| commit.message.replace("\r\n", "\n"))
+        .filter(|message| !message.trim().is_empty() && Commit::parse(message.trim()).is_err())
+        .filter_map(|message| message.lines().next().map(|line| line.trim().to_string()))
+        .collect()
+}
+
+/// The summary of each feature or fix commit (which applies to `package`) that has none of the
+/// `footers`.
+fn commits_without_issue_footer(
+    commit_messages: &[String],
+    scope_filter: ScopeFilter,
+    package: &Package,
+    footers: &IssueFooters,
+) -> Vec<String> {
+    commit_messages
+        .iter()
+        .map(|message| message.replace("\r\n", "\n"))
+        .filter_map(|message| {
+            let commit = Commit::parse(message.trim()).ok()?;
+            let commit_type = resolve_alias(package, commit.type_());
+            let has_issue_footer = commit
+                .footers()
+                .iter()
+                .any(|footer| footers.matches(footer));
+            (scope_filter.applies_to_package(&commit, package)
+                && (commit_type == Type::FEAT || commit_type == Type::FIX)
+                && !has_issue_footer)
+                .then(|| format_commit_summary(&commit))
+        })
         .collect()
 }
 
 fn add_release_for_package(
     mut package: Package,
-    consider_scopes: bool,
-    tags: &[String],
-    verbose: Verbose,
+    mut commits: Vec<CommitInfo>,
+    scope_filter: ScopeFilter,
+    options: &CommitOptions,
 ) -> Result<Package, Error> {
-    get_conventional_commits_after_last_stable_version(&package, consider_scopes, verbose, tags)
-        .map(|commits| {
-            if commits.is_empty() {
-                package
-            } else {
-                package.pending_changes = commits
-                    .into_iter()
-                    .map(Change::ConventionalCommit)
-                    .collect();
-                package
+    let CommitOptions {
+        ref release_commits,
+        ignore_contributors,
+        non_conventional_commits,
+        ref issue_footers,
+        strip_emoji,
+        ..
+    } = *options;
+    commits.retain(|commit| !release_commits.matches(&commit.message));
+    if strip_emoji {
+        for commit in &mut commits {
+            commit.message = strip_leading_emoji(&commit.message).to_string();
+        }
+    }
+    if let Some(ignore) = ignore_contributors {
+        package.contributors = contributors(&commits, scope_filter, &package, ignore);
+    }
+    let (dependency_updates, commits): (Vec<_>, Vec<_>) = commits.into_iter().partition(|commit| {
+        package
+            .dependency_updates
+            .as_ref()
+            .is_some_and(|dependency_updates| dependency_updates.matches_author(&commit.author))
+    });
+    let non_conventional = non_conventional_summaries(&commits);
+    let commit_messages = commits
+        .into_iter()
+        .map(|commit| commit.message)
+        .collect::<Vec<_>>();
+    if let Some(footers) = issue_footers {
+        let missing =
+            commits_without_issue_footer(&commit_messages, scope_filter, &package, footers);
+        if !missing.is_empty() {
+            return Err(Error::MissingIssueReference {
+                commits: missing,
+                footers: footers.tokens.to_vec(),
+            });
+        }
+    }
+    let mut conventional_commits =
+        ConventionalCommit::from_commit_messages(&commit_messages, scope_filter, &package);
+    match non_conventional_commits {
+        NonConventionalCommits::Ignore => {}
+        NonConventionalCommits::Fail => {
+            if !non_conventional.is_empty() {
+                return Err(Error::NonConventionalCommits {
+                    commits: non_conventional,
+                });
             }
-        })
+        }
+        NonConventionalCommits::Patch => {
+            conventional_commits.extend(non_conventional.into_iter().map(|summary| {
+                ConventionalCommit {
+                    change_type: ChangeType::Fix,
+                    message: summary.clone(),
+                    original_source: summary,
+                }
+            }));
+        }
+    }
+    conventional_commits.extend(ConventionalCommit::from_dependency_update_authors(
+        &dependency_updates,
+        scope_filter,
+        &package,
+    ));
+    if !conventional_commits.is_empty() {
+        package.pending_changes = conventional_commits
+            .into_iter()
+            .map(Change::ConventionalCommit)
+            .collect();
+    }
+    Ok(package)
 }
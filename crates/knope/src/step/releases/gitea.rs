@@ -1,25 +1,58 @@
 use miette::{diagnostic, Diagnostic};
 
-use super::{PackageName, Release, TimeError};
+use relative_path::RelativePathBuf;
+
+use super::{changelog::ReleaseNotes, ExistingTag, Package, Release, TimeError};
 use crate::{config, dry_run::DryRun, integrations::gitea as api, state};
 
+/// Create a Gitea release for `tag`.
+///
+/// `existing_tag` is set if `tag` already exists. Gitea can't move tags, so only skipping works,
+/// which leaves an existing release for the tag alone.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn release(
-    package_name: Option<&PackageName>,
+    package: &Package,
     release: &Release,
     gitea_state: state::Gitea,
     gitea_config: &config::Gitea,
     dry_run_stdout: DryRun,
     tag: &str,
+    existing_tag: Option<ExistingTag>,
+    release_notes: &ReleaseNotes,
 ) -> Result<state::Gitea, Error> {
+    let mut gitea_state = gitea_state;
+    match existing_tag {
+        Some(ExistingTag::Force) => {
+            return Err(Error::CannotMoveTag {
+                tag: tag.to_string(),
+            })
+        }
+        Some(_) => {
+            let (release_exists, new_state) =
+                api::release_exists(tag, gitea_state, gitea_config, dry_run_stdout)?;
+            if release_exists {
+                return Ok(new_state);
+            }
+            gitea_state = new_state;
+        }
+        None => {}
+    }
     let version = &release.version;
-    let mut name = if let Some(package_name) = package_name {
+    let mut name = if let Some(package_name) = package.name.as_ref() {
         format!("{package_name} ")
     } else {
         String::new()
     };
     name.push_str(&release.title(false, true)?);
 
-    let body = release.body_at_h1().map(|body| body.trim().to_string());
+    let changelog_url = package.changelog.as_ref().and_then(|changelog| {
+        RelativePathBuf::from_path(&changelog.path)
+            .ok()
+            .map(|path| gitea_config.get_file_url(tag, &path))
+    });
+    let body = release
+        .summarized_body_at_h1(release_notes, changelog_url.as_deref())
+        .map(|body| body.trim().to_string());
 
     api::create_release(
         &name,
@@ -40,5 +73,12 @@ pub(crate) enum Error {
     Api(#[from] api::CreateReleaseError),
     #[error(transparent)]
     #[diagnostic(transparent)]
-    TimeError(#[from] TimeError),
+    Time(#[from] TimeError),
+    #[error("Tag {tag} already exists, and Gitea can't move it")]
+    #[diagnostic(
+        code(gitea::cannot_move_tag),
+        help("Delete the tag yourself, or use `existing_tag = \"skip\"` instead."),
+        url("https://knope.tech/reference/config-file/steps/release/#existing-tags")
+    )]
+    CannotMoveTag { tag: String },
 }
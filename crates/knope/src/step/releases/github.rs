@@ -1,26 +1,52 @@
 use miette::{diagnostic, Diagnostic};
 
-use super::{package::Asset, PackageName, Release, TimeError};
-use crate::{config::GitHub, dry_run::DryRun, integrations::github as api, state};
+use relative_path::RelativePathBuf;
 
+use super::{changelog::ReleaseNotes, ExistingTag, Package, Release, TimeError};
+use crate::{
+    config::GitHub,
+    dry_run::DryRun,
+    integrations::{git, github as api},
+    state,
+};
+
+/// Create a GitHub release for `tag`.
+///
+/// `existing_tag` is set if `tag` already exists, and decides whether the tag is moved first.
+/// Either way, an existing release for the tag is left alone.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn release(
-    package_name: Option<&PackageName>,
+    package: &Package,
     release: &Release,
     github_state: state::GitHub,
     github_config: &GitHub,
     dry_run_stdout: DryRun,
-    assets: Option<&Vec<Asset>>,
     tag: &str,
+    existing_tag: Option<ExistingTag>,
+    release_notes: &ReleaseNotes,
 ) -> Result<state::GitHub, Error> {
+    let mut github_state = github_state;
+    if let Some(existing_tag) = existing_tag {
+        if existing_tag == ExistingTag::Force {
+            let sha = git::head_commit_sha()?;
+            github_state = api::move_tag(tag, &sha, github_state, github_config, dry_run_stdout)?;
+        }
+        let (release_exists, new_state) =
+            api::release_exists(tag, github_state, github_config, dry_run_stdout)?;
+        if release_exists {
+            return Ok(new_state);
+        }
+        github_state = new_state;
+    }
     let version = &release.version;
-    let mut name = if let Some(package_name) = package_name {
+    let mut name = if let Some(package_name) = package.name.as_ref() {
         format!("{package_name} ")
     } else {
         String::new()
     };
     name.push_str(&release.title(false, true)?);
 
-    let body = release.body_at_h1().map(|body| body.trim().to_string());
+    let body = release_body(package, release, github_config, tag, release_notes);
 
     api::create_release(
         &name,
@@ -30,11 +56,54 @@ pub(crate) fn release(
         github_state,
         github_config,
         dry_run_stdout,
-        assets,
+        package.assets.as_ref(),
     )
     .map_err(Error::from)
 }
 
+/// Replace the body of the existing GitHub release for `tag` with the notes of `release`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_release(
+    package: &Package,
+    release: &Release,
+    github_state: state::GitHub,
+    github_config: &GitHub,
+    dry_run_stdout: DryRun,
+    tag: &str,
+    release_notes: &ReleaseNotes,
+    draft: Option<bool>,
+    prerelease: Option<bool>,
+) -> Result<state::GitHub, api::UpdateReleaseError> {
+    let body = release_body(package, release, github_config, tag, release_notes);
+    api::update_release(
+        tag,
+        body.as_deref().unwrap_or_default(),
+        draft,
+        prerelease,
+        github_state,
+        github_config,
+        dry_run_stdout,
+    )
+}
+
+/// The notes of `release`, shortened as `release_notes` says, linking to the changelog at `tag`.
+fn release_body(
+    package: &Package,
+    release: &Release,
+    github_config: &GitHub,
+    tag: &str,
+    release_notes: &ReleaseNotes,
+) -> Option<String> {
+    let changelog_url = package.changelog.as_ref().and_then(|changelog| {
+        RelativePathBuf::from_path(&changelog.path)
+            .ok()
+            .map(|path| github_config.get_file_url(tag, &path))
+    });
+    release
+        .summarized_body_at_h1(release_notes, changelog_url.as_deref())
+        .map(|body| body.trim().to_string())
+}
+
 #[derive(Debug, Diagnostic, thiserror::Error)]
 pub(crate) enum Error {
     #[error(transparent)]
@@ -42,5 +111,8 @@ pub(crate) enum Error {
     Api(#[from] api::CreateReleaseError),
     #[error(transparent)]
     #[diagnostic(transparent)]
-    TimeError(#[from] TimeError),
+    Time(#[from] TimeError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::Error),
 }
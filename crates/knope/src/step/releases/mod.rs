@@ -1,21 +1,39 @@
-use std::{collections::BTreeMap, fmt, fmt::Display};
+use std::{collections::BTreeMap, fmt, fmt::Display, iter, mem, path::Path, str::FromStr};
 
 use ::changesets::PackageChange;
-use conventional_commits::{add_releases_from_conventional_commits, ConventionalCommit};
+use conventional_commits::{
+    add_releases_from_conventional_commits, CommitOptions, ConventionalCommit, IssueFooters,
+    ReleaseCommits,
+};
 use itertools::Itertools;
-use knope_versioning::{PreVersion, StableVersion, Version};
+use knope_versioning::{Action, BuildMetadata, PreVersion, StableVersion, Version};
 use miette::Diagnostic;
 pub(crate) use non_empty_map::PrereleaseMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use time::{macros::format_description, Date, OffsetDateTime};
 
 pub(crate) use self::{
-    changelog::Release,
-    changesets::{create_change_file, ChangeType},
-    package::{Package, PackageName},
-    semver::{bump_version_and_update_state, Rule},
+    changelog::{ExistingEntry, Release, ReleaseNotes},
+    changesets::{create_change_file, ChangeFileArgs, ChangeType, DEFAULT_CHANGE_DIRECTORY},
+    conventional_commits::NonConventionalCommits,
+    package::{Package, PackageName, PackageOrder},
+    semver::{bump_version_and_update_state, Rule, UpdatePackageVersionError},
 };
 use crate::{
-    integrations::git::{create_tag, get_current_versions_from_tags},
-    step::PrepareRelease,
+    config,
+    dry_run::DryRun,
+    fs,
+    integrations::{
+        git,
+        git::{create_tag, get_current_versions_from_tags, tag_exists},
+        github as github_api,
+    },
+    state,
+    state::State,
+    step::{PrepareRelease, SeparateCommits},
+    variables,
+    variables::replace_variables,
     workflow::Verbose,
     RunType,
 };
@@ -33,10 +51,7 @@ pub(crate) fn prepare_release(
     run_type: RunType,
     prepare_release: &PrepareRelease,
 ) -> Result<RunType, Error> {
-    let (mut state, mut dry_run_stdout) = match run_type {
-        RunType::DryRun { state, stdout } => (state, Some(stdout)),
-        RunType::Real(state) => (state, None),
-    };
+    let (mut state, mut dry_run_stdout) = run_type.decompose();
     if state.packages.is_empty() {
         return Err(package::Error::NoDefinedPackages.into());
     }
@@ -44,53 +59,356 @@ pub(crate) fn prepare_release(
         prerelease_label,
         allow_empty,
         ignore_conventional_commits,
+        since,
+        since_date,
+        max_commits,
+        first_parent,
+        release_commit_pattern,
+        contributors,
+        ignore_contributors,
+        keep_change_files,
+        separate_commits,
+        non_conventional_commits,
+        require_issue_reference,
+        scope_footer,
+        prerelease_only,
+        existing_changelog_entry,
+        package_order,
+        strip_emoji,
+        previous_version_source,
+        build_metadata,
     } = prepare_release;
+    package_order.sort(&mut state.packages);
+    let build_metadata = resolve_build_metadata(build_metadata.as_ref())?;
+    let commit_options = CommitOptions {
+        since: since.as_deref(),
+        since_date: since_date.as_deref().map(parse_since_date).transpose()?,
+        max_commits: *max_commits,
+        first_parent: *first_parent,
+        release_commits: ReleaseCommits::new(release_commit_pattern.as_deref())?,
+        ignore_contributors: contributors.then_some(ignore_contributors.as_slice()),
+        non_conventional_commits: *non_conventional_commits,
+        issue_footers: require_issue_reference
+            .as_ref()
+            .map(IssueFooters::new)
+            .transpose()?,
+        scope_footer: scope_footer.as_deref(),
+        strip_emoji: *strip_emoji,
+    };
+    let forge_tags = forge_tags(*previous_version_source, &mut state, &mut dry_run_stdout)?;
+    let sources = git::ReleaseSources {
+        notes_ref: state.git_config.notes_ref.as_deref(),
+        forge_tags: &forge_tags,
+    };
+    // Pre-releases keep change files, so they end up in the next full release
+    let keep_change_files = *keep_change_files || prerelease_label.is_some();
     let packages = if *ignore_conventional_commits {
         state.packages
     } else {
-        add_releases_from_conventional_commits(state.packages, &state.all_git_tags, state.verbose)
-            .map_err(Error::from)?
+        add_releases_from_conventional_commits(
+            state.packages,
+            &state.all_git_tags,
+            sources,
+            &commit_options,
+            state.verbose,
+        )?
     };
-    state.packages = changesets::add_releases_from_changeset(
+    let packages = changesets::add_releases_from_changeset(
         packages,
-        prerelease_label.is_some(),
+        keep_change_files,
+        &state.change_directory,
         &mut dry_run_stdout,
-    )
-    .map_err(Error::from)
-    .and_then(|packages| {
-        packages
-            .into_iter()
-            .map(|package| {
-                package
-                    .write_release(
-                        prerelease_label,
-                        &state.all_git_tags,
-                        &mut dry_run_stdout,
-                        state.verbose,
-                    )
-                    .map_err(Error::from)
-            })
-            .collect()
-    })?;
+    )?;
+    state.packages = packages
+        .into_iter()
+        .map(|package| {
+            package.write_release(
+                prerelease_label,
+                *prerelease_only,
+                *existing_changelog_entry,
+                keep_change_files,
+                &state.change_directory,
+                build_metadata.as_ref(),
+                &state.all_git_tags,
+                &mut dry_run_stdout,
+                state.verbose,
+            )
+        })
+        .try_collect()?;
+    update_dependencies(&mut state.packages, &mut dry_run_stdout)?;
+    state.released_packages = released_package_names(&state.packages);
+    write_output_file(&state, &mut dry_run_stdout)?;
+
+    let any_release = state
+        .packages
+        .iter()
+        .any(|package| package.prepared_release.is_some());
+    if let (Some(separate_commits), true) = (separate_commits, any_release) {
+        commit_separately(&state, separate_commits, &mut dry_run_stdout)?;
+    }
 
     if let Some(stdout) = dry_run_stdout {
         Ok(RunType::DryRun { state, stdout })
-    } else if !*allow_empty
-        && state
-            .packages
-            .iter()
-            .filter(|package| package.prepared_release.is_some())
-            .count()
-            == 0
-    {
+    } else if !*allow_empty && !any_release {
         Err(Error::NoRelease)
     } else {
         Ok(RunType::Real(state))
     }
 }
 
+/// Update everything that depends on the new versions of `packages`, like the dependencies of other
+/// packages and lockfiles.
+fn update_dependencies(packages: &mut [Package], dry_run: DryRun) -> Result<(), Error> {
+    package::update_workspace_dependencies(packages, dry_run)?;
+    package::update_package_json_dependencies(packages, dry_run)?;
+    package::update_lockfiles(packages, dry_run)?;
+    for package in packages {
+        package.pending_writes.clear();
+    }
+    Ok(())
+}
+
+/// The names of the `packages` with a prepared release.
+fn released_package_names(packages: &[Package]) -> Vec<String> {
+    packages
+        .iter()
+        .filter(|package| package.prepared_release.is_some())
+        .filter_map(|package| package.name.as_ref().map(ToString::to_string))
+        .collect()
+}
+
+/// Append the new version of each package with a prepared release to the `--output-file`, if any,
+/// as lines like `version=1.2.3` (or `name_version=1.2.3` for named packages), which is the format
+/// of `$GITHUB_OUTPUT`.
+pub(crate) fn write_output_file(state: &State, dry_run: DryRun) -> Result<(), fs::Error> {
+    let Some(path) = state.output_file.as_deref() else {
+        return Ok(());
+    };
+    let mut lines = String::new();
+    for package in &state.packages {
+        let Some(release) = package.prepared_release.as_ref() else {
+            continue;
+        };
+        let version = release.version.with_build(release.build_metadata.as_ref());
+        if let Some(name) = package.name.as_ref() {
+            lines.push_str(&format!("{name}_version={version}\n"));
+        }
+        if state.packages.len() == 1 {
+            lines.push_str(&format!("version={version}\n"));
+        }
+    }
+    if lines.is_empty() {
+        return Ok(());
+    }
+    fs::append(dry_run, path, &lines)
+}
+
+/// The build metadata for new versions from `source`, if set.
+fn resolve_build_metadata(
+    source: Option<&BuildMetadataSource>,
+) -> Result<Option<BuildMetadata>, Error> {
+    let Some(source) = source else {
+        return Ok(None);
+    };
+    let build_metadata = match source {
+        BuildMetadataSource::Date => OffsetDateTime::now_utc()
+            .date()
+            .format(format_description!("[year][month][day]"))
+            .map_err(TimeError::from)?,
+        BuildMetadataSource::CommitHash => git::head_commit_sha()?.chars().take(7).collect(),
+        BuildMetadataSource::Literal(literal) => literal.clone(),
+    };
+    BuildMetadata::from_str(&build_metadata)
+        .map(Some)
+        .map_err(|_| Error::InvalidBuildMetadata { build_metadata })
+}
+
+/// The date from `since_date`, which must be like `2024-01-31`.
+fn parse_since_date(since_date: &str) -> Result<Date, Error> {
+    Date::parse(
+        since_date.trim(),
+        format_description!("[year]-[month]-[day]"),
+    )
+    .map_err(|err| Error::InvalidSinceDate {
+        since_date: since_date.to_string(),
+        reason: err.to_string(),
+    })
+}
+
+/// The release tags from `previous_version_source`, if it's a forge, which replace the local tags
+/// in `state` (except in a dry run).
+fn forge_tags(
+    previous_version_source: PreviousVersionSource,
+    state: &mut State,
+    dry_run: DryRun,
+) -> Result<Vec<git::ForgeTag>, Error> {
+    match previous_version_source {
+        PreviousVersionSource::Tags => Ok(Vec::new()),
+        PreviousVersionSource::GitHub => {
+            let github_config =
+                state
+                    .github_config
+                    .as_ref()
+                    .ok_or(Error::PreviousVersionSourceNotConfigured {
+                        forge: previous_version_source,
+                    })?;
+            let (forge_tags, github_state) = github_api::release_tags(
+                mem::replace(&mut state.github, state::GitHub::New),
+                github_config,
+                dry_run,
+            )?;
+            state.github = github_state;
+            if let Some(forge_tags) = &forge_tags {
+                state.all_git_tags = forge_tags.iter().map(|tag| tag.name.clone()).collect();
+            }
+            Ok(forge_tags.unwrap_or_default())
+        }
+    }
+}
+
+/// Commit everything that [`prepare_release`] staged except the changelogs, then commit the
+/// changelogs on their own.
+fn commit_separately(
+    state: &State,
+    separate_commits: &SeparateCommits,
+    dry_run: DryRun,
+) -> Result<(), Error> {
+    let version_message = replace_variables(separate_commits.version_message.clone(), state)?;
+    let changelog_message = replace_variables(separate_commits.changelog_message.clone(), state)?;
+    let changelogs = state
+        .packages
+        .iter()
+        .filter(|package| package.prepared_release.is_some())
+        .filter_map(|package| package.changelog.as_ref())
+        .map(|changelog| changelog.path.clone())
+        .collect_vec();
+
+    git::unstage_files(dry_run, &changelogs)?;
+    // Nothing was really staged in a dry run, so assume the release changed more than changelogs
+    if dry_run.is_some() || git::has_staged_changes()? {
+        git::commit(dry_run, &version_message, &state.git_config)?;
+    }
+    if !changelogs.is_empty() {
+        git::add_files(dry_run, &changelogs)?;
+        git::commit(dry_run, &changelog_message, &state.git_config)?;
+    }
+    Ok(())
+}
+
+/// Print the changelog section that each package would get from its change files, without
+/// bumping versions or deleting the change files like [`prepare_release`] does.
+pub(crate) fn preview_changelog(run_type: RunType) -> Result<RunType, Error> {
+    let (state, mut dry_run_stdout) = run_type.decompose();
+    if state.packages.is_empty() {
+        return Err(package::Error::NoDefinedPackages.into());
+    }
+    // Deleting change files is only ever pretended, into a sink, so they stay in place
+    let packages = changesets::add_releases_from_changeset(
+        state.packages.clone(),
+        false,
+        &state.change_directory,
+        &mut Some(Box::new(std::io::sink())),
+    )?;
+    let mut previews = Vec::new();
+    for package in &packages {
+        if package.pending_changes.is_empty() {
+            continue;
+        }
+        let Some(version) =
+            package.next_version(&None, false, &state.all_git_tags, state.verbose)?
+        else {
+            continue;
+        };
+        let release = package.release_from_pending_changes(version, Vec::new());
+        let mut preview = release.title(true, true)?;
+        if let Some(body) = release.body() {
+            preview.push_str("\n\n");
+            preview.push_str(&body);
+        }
+        if let Some(name) = &package.name {
+            preview = format!("Changelog for {name}:\n{preview}");
+        }
+        previews.push(preview);
+    }
+    let output = if previews.is_empty() {
+        String::from("There are no change files to preview")
+    } else {
+        previews.join("\n\n")
+    };
+    if let Some(stdout) = dry_run_stdout.as_mut() {
+        writeln!(stdout, "{output}").map_err(fs::Error::Stdout)?;
+    } else {
+        println!("{output}");
+    }
+    Ok(RunType::recompose(state, dry_run_stdout))
+}
+
+/// The version that [`prepare_release`] (with the default options) would release `package` at,
+/// if any of its commits or change files bump it.
+pub(crate) fn next_version(
+    package: &Package,
+    git_tags: &[String],
+    sources: git::ReleaseSources,
+    change_directory: &Path,
+    verbose: Verbose,
+) -> Result<Option<Version>, Error> {
+    let packages = add_releases_from_conventional_commits(
+        vec![package.clone()],
+        git_tags,
+        sources,
+        &CommitOptions::default(),
+        verbose,
+    )?;
+    // Deleting change files is only ever pretended, into a sink, so they stay in place
+    let packages = changesets::add_releases_from_changeset(
+        packages,
+        false,
+        change_directory,
+        &mut Some(Box::new(std::io::sink())),
+    )?;
+    let Some(package) = packages.first() else {
+        return Ok(None);
+    };
+    Ok(package.next_version(&None, false, git_tags, verbose)?)
+}
+
+/// Overwrite the version in every versioned file of each package with a `source_of_truth` to match
+/// that file.
+pub(crate) fn sync_versions(run_type: RunType) -> Result<RunType, Error> {
+    let (state, mut dry_run_stdout) = run_type.decompose();
+    let mut packages_to_sync = state
+        .packages
+        .iter()
+        .filter(|package| package.source_of_truth.is_some())
+        .filter_map(|package| Some((package.files.clone()?, package.go_versioning)))
+        .peekable();
+    if packages_to_sync.peek().is_none() {
+        return Err(package::Error::NoSourceOfTruth.into());
+    }
+    for (files, go_versioning) in packages_to_sync {
+        let version = files.get_version().to_string();
+        let actions = files
+            .sync_versions(go_versioning)
+            .map_err(UpdatePackageVersionError::from)
+            .map_err(package::Error::from)?;
+        for action in actions {
+            if let Action::WriteToFile { path, content } = action {
+                fs::write(&mut dry_run_stdout, &version, &path.to_path(""), content)?;
+            }
+        }
+    }
+    Ok(RunType::recompose(state, dry_run_stdout))
+}
+
 pub(crate) fn bump_version(run_type: RunType, rule: &Rule) -> Result<RunType, Error> {
-    bump_version_and_update_state(run_type, rule).map_err(Error::from)
+    let (mut state, mut dry_run_stdout) =
+        bump_version_and_update_state(run_type, rule)?.decompose();
+    package::update_package_json_dependencies(&state.packages, &mut dry_run_stdout)?;
+    package::update_lockfiles(&state.packages, &mut dry_run_stdout)?;
+    for package in &mut state.packages {
+        package.pending_writes.clear();
+    }
+    Ok(RunType::recompose(state, dry_run_stdout))
 }
 
 #[derive(Debug, Diagnostic, thiserror::Error)]
@@ -110,6 +428,20 @@ pub(crate) enum Error {
         url("https://knope.tech/reference/config-file/steps/prepare-release/#errors"),
     )]
     NoRelease,
+    #[error("No changelog has a release to publish")]
+    #[diagnostic(
+        code(releases::no_changelog_release),
+        help("With `from_changelog`, each package needs a `changelog` with a heading for a version, like `## 1.2.3`."),
+        url("https://knope.tech/reference/config-file/steps/release/#from-the-changelog"),
+    )]
+    NoChangelogRelease,
+    #[error("Tag {tag} already exists")]
+    #[diagnostic(
+        code(releases::tag_exists),
+        help("If this version was already released, set `existing_tag` on the `Release` or `Tag` step to \"skip\" or \"force\"."),
+        url("https://knope.tech/reference/config-file/steps/release/#existing-tags"),
+    )]
+    TagExists { tag: String },
     #[error(transparent)]
     #[diagnostic(transparent)]
     Semver(#[from] semver::Error),
@@ -127,6 +459,40 @@ pub(crate) enum Error {
     GitHub(#[from] github::Error),
     #[error(transparent)]
     #[diagnostic(transparent)]
+    GitHubReleaseTags(#[from] github_api::ReleaseTagsError),
+    #[error("`previous_version_source` is {forge}, which isn't configured")]
+    #[diagnostic(
+        code(releases::previous_version_source_not_configured),
+        help("Configure {forge} in knope.toml, or remove `previous_version_source` from `PrepareRelease`."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#previous-version-source")
+    )]
+    PreviousVersionSourceNotConfigured { forge: PreviousVersionSource },
+    #[error("Invalid `since_date` {since_date}: {reason}")]
+    #[diagnostic(
+        code(releases::invalid_since_date),
+        help("Use a date like `2024-01-31` (year, month, then day)."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#options")
+    )]
+    InvalidSinceDate { since_date: String, reason: String },
+    #[error("Invalid build metadata {build_metadata}")]
+    #[diagnostic(
+        code(releases::invalid_build_metadata),
+        help("Build metadata must be dot-separated identifiers of ASCII letters, digits, and hyphens, like `exp.sha.5114f85`."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#build-metadata")
+    )]
+    InvalidBuildMetadata { build_metadata: String },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    ForgeReleases(#[from] ForgeReleasesError),
+    #[error("The `Release` step targets {forge}, which isn't configured")]
+    #[diagnostic(
+        code(releases::forge_not_configured),
+        help("Configure {forge} in knope.toml, or remove it from `forges`."),
+        url("https://knope.tech/reference/config-file/steps/release/#multiple-forges")
+    )]
+    ForgeNotConfigured { forge: Forge },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
     Gitea(#[from] gitea::Error),
     #[error(transparent)]
     #[diagnostic(transparent)]
@@ -134,6 +500,15 @@ pub(crate) enum Error {
     #[error(transparent)]
     #[diagnostic(transparent)]
     Parse(#[from] changelog::ParseError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fs(#[from] fs::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Time(#[from] TimeError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Variables(#[from] variables::Error),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -276,11 +651,139 @@ impl From<Version> for CurrentVersions {
 
 /// Create a release for the package.
 ///
-/// If GitHub config is present, this creates a GitHub release. Otherwise, it tags the Git repo.
-pub(crate) fn release(run_type: RunType) -> Result<RunType, Error> {
+/// This creates a release on each configured forge in `forges` (all of them if `None`). If there
+/// are none, it tags the Git repo instead, unless the releases are recorded in Git notes.
+///
+/// If the `[git]` config has a `notes_ref`, every release is recorded in a Git note on the
+/// current commit.
+///
+/// With `from_changelog`, the latest version in each changelog is released instead of the
+/// prepared or current version.
+pub(crate) fn release(
+    run_type: RunType,
+    existing_tag: ExistingTag,
+    release_notes: &ReleaseNotes,
+    forges: Option<&[Forge]>,
+    from_changelog: bool,
+) -> Result<RunType, Error> {
     let (mut state, mut dry_run_stdout) = run_type.decompose();
+    for forge in forges.unwrap_or_default() {
+        let configured = match forge {
+            Forge::GitHub => state.github_config.is_some(),
+            Forge::Gitea => state.gitea_config.is_some(),
+        };
+        if !configured {
+            return Err(Error::ForgeNotConfigured { forge: *forge });
+        }
+    }
+    let targets = |forge| forges.map_or(true, |forges| forges.contains(&forge));
+
+    let releases = if from_changelog {
+        releases_from_changelogs(&state.packages)?
+    } else {
+        prepared_releases(&mut state)?
+    };
+
+    let github_config = state
+        .github_config
+        .clone()
+        .filter(|_| targets(Forge::GitHub));
+    let gitea_config = state.gitea_config.clone().filter(|_| targets(Forge::Gitea));
+    let notes_ref = state.git_config.notes_ref.clone();
+    let mut release_note = git::ReleaseNote::default();
+    for package_to_release in releases {
+        let tag = tag_name(
+            &package_to_release.release.version,
+            package_to_release.release.build_metadata.as_ref(),
+            &package_to_release.package.name,
+        );
+        release_note.releases.push(git::NoteRelease {
+            package: package_to_release
+                .package
+                .name
+                .as_ref()
+                .map(ToString::to_string),
+            version: package_to_release.release.version.to_string(),
+            tag: tag.clone(),
+            notes: package_to_release.release.body_at_h1(),
+        });
+        let additional_tags = package_to_release
+            .release
+            .additional_tags
+            .iter()
+            .filter(|additional_tag| **additional_tag != tag)
+            .collect_vec();
+        if existing_tag == ExistingTag::Fail {
+            for tag in iter::once(&tag).chain(additional_tags.iter().copied()) {
+                if tag_exists(tag)? {
+                    return Err(Error::TagExists { tag: tag.clone() });
+                }
+            }
+        }
+        let main_tag_exists = tag_exists(&tag)?.then_some(existing_tag);
 
-    let mut releases = state
+        release_on_forges(
+            &package_to_release,
+            &mut state,
+            github_config.as_ref(),
+            gitea_config.as_ref(),
+            &mut dry_run_stdout,
+            &tag,
+            main_tag_exists,
+            release_notes,
+        )?;
+
+        // if neither is present, we fall back to just creating a tag (or only the Git note)
+        if github_config.is_none() && gitea_config.is_none() && notes_ref.is_none() {
+            create_or_skip_tag(&mut dry_run_stdout, &tag, existing_tag, &state.git_config)?;
+        }
+
+        additional_tags.into_iter().try_for_each(|additional_tag| {
+            create_or_skip_tag(
+                &mut dry_run_stdout,
+                additional_tag,
+                existing_tag,
+                &state.git_config,
+            )
+        })?;
+        mark_released(&mut state, package_to_release, tag);
+    }
+
+    if let (Some(notes_ref), false) = (notes_ref, release_note.releases.is_empty()) {
+        git::add_note(
+            &mut dry_run_stdout,
+            &notes_ref,
+            &release_note,
+            &state.git_config,
+        )?;
+    }
+
+    if let Some(stdout) = dry_run_stdout {
+        Ok(RunType::DryRun { stdout, state })
+    } else {
+        Ok(RunType::Real(state))
+    }
+}
+
+/// Keep the release for later steps (like `CreateDiscussion`), and record its tag so that a later
+/// `Release` step doesn't find it again.
+fn mark_released(state: &mut State, released: PackageWithRelease, tag: String) {
+    if !state.all_git_tags.contains(&tag) {
+        state.all_git_tags.push(tag);
+    }
+    let PackageWithRelease {
+        mut package,
+        release,
+    } = released;
+    package.prepared_release = Some(release);
+    state.released.push(package);
+}
+
+/// The releases prepared earlier in the workflow, or found from versions newer than the last tags.
+///
+/// Prepared releases are taken out of `state`, so that a later `Release` step doesn't repeat them.
+fn prepared_releases(state: &mut State) -> Result<Vec<PackageWithRelease>, Error> {
+    let releases = state
         .packages
         .iter_mut()
         .filter_map(|package| {
@@ -293,78 +796,343 @@ pub(crate) fn release(run_type: RunType) -> Result<RunType, Error> {
                 })
         })
         .collect_vec();
+    if !releases.is_empty() {
+        return Ok(releases);
+    }
+    state
+        .packages
+        .iter()
+        .map(|package| {
+            find_prepared_release(package, state.verbose, &state.all_git_tags).map(|release| {
+                release.map(|release| PackageWithRelease {
+                    package: package.clone(),
+                    release,
+                })
+            })
+        })
+        .filter_map_ok(|stuff| stuff)
+        .try_collect()
+}
 
-    if releases.is_empty() {
-        releases = state
-            .packages
-            .iter()
-            .map(|package| {
-                find_prepared_release(package, state.verbose, &state.all_git_tags).map(|release| {
+/// The latest release in the changelog of each package, for changelogs maintained by hand.
+fn releases_from_changelogs(packages: &[Package]) -> Result<Vec<PackageWithRelease>, Error> {
+    let releases: Vec<PackageWithRelease> = packages
+        .iter()
+        .filter_map(|package| {
+            let changelog = package.changelog.as_ref()?;
+            changelog
+                .latest_release(package.files.clone(), package.go_versioning)
+                .transpose()
+                .map(|release| {
                     release.map(|release| PackageWithRelease {
                         package: package.clone(),
                         release,
                     })
                 })
-            })
-            .filter_map_ok(|stuff| stuff)
-            .try_collect()?;
+        })
+        .try_collect()?;
+    if releases.is_empty() {
+        return Err(Error::NoChangelogRelease);
     }
+    Ok(releases)
+}
 
-    let github_config = state.github_config.clone();
-    let gitea_config = state.gitea_config.clone();
-    for package_to_release in releases {
-        let tag = tag_name(
-            &package_to_release.release.version,
-            &package_to_release.package.name,
-        );
+/// Create the release for `tag` on each forge with a config.
+///
+/// A failure on one forge doesn't keep the release from the others, all the failures are reported
+/// together afterward.
+#[allow(clippy::too_many_arguments)]
+fn release_on_forges(
+    package_to_release: &PackageWithRelease,
+    state: &mut State,
+    github_config: Option<&config::GitHub>,
+    gitea_config: Option<&config::Gitea>,
+    dry_run_stdout: DryRun,
+    tag: &str,
+    existing_tag: Option<ExistingTag>,
+    release_notes: &ReleaseNotes,
+) -> Result<(), Error> {
+    let mut failures = Vec::new();
+    let mut succeeded = Vec::new();
+    if let Some(github_config) = github_config {
+        match github::release(
+            &package_to_release.package,
+            &package_to_release.release,
+            mem::replace(&mut state.github, state::GitHub::New),
+            github_config,
+            dry_run_stdout,
+            tag,
+            existing_tag,
+            release_notes,
+        ) {
+            Ok(github_state) => {
+                state.github = github_state;
+                succeeded.push(Forge::GitHub);
+            }
+            Err(err) => failures.push(ForgeError::GitHub(err)),
+        }
+    }
 
-        if let Some(github_config) = github_config.as_ref() {
-            state.github = github::release(
-                package_to_release.package.name.as_ref(),
-                &package_to_release.release,
-                state.github,
-                github_config,
-                &mut dry_run_stdout,
-                package_to_release.package.assets.as_ref(),
-                &tag,
-            )?;
+    if let Some(gitea_config) = gitea_config {
+        match gitea::release(
+            &package_to_release.package,
+            &package_to_release.release,
+            mem::replace(&mut state.gitea, state::Gitea::New),
+            gitea_config,
+            dry_run_stdout,
+            tag,
+            existing_tag,
+            release_notes,
+        ) {
+            Ok(gitea_state) => {
+                state.gitea = gitea_state;
+                succeeded.push(Forge::Gitea);
+            }
+            Err(err) => failures.push(ForgeError::Gitea(err)),
+        }
+    }
+    match (failures.pop(), succeeded.is_empty() && failures.is_empty()) {
+        (None, _) => Ok(()),
+        // Only one forge was released on, so its error is enough
+        (Some(failure), true) => Err(failure.into()),
+        (Some(failure), false) => {
+            failures.push(failure);
+            Err(ForgeReleasesError::new(tag.to_string(), failures, &succeeded).into())
         }
+    }
+}
 
-        if let Some(ref gitea_config) = gitea_config {
-            state.gitea = gitea::release(
-                package_to_release.package.name.as_ref(),
-                &package_to_release.release,
-                state.gitea,
-                gitea_config,
-                &mut dry_run_stdout,
-                &tag,
-            )?;
+/// Create the release tags for the current version of every package, without bumping anything
+/// or creating a forge release.
+pub(crate) fn tag(run_type: RunType, existing_tag: ExistingTag) -> Result<RunType, Error> {
+    let (state, mut dry_run_stdout) = run_type.decompose();
+    if state.packages.is_empty() {
+        return Err(package::Error::NoDefinedPackages.into());
+    }
+
+    let mut tags = Vec::new();
+    for package in &state.packages {
+        let (version, build_metadata, additional_tags) =
+            if let Some(release) = package.prepared_release.as_ref() {
+                (
+                    release.version.clone(),
+                    release.build_metadata.clone(),
+                    release.additional_tags.clone(),
+                )
+            } else if let Some(version) = package.current_version() {
+                let additional_tags = package
+                    .files
+                    .clone()
+                    .map(|files| files.set_version(version, None, package.go_versioning))
+                    .transpose()
+                    .map_err(UpdatePackageVersionError::from)
+                    .map_err(package::Error::from)?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|action| match action {
+                        Action::AddTag { tag } => Some(tag),
+                        Action::WriteToFile { .. } => None,
+                    })
+                    .collect_vec();
+                (version.clone(), None, additional_tags)
+            } else {
+                continue;
+            };
+        tags.push(tag_name(&version, build_metadata.as_ref(), &package.name));
+        tags.extend(additional_tags);
+    }
+    let tags = tags.into_iter().unique().collect_vec();
+
+    if existing_tag == ExistingTag::Fail {
+        for tag in &tags {
+            if tag_exists(tag)? {
+                return Err(Error::TagExists { tag: tag.clone() });
+            }
         }
+    }
+    for tag in &tags {
+        create_or_skip_tag(&mut dry_run_stdout, tag, existing_tag, &state.git_config)?;
+    }
 
-        // if neither is present, we fall back to just creating a tag
-        if github_config.is_none() && gitea_config.is_none() {
-            create_tag(&mut dry_run_stdout, &tag)?;
+    Ok(RunType::recompose(state, dry_run_stdout))
+}
+
+/// Create the Git tag `tag`, handling an existing one as `existing_tag` says.
+fn create_or_skip_tag(
+    dry_run_stdout: DryRun,
+    tag: &str,
+    existing_tag: ExistingTag,
+    git_config: &config::Git,
+) -> Result<(), Error> {
+    if !tag_exists(tag)? {
+        return create_tag(dry_run_stdout, tag, git_config).map_err(Error::from);
+    }
+    match (existing_tag, dry_run_stdout) {
+        (ExistingTag::Fail, _) => Err(Error::TagExists {
+            tag: tag.to_string(),
+        }),
+        (ExistingTag::Skip, Some(stdout)) => {
+            writeln!(
+                stdout,
+                "Git tag {tag} already exists, would skip creating it"
+            )
+            .map_err(fs::Error::Stdout)?;
+            Ok(())
         }
+        (ExistingTag::Skip, None) => Ok(()),
+        (ExistingTag::Force, Some(stdout)) => {
+            writeln!(stdout, "Would move Git tag {tag} to the current commit")
+                .map_err(fs::Error::Stdout)?;
+            Ok(())
+        }
+        (ExistingTag::Force, None) => create_tag(&mut None, tag, git_config).map_err(Error::from),
+    }
+}
 
-        package_to_release
-            .release
-            .additional_tags
-            .iter()
-            .filter(|additional_tag| **additional_tag != tag)
-            .try_for_each(|additional_tag| create_tag(&mut dry_run_stdout, additional_tag))?;
+/// What the `Release` and `Tag` steps do when a tag they would create already exists.
+#[derive(Clone, Copy, Debug, Default, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ExistingTag {
+    /// Stop with an error.
+    #[default]
+    Fail,
+    /// Treat the tag as already released, but still create a missing forge release.
+    Skip,
+    /// Move the tag to the current commit.
+    Force,
+}
+
+impl ExistingTag {
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Required by serde's `skip_serializing_if`
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
     }
+}
 
-    if let Some(stdout) = dry_run_stdout {
-        Ok(RunType::DryRun { stdout, state })
-    } else {
-        Ok(RunType::Real(state))
+/// Where `PrepareRelease` finds the previous version of each package.
+#[derive(Clone, Copy, Debug, Default, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PreviousVersionSource {
+    /// The release tags in the local Git repository.
+    #[default]
+    Tags,
+    /// The releases on GitHub, for when the tags weren't fetched.
+    GitHub,
+}
+
+impl PreviousVersionSource {
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Required by serde's `skip_serializing_if`
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for PreviousVersionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tags => f.write_str("tags"),
+            Self::GitHub => f.write_str("GitHub"),
+        }
     }
 }
 
-/// The tag that a particular version should have for a particular package
-pub(crate) fn tag_name(version: &Version, package_name: &Option<PackageName>) -> String {
+/// Where `PrepareRelease` gets the build metadata to add to new versions, like `1.2.3+20240501`.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum BuildMetadataSource {
+    /// The current date (in UTC), like `20240501`.
+    Date,
+    /// The short hash of the `HEAD` commit, like `5114f85`.
+    CommitHash,
+    /// This exact text, like `exp.1`.
+    Literal(String),
+}
+
+/// A forge that the `Release` step can create releases on.
+#[derive(Clone, Copy, Debug, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Forge {
+    GitHub,
+    Gitea,
+}
+
+impl Display for Forge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GitHub => f.write_str("GitHub"),
+            Self::Gitea => f.write_str("Gitea"),
+        }
+    }
+}
+
+/// The error from releasing on a single forge.
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum ForgeError {
+    #[error("Could not create the GitHub release")]
+    GitHub(
+        #[source]
+        #[diagnostic_source]
+        github::Error,
+    ),
+    #[error("Could not create the Gitea release")]
+    Gitea(
+        #[source]
+        #[diagnostic_source]
+        gitea::Error,
+    ),
+}
+
+impl From<ForgeError> for Error {
+    fn from(err: ForgeError) -> Self {
+        match err {
+            ForgeError::GitHub(err) => Self::GitHub(err),
+            ForgeError::Gitea(err) => Self::Gitea(err),
+        }
+    }
+}
+
+/// Releasing on more than one forge, where some of them failed.
+#[derive(Debug, Diagnostic, thiserror::Error)]
+#[error("Releasing {tag} failed on {count} forge(s)", count = failures.len())]
+#[diagnostic(
+    code(releases::forge_releases),
+    url("https://knope.tech/reference/config-file/steps/release/#multiple-forges")
+)]
+pub(crate) struct ForgeReleasesError {
+    tag: String,
+    #[related]
+    failures: Vec<ForgeError>,
+    #[help]
+    help: String,
+}
+
+impl ForgeReleasesError {
+    fn new(tag: String, failures: Vec<ForgeError>, succeeded: &[Forge]) -> Self {
+        let help = if succeeded.is_empty() {
+            "No releases were created.".to_string()
+        } else {
+            format!(
+                "The release was still created on {}. Fix the problem, then set `existing_tag = \"skip\"` on the `Release` step to create the rest.",
+                succeeded.iter().join(" and ")
+            )
+        };
+        Self {
+            tag,
+            failures,
+            help,
+        }
+    }
+}
+
+/// The tag that a particular version (with optional build metadata) should have for a particular
+/// package
+pub(crate) fn tag_name(
+    version: &Version,
+    build_metadata: Option<&BuildMetadata>,
+    package_name: &Option<PackageName>,
+) -> String {
     let prefix = tag_prefix(package_name);
-    format!("{prefix}{version}")
+    format!("{prefix}{}", version.with_build(build_metadata))
 }
 
 /// The prefix for tags for a particular package
@@ -374,6 +1142,12 @@ fn tag_prefix(package_name: &Option<PackageName>) -> String {
         .map_or_else(|| "v".to_string(), |name| format!("{name}/v"))
 }
 
+/// Whether `tag` is a release tag (of any version) for a particular package
+pub(crate) fn is_release_tag(tag: &str, package_name: &Option<PackageName>) -> bool {
+    tag.strip_prefix(&tag_prefix(package_name))
+        .is_some_and(|version| Version::from_str(version).is_ok())
+}
+
 struct PackageWithRelease {
     package: Package,
     release: Release,
@@ -386,7 +1160,7 @@ fn find_prepared_release(
     verbose: Verbose,
     all_tags: &[String],
 ) -> Result<Option<Release>, Error> {
-    let Some(current_version) = package.version_from_files() else {
+    let Some(current_version) = package.current_version() else {
         return Ok(None);
     };
     if let Verbose::Yes = verbose {
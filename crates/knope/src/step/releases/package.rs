@@ -2,29 +2,35 @@ use std::{
     borrow::{Borrow, Cow},
     fmt,
     fmt::Display,
-    io::Write,
     ops::Deref,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+use indexmap::IndexMap;
 use itertools::Itertools;
 use knope_versioning::{
-    GoVersioning, Label, PackageNewError, Version, VersionedFile, VersionedFileError,
+    cargo, package_json, package_json::Lockfile, BuildMetadata, GoVersioning, Label,
+    PackageNewError, Version, VersionedFile, VersionedFileError, VersionedFilePath,
 };
 use miette::Diagnostic;
+use relative_path::{RelativePath, RelativePathBuf};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::{
     changelog,
-    changelog::Changelog,
+    changelog::{Changelog, ExistingEntry},
     changesets::DEFAULT_CHANGESET_PACKAGE_NAME,
     semver,
-    semver::{bump, ConventionalRule},
+    semver::{bump, bump_pre_only, ConventionalRule, PrereleaseFormat, ZeroVersioning},
     Change, Release, Rule,
 };
 use crate::{
     config,
-    config::{ChangeLogSectionName, ChangelogSection, CommitFooter, CustomChangeType},
+    config::{
+        Bump, ChangeLogSectionName, ChangelogSection, CommitFooter, CommitType, CustomChangeType,
+        DependencyUpdates,
+    },
     dry_run::DryRun,
     fs,
     fs::read_to_string,
@@ -40,17 +46,42 @@ use crate::{
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct Package {
     pub(crate) files: Option<knope_versioning::Package>,
+    /// The versioned file that [`crate::step::Step::SyncVersions`] copies the version from
+    pub(crate) source_of_truth: Option<RelativePathBuf>,
     pub(crate) changelog: Option<Changelog>,
     pub(crate) changelog_sections: ChangelogSections,
+    pub(crate) changelog_sort: changelog::Sort,
+    /// Conventional commit types, other than `feat` and `fix`, which Knope cares about
+    pub(crate) commit_types: Vec<CommitType>,
+    /// Other names for conventional commit types, like `bug` for `fix`
+    pub(crate) commit_type_aliases: IndexMap<String, String>,
+    /// How commits which update dependencies are recognized, if they're handled separately
+    pub(crate) dependency_updates: Option<DependencyUpdates>,
     pub(crate) name: Option<PackageName>,
     pub(crate) scopes: Option<Vec<String>>,
     pub(crate) pending_changes: Vec<Change>,
     pub(crate) pending_tags: Vec<String>,
+    /// Files written by the last version change, read back from here so a dry run sees them too
+    pub(crate) pending_writes: Vec<(RelativePathBuf, String)>,
+    /// The authors of the commits being released, listed in the release notes if not empty
+    pub(crate) contributors: Vec<String>,
     pub(crate) prepared_release: Option<Release>,
-    /// Version manually set by the caller to use instead of the one determined by semantic rule
+    /// Version manually set by the caller (or `version_env_var`) to use instead of the one
+    /// determined by semantic rule
     pub(crate) override_version: Option<Version>,
     pub(crate) assets: Option<Vec<Asset>>,
     pub(crate) go_versioning: GoVersioning,
+    pub(crate) zero_versioning: ZeroVersioning,
+    /// Whether to update this package's entry in the root `Cargo.toml`'s `[workspace.dependencies]`
+    pub(crate) update_workspace_dependencies: bool,
+    /// Used instead of the `PrepareRelease` step's label when a pre-release is requested
+    pub(crate) prerelease_label: Option<Label>,
+    /// How new pre-release versions are written
+    pub(crate) prerelease_format: PrereleaseFormat,
+    /// Written to the changelog when a release is forced without any changes
+    pub(crate) no_changes_entry: Option<String>,
+    /// The version from the package's `version_env_var`, preferred over files and tags
+    pub(crate) version_from_env: Option<Version>,
 }
 
 impl Package {
@@ -95,20 +126,51 @@ impl Package {
                 );
             }
         }
-        let files = match knope_versioning::Package::new(versioned_files) {
+        let source_of_truth = package
+            .source_of_truth
+            .as_ref()
+            .map(VersionedFilePath::as_path);
+        let files = match &source_of_truth {
+            Some(source_of_truth) => {
+                knope_versioning::Package::with_source_of_truth(versioned_files, source_of_truth)
+            }
+            None => knope_versioning::Package::new(versioned_files),
+        };
+        let files = match files {
             Ok(pkg) => Some(pkg),
             Err(err) => match err {
                 PackageNewError::NoPackages => None,
-                err @ PackageNewError::InconsistentVersions(..) => return Err(err.into()),
+                err @ (PackageNewError::InconsistentVersions(..)
+                | PackageNewError::UnknownSourceOfTruth(..)) => return Err(err.into()),
             },
         };
+        let version_from_env = package
+            .version_env_var
+            .as_deref()
+            .map(version_from_env)
+            .transpose()?
+            .flatten();
         Ok(Self {
             files,
+            source_of_truth,
             changelog: package
                 .changelog
-                .map(|path| path.to_path("").try_into())
+                .map(|path| {
+                    Changelog::load(
+                        path.to_path(""),
+                        package.changelog_header.as_deref(),
+                        package.changelog_anchor.as_deref(),
+                        package.changelog_wrap,
+                    )
+                })
                 .transpose()?,
-            changelog_sections: package.extra_changelog_sections.into(),
+            changelog_sections: ChangelogSections::from(package.extra_changelog_sections)
+                .with_commit_types(&package.commit_types)
+                .with_dependency_updates(package.dependency_updates.as_ref()),
+            changelog_sort: package.changelog_sort.unwrap_or_default(),
+            commit_types: package.commit_types,
+            commit_type_aliases: package.commit_type_aliases,
+            dependency_updates: package.dependency_updates,
             name: package.name,
             scopes: package.scopes,
             assets: package.assets,
@@ -117,39 +179,114 @@ impl Package {
             } else {
                 GoVersioning::default()
             },
+            zero_versioning: if package.strict_semver {
+                ZeroVersioning::Strict
+            } else {
+                ZeroVersioning::default()
+            },
+            update_workspace_dependencies: package.update_workspace_dependencies,
+            prerelease_label: package.prerelease_label,
+            prerelease_format: PrereleaseFormat {
+                separator: package.prerelease_separator.unwrap_or_default(),
+                start: package.prerelease_start.unwrap_or_default(),
+            },
+            no_changes_entry: package.no_changes_entry,
+            // A version from the environment is the one to release, unless `--override-version` is used
+            override_version: version_from_env.clone(),
+            version_from_env,
             pending_changes: Vec::new(),
             pending_tags: Vec::new(),
+            pending_writes: Vec::new(),
+            contributors: Vec::new(),
             prepared_release: None,
-            override_version: None,
         })
     }
 
-    fn bump_rule(&self, verbose: Verbose) -> ConventionalRule {
+    /// The largest rule implied by any pending change, or `None` if no change should bump the version.
+    fn bump_rule(&self, verbose: Verbose) -> Option<ConventionalRule> {
         self.pending_changes
             .iter()
-            .map(|change| {
-                let rule = change.change_type().into();
+            .filter_map(|change| {
                 let change_source = match change {
                     Change::ConventionalCommit(_) => "commit",
                     Change::ChangeSet(_) => "changeset",
                 };
+                let rule = match change.change_type() {
+                    ChangeType::Custom(ChangelogSectionSource::CommitType(name)) => self
+                        .commit_types
+                        .iter()
+                        .find(|commit_type| commit_type.name == name)
+                        .map_or(Bump::Patch, |commit_type| commit_type.bump)
+                        .into(),
+                    ChangeType::Custom(ChangelogSectionSource::DependencyUpdate) => self
+                        .dependency_updates
+                        .as_ref()
+                        .map_or(Bump::Patch, |dependency_updates| dependency_updates.bump)
+                        .into(),
+                    change_type => Some(change_type.into()),
+                };
                 if let Verbose::Yes = verbose {
-                    println!("{change_source} {change}\n\timplies rule {rule}");
+                    match &rule {
+                        Some(rule) => println!("{change_source} {change}\n\timplies rule {rule}"),
+                        None => println!("{change_source} {change}\n\tdoes not bump the version"),
+                    }
                 }
                 rule
             })
             .max()
-            .unwrap_or_default()
     }
 
+    /// The version that `pending_changes` bump this package to, if any of them bump it.
+    ///
+    /// With `prerelease_only`, only the number of the current pre-release is bumped instead.
+    pub(crate) fn next_version(
+        &self,
+        prerelease_label: &Option<Label>,
+        prerelease_only: bool,
+        git_tags: &[String],
+        verbose: Verbose,
+    ) -> Result<Option<Version>, Error> {
+        let versions = self.get_version(verbose, git_tags);
+        let Some(bump_rule) = self.bump_rule(verbose) else {
+            if let Verbose::Yes = verbose {
+                println!("No changes bump the version, so there is nothing to release");
+            }
+            return Ok(None);
+        };
+        let prerelease_label = prerelease_label
+            .as_ref()
+            .map(|step_label| self.prerelease_label.as_ref().unwrap_or(step_label));
+        if prerelease_only {
+            return bump_pre_only(versions, prerelease_label, self.prerelease_format, verbose)
+                .map(Some)
+                .ok_or(Error::NoPrereleaseToBump);
+        }
+        let rule = if let Some(pre_label) = prerelease_label {
+            Rule::Pre {
+                label: pre_label.clone(),
+                stable_rule: bump_rule,
+                format: self.prerelease_format,
+            }
+        } else {
+            bump_rule.into()
+        };
+        Ok(Some(bump(versions, &rule, self.zero_versioning, verbose)?))
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn write_release(
         mut self,
         prerelease_label: &Option<Label>,
+        prerelease_only: bool,
+        existing_changelog_entry: ExistingEntry,
+        keep_change_files: bool,
+        change_directory: &Path,
+        build_metadata: Option<&BuildMetadata>,
         git_tags: &[String],
         dry_run: DryRun,
         verbose: Verbose,
     ) -> Result<Self, Error> {
-        if self.pending_changes.is_empty() {
+        if self.pending_changes.is_empty() && self.override_version.is_none() {
             return Ok(self);
         }
 
@@ -168,33 +305,35 @@ impl Package {
                 source: VersionSource::OverrideVersion,
             }
         } else {
-            let versions = self.get_version(verbose, git_tags);
-            let bump_rule = self.bump_rule(verbose);
-            let rule = if let Some(pre_label) = prerelease_label {
-                Rule::Pre {
-                    label: pre_label.clone(),
-                    stable_rule: bump_rule,
-                }
-            } else {
-                bump_rule.into()
+            let Some(version) =
+                self.next_version(prerelease_label, prerelease_only, git_tags, verbose)?
+            else {
+                return Ok(self);
             };
-            let version = bump(versions, &rule, verbose)?;
             VersionFromSource {
                 version,
                 source: VersionSource::Calculated,
             }
         };
 
-        self = self.write_version(&new_version, dry_run)?;
-        let prepared_release = self.write_changelog(new_version.version, dry_run)?;
-        let is_prerelease = prepared_release.version.is_prerelease();
+        if let Some(changelog) = &self.changelog {
+            changelog.check_existing_entry(&new_version.version, existing_changelog_entry)?;
+        }
+        self = self.write_version(&new_version, build_metadata, dry_run)?;
+        let mut prepared_release = self.write_changelog(new_version.version, dry_run)?;
+        prepared_release.build_metadata = build_metadata.cloned();
+        let keep_change_files = keep_change_files || prepared_release.version.is_prerelease();
         self.prepared_release = Some(prepared_release);
-        self.stage_changes_to_git(is_prerelease, dry_run)?;
+        self.stage_changes_to_git(keep_change_files, change_directory, dry_run)?;
 
         Ok(self)
     }
-    fn stage_changes_to_git(&self, is_prerelease: bool, dry_run: DryRun) -> Result<(), Error> {
-        let changeset_path = PathBuf::from(".changeset");
+    fn stage_changes_to_git(
+        &self,
+        keep_change_files: bool,
+        change_directory: &Path,
+        dry_run: DryRun,
+    ) -> Result<(), Error> {
         let paths = self
             .files
             .as_ref()
@@ -208,27 +347,213 @@ impl Package {
                     .map(|changelog| changelog.path.clone()),
             )
             .chain(self.pending_changes.iter().filter_map(|change| {
-                if is_prerelease {
+                if keep_change_files {
                     None
                 } else if let Change::ChangeSet(change) = change {
-                    Some(changeset_path.join(change.unique_id.to_file_name()))
+                    Some(change_directory.join(change.unique_id.to_file_name()))
                 } else {
                     None
                 }
             }))
             .collect_vec();
-        if paths.is_empty() {
-            Ok(())
-        } else if let Some(stdio) = dry_run {
-            writeln!(stdio, "Would add files to git:").map_err(fs::Error::Stdout)?;
-            for path in &paths {
-                writeln!(stdio, "  {}", path.display()).map_err(fs::Error::Stdout)?;
+        add_files(dry_run, &paths).map_err(Error::from)
+    }
+}
+
+/// Read the version from the environment variable `var`, if it's set.
+///
+/// A leading `v` is allowed, so the variable can hold a tag name.
+fn version_from_env(var: &str) -> Result<Option<Version>, Error> {
+    let Some(value) = std::env::var(var)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+    else {
+        return Ok(None);
+    };
+    let trimmed = value.trim();
+    trimmed
+        .strip_prefix('v')
+        .unwrap_or(trimmed)
+        .parse()
+        .map(Some)
+        .map_err(|source| Error::InvalidEnvVersion {
+            var: var.to_string(),
+            value,
+            source,
+        })
+}
+
+/// Update the `[workspace.dependencies]` of the root `Cargo.toml` for every released package that
+/// opted in with `update_workspace_dependencies`.
+///
+/// This runs after all packages have written their own files, so it sees their changes.
+pub(crate) fn update_workspace_dependencies(
+    packages: &[Package],
+    dry_run: DryRun,
+) -> Result<(), Error> {
+    let updates = packages
+        .iter()
+        .filter(|package| package.update_workspace_dependencies)
+        .filter_map(|package| {
+            let version = &package.prepared_release.as_ref()?.version;
+            let files = package.files.as_ref()?;
+            Some(files.versioned_files().iter().filter_map(move |file| {
+                if let VersionedFile::Cargo(cargo) = file {
+                    Some((cargo.get_package_name(), version))
+                } else {
+                    None
+                }
+            }))
+        })
+        .flatten()
+        .collect_vec();
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    let path = RelativePathBuf::from("Cargo.toml");
+    let mut content = read_pending(packages, &path)?;
+    let mut changed = Vec::with_capacity(updates.len());
+    for (name, version) in updates {
+        if let Some(new_content) =
+            cargo::set_workspace_dependency_version(&path, &content, name, version)?
+        {
+            content = new_content;
+            changed.push(format!("{name} = {version}"));
+        }
+    }
+    if changed.is_empty() {
+        return Ok(());
+    }
+    fs::write(dry_run, &changed.join(", "), &path.to_path(""), content)?;
+    add_files(dry_run, &[path.to_path("")]).map_err(Error::from)
+}
+
+/// Update the ranges that every package's `package.json` uses to depend on released npm packages,
+/// so packages in a JavaScript workspace keep depending on each other's newest versions.
+///
+/// Like [`update_workspace_dependencies`], this runs after all packages have written their own files.
+pub(crate) fn update_package_json_dependencies(
+    packages: &[Package],
+    dry_run: DryRun,
+) -> Result<(), Error> {
+    let released = packages
+        .iter()
+        .filter_map(|package| {
+            let version = &package.prepared_release.as_ref()?.version;
+            let files = package.files.as_ref()?;
+            Some(files.versioned_files().iter().filter_map(move |file| {
+                if let VersionedFile::PackageJson(package_json) = file {
+                    Some((package_json.get_package_name()?, version))
+                } else {
+                    None
+                }
+            }))
+        })
+        .flatten()
+        .collect_vec();
+    if released.is_empty() {
+        return Ok(());
+    }
+
+    let paths = packages
+        .iter()
+        .filter_map(|package| package.files.as_ref())
+        .flat_map(knope_versioning::Package::versioned_files)
+        .filter(|file| matches!(file, VersionedFile::PackageJson(_)))
+        .map(VersionedFile::path)
+        .unique()
+        .collect_vec();
+    for path in paths {
+        let mut content = read_pending(packages, path)?;
+        let mut changed = Vec::new();
+        for (name, version) in &released {
+            if let Some(new_content) =
+                package_json::set_dependency_version(path, &content, name, version)?
+            {
+                content = new_content;
+                changed.push(format!("{name} = {version}"));
             }
-            Ok(())
-        } else {
-            add_files(&paths).map_err(Error::from)
         }
+        if changed.is_empty() {
+            continue;
+        }
+        fs::write(dry_run, &changed.join(", "), &path.to_path(""), content)?;
+        add_files(dry_run, &[path.to_path("")])?;
     }
+    Ok(())
+}
+
+/// Update the version that a `pnpm-lock.yaml` or `yarn.lock` records for each released npm package,
+/// looking next to each `package.json` and in the current directory.
+///
+/// Only unambiguous entries are changed, see [`Lockfile::set_self_version`].
+pub(crate) fn update_lockfiles(packages: &[Package], dry_run: DryRun) -> Result<(), Error> {
+    let released = packages
+        .iter()
+        .filter_map(|package| {
+            let version = &package.prepared_release.as_ref()?.version;
+            let files = package.files.as_ref()?;
+            Some(files.versioned_files().iter().filter_map(move |file| {
+                if let VersionedFile::PackageJson(package_json) = file {
+                    Some((package_json.get_package_name()?, file, version))
+                } else {
+                    None
+                }
+            }))
+        })
+        .flatten()
+        .collect_vec();
+
+    let paths = released
+        .iter()
+        .flat_map(|(_, file, _)| {
+            Lockfile::ALL.into_iter().flat_map(|lockfile| {
+                let beside = file
+                    .path()
+                    .parent()
+                    .map(|parent| (lockfile, parent.join(lockfile.file_name())));
+                beside
+                    .into_iter()
+                    .chain([(lockfile, RelativePathBuf::from(lockfile.file_name()))])
+            })
+        })
+        .unique()
+        .filter(|(_, path)| path.to_path("").exists())
+        .collect_vec();
+    for (lockfile, path) in paths {
+        let mut content = read_pending(packages, &path)?;
+        let mut changed = Vec::new();
+        for (name, file, version) in &released {
+            if let Some(new_content) =
+                lockfile.set_self_version(&content, name, file.version(), version)
+            {
+                content = new_content;
+                changed.push(format!("{name} = {version}"));
+            }
+        }
+        if changed.is_empty() {
+            continue;
+        }
+        fs::write(dry_run, &changed.join(", "), &path.to_path(""), content)?;
+        add_files(dry_run, &[path.to_path("")])?;
+    }
+    Ok(())
+}
+
+/// The content that a package is about to write to `path`, or what's on disk if none is.
+///
+/// In a dry run nothing is written, so reading from disk would miss the new versions.
+fn read_pending(packages: &[Package], path: &RelativePath) -> Result<String, Error> {
+    packages
+        .iter()
+        .flat_map(|package| &package.pending_writes)
+        .rev()
+        .find(|(pending_path, _)| pending_path == path)
+        .map_or_else(
+            || read_to_string(path.to_path("")).map_err(Error::from),
+            |(_, content)| Ok(content.clone()),
+        )
 }
 
 #[cfg(test)]
@@ -247,16 +572,29 @@ impl Package {
             )
             .unwrap()])
             .ok(),
+            source_of_truth: None,
             changelog: None,
             changelog_sections: ChangelogSections::default(),
+            changelog_sort: changelog::Sort::default(),
+            commit_types: Vec::new(),
+            commit_type_aliases: IndexMap::new(),
+            dependency_updates: None,
             name: None,
             scopes: None,
             pending_changes: vec![],
             pending_tags: vec![],
+            pending_writes: vec![],
+            contributors: vec![],
             prepared_release: None,
             override_version: None,
             assets: None,
             go_versioning: GoVersioning::default(),
+            zero_versioning: ZeroVersioning::default(),
+            update_workspace_dependencies: false,
+            prerelease_label: None,
+            prerelease_format: PrereleaseFormat::default(),
+            no_changes_entry: None,
+            version_from_env: None,
         }
     }
 }
@@ -273,7 +611,36 @@ impl Display for Package {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+/// The order that `PrepareRelease` handles packages in, which is also the order of its output
+/// and of the `ReleasedPackages` variable.
+#[derive(Clone, Copy, Debug, Default, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PackageOrder {
+    /// The order they're declared in the config file.
+    #[default]
+    Config,
+    /// Sorted by name.
+    Alphabetical,
+}
+
+impl PackageOrder {
+    #[allow(clippy::trivially_copy_pass_by_ref)] // Required by serde's `skip_serializing_if`
+    pub(crate) fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Put `packages`, which start out in config order, into this order.
+    pub(crate) fn sort(self, packages: &mut [Package]) {
+        match self {
+            Self::Config => {}
+            Self::Alphabetical => {
+                packages.sort_by(|left, right| left.name.as_deref().cmp(&right.name.as_deref()));
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, Hash, PartialEq, Serialize)]
 #[serde(transparent)]
 pub(crate) struct PackageName(String);
 
@@ -358,6 +725,37 @@ impl ChangelogSections {
         })
     }
 
+    /// Add every commit type with a `section` to that section, creating it if needed.
+    fn with_commit_types(mut self, commit_types: &[CommitType]) -> Self {
+        for commit_type in commit_types {
+            let Some(section_name) = &commit_type.section else {
+                continue;
+            };
+            let source =
+                ChangeType::Custom(ChangelogSectionSource::CommitType(commit_type.name.clone()));
+            if let Some((_, sources)) = self.0.iter_mut().find(|(name, _)| name == section_name) {
+                sources.push(source);
+            } else {
+                self.0.push((section_name.clone(), vec![source]));
+            }
+        }
+        self
+    }
+
+    /// Add the section for dependency updates, if they're recognized, creating it if needed.
+    fn with_dependency_updates(mut self, dependency_updates: Option<&DependencyUpdates>) -> Self {
+        let Some(DependencyUpdates { section, .. }) = dependency_updates else {
+            return self;
+        };
+        let source = ChangeType::Custom(ChangelogSectionSource::DependencyUpdate);
+        if let Some((_, sources)) = self.0.iter_mut().find(|(name, _)| name == section) {
+            sources.push(source);
+        } else {
+            self.0.push((section.clone(), vec![source]));
+        }
+        self
+    }
+
     pub(crate) fn footers(&self) -> Vec<CommitFooter> {
         self.0
             .iter()
@@ -451,7 +849,11 @@ impl From<ChangelogSections> for Vec<ChangelogSection> {
                         ChangeType::Breaking => Some("Breaking Changes".into()),
                         ChangeType::Feature => Some("Features".into()),
                         ChangeType::Fix => Some("Fixes".into()),
-                        ChangeType::Custom(ChangelogSectionSource::CommitFooter(_)) => None,
+                        ChangeType::Custom(
+                            ChangelogSectionSource::CommitFooter(_)
+                            | ChangelogSectionSource::CommitType(_)
+                            | ChangelogSectionSource::DependencyUpdate,
+                        ) => None,
                     })
                     .collect(),
             })
@@ -468,7 +870,7 @@ impl IntoIterator for ChangelogSections {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Eq, PartialEq, Serialize)]
 pub(crate) struct Asset {
     pub(crate) path: PathBuf,
     name: Option<String>,
@@ -499,6 +901,10 @@ pub(crate) struct AssetNameError {
 pub(crate) enum ChangelogSectionSource {
     CommitFooter(CommitFooter),
     CustomChangeType(CustomChangeType),
+    /// A conventional commit type configured in `commit_types`
+    CommitType(CustomChangeType),
+    /// A commit recognized by `dependency_updates`
+    DependencyUpdate,
 }
 
 impl From<CommitFooter> for ChangelogSectionSource {
@@ -517,7 +923,10 @@ impl Display for ChangelogSectionSource {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::CommitFooter(footer) => footer.fmt(f),
-            Self::CustomChangeType(change_type) => change_type.fmt(f),
+            Self::CustomChangeType(change_type) | Self::CommitType(change_type) => {
+                change_type.fmt(f)
+            }
+            Self::DependencyUpdate => f.write_str("dependency update"),
         }
     }
 }
@@ -545,6 +954,20 @@ pub(crate) enum Error {
     #[error(transparent)]
     #[diagnostic(transparent)]
     Git(#[from] git::Error),
+    #[error("There is no unreleased pre-release to bump")]
+    #[diagnostic(
+        code(package::no_prerelease_to_bump),
+        help("`prerelease_only` needs a pre-release newer than the latest stable version, release one without it first."),
+        url("https://knope.tech/reference/config-file/steps/prepare-release/#pre-release-only")
+    )]
+    NoPrereleaseToBump,
+    #[error("No packages have a source of truth to sync")]
+    #[diagnostic(
+        code(package::no_source_of_truth),
+        help("Set `source_of_truth` on a package to the versioned file the others should match."),
+        url("https://knope.tech/reference/config-file/packages/#source_of_truth")
+    )]
+    NoSourceOfTruth,
     #[error("No packages to operate on")]
     #[diagnostic(
         code(package::no_defined_packages),
@@ -561,4 +984,22 @@ pub(crate) enum Error {
     #[error(transparent)]
     #[diagnostic(transparent)]
     New(#[from] PackageNewError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    CargoWorkspace(#[from] cargo::EditError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    PackageJsonDependencies(#[from] package_json::EditError),
+    #[error("The environment variable {var} is not a valid version: {value}")]
+    #[diagnostic(
+        code(package::invalid_env_version),
+        help("Set {var} to a Semantic Version, like 1.2.3, or unset it to ignore it."),
+        url("https://knope.tech/reference/config-file/packages/#version_env_var")
+    )]
+    InvalidEnvVersion {
+        var: String,
+        value: String,
+        #[source]
+        source: knope_versioning::semver::Error,
+    },
 }
@@ -1,13 +1,19 @@
 use std::fmt::Display;
 
+use itertools::Itertools;
 use knope_versioning::{
-    Action, GoVersioning, Label, PreVersion, Prerelease, StableVersion, Version,
+    Action, BuildMetadata, GoVersioning, Label, PreVersion, Prerelease, PrereleaseSeparator,
+    StableVersion, Version,
 };
 use miette::Diagnostic;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::{package::Package, ChangeType, CurrentVersions, Prereleases, Release};
+use super::{
+    package::Package, write_output_file, ChangeType, CurrentVersions, Prereleases, Release,
+};
 use crate::{
+    config::Bump,
     dry_run::DryRun,
     fs,
     integrations::{git, git::get_current_versions_from_tags},
@@ -18,16 +24,19 @@ use crate::{
 
 /// The various rules that can be used when bumping the current version of a project via
 /// [`crate::step::Step::BumpVersion`].
-#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Eq, Serialize)]
 #[serde(tag = "rule")]
 pub(crate) enum Rule {
     Major,
     Minor,
     Patch,
     Pre {
+        #[schemars(with = "String")]
         label: Label,
         #[serde(skip)]
         stable_rule: ConventionalRule,
+        #[serde(skip)]
+        format: PrereleaseFormat,
     },
     Release,
 }
@@ -42,6 +51,26 @@ impl From<ConventionalRule> for Rule {
     }
 }
 
+/// How a package writes new pre-release versions, from its `prerelease_separator` and
+/// `prerelease_start`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) struct PrereleaseFormat {
+    pub(crate) separator: PrereleaseSeparator,
+    /// The number of the first pre-release for each label
+    pub(crate) start: u64,
+}
+
+/// How a package with a major version of 0 is bumped, from its `strict_semver` option.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub(crate) enum ZeroVersioning {
+    /// Treat `0.x` as initial development: major rules bump the minor component and minor rules
+    /// bump the patch component.
+    #[default]
+    InitialDevelopment,
+    /// Bump `0.x` versions the same as any other, so a major rule releases `1.0.0`.
+    Strict,
+}
+
 /// The rules that can be derived from Conventional Commits.
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub(crate) enum ConventionalRule {
@@ -99,6 +128,17 @@ impl From<ChangeType> for ConventionalRule {
     }
 }
 
+impl From<Bump> for Option<ConventionalRule> {
+    fn from(bump: Bump) -> Self {
+        match bump {
+            Bump::None => None,
+            Bump::Patch => Some(ConventionalRule::Patch),
+            Bump::Minor => Some(ConventionalRule::Minor),
+            Bump::Major => Some(ConventionalRule::Major),
+        }
+    }
+}
+
 /// The implementation of [`crate::step::Step::BumpVersion`].
 ///
 /// Bumps the version of every configured package using `rule`.
@@ -115,6 +155,16 @@ pub(crate) fn bump_version_and_update_state(
         .packages
         .into_iter()
         .map(|package| {
+            let rule = match rule {
+                Rule::Pre {
+                    label, stable_rule, ..
+                } => Rule::Pre {
+                    label: label.clone(),
+                    stable_rule: *stable_rule,
+                    format: package.prerelease_format,
+                },
+                _ => rule.clone(),
+            };
             let version = if let Some(version) = package.override_version.clone() {
                 VersionFromSource {
                     version,
@@ -123,7 +173,8 @@ pub(crate) fn bump_version_and_update_state(
             } else {
                 let version = bump(
                     package.get_version(state.verbose, &state.all_git_tags),
-                    rule,
+                    &rule,
+                    package.zero_versioning,
                     state.verbose,
                 )?;
                 VersionFromSource {
@@ -131,13 +182,14 @@ pub(crate) fn bump_version_and_update_state(
                     source: VersionSource::Calculated,
                 }
             };
-            let mut package = package.write_version(&version, &mut dry_run_stdout)?;
+            let mut package = package.write_version(&version, None, &mut dry_run_stdout)?;
             let additional_tags = package.pending_tags;
             package.pending_tags = Vec::new();
             package.prepared_release = Some(Release::empty(version.version, additional_tags));
             Ok(package)
         })
         .collect::<Result<Vec<Package>, Error>>()?;
+    write_output_file(&state, &mut dry_run_stdout)?;
     if let Some(stdout) = dry_run_stdout {
         Ok(RunType::DryRun { state, stdout })
     } else {
@@ -147,7 +199,8 @@ pub(crate) fn bump_version_and_update_state(
 
 impl Package {
     /// Get the current version of a package determined by the last tag for the package _and_ the
-    /// version in versioned files. The version from files takes precedent over version from tag.
+    /// version in versioned files (or `version_env_var`). The version from files takes precedent
+    /// over version from tag.
     pub(crate) fn get_version(&self, verbose: Verbose, all_tags: &[String]) -> CurrentVersions {
         if let Verbose::Yes = verbose {
             println!("Looking for Git tags matching package name.");
@@ -155,15 +208,21 @@ impl Package {
         let mut current_versions =
             get_current_versions_from_tags(self.name.as_deref(), verbose, all_tags);
 
-        if let Some(version_from_files) = self.version_from_files() {
-            current_versions.update_version(version_from_files.clone());
+        if let Some(current_version) = self.current_version() {
+            if let (Verbose::Yes, Some(version)) = (verbose, &self.version_from_env) {
+                println!("Using version {version} from environment variable");
+            }
+            current_versions.update_version(current_version.clone());
         }
 
         current_versions
     }
 
-    pub(crate) fn version_from_files(&self) -> Option<&Version> {
-        Some(self.files.as_ref()?.get_version())
+    /// The version set by `version_env_var`, if any, otherwise the version from versioned files.
+    pub(crate) fn current_version(&self) -> Option<&Version> {
+        self.version_from_env
+            .as_ref()
+            .or_else(|| Some(self.files.as_ref()?.get_version()))
     }
 
     /// Consumes a [`Package`], writing it back to the file it came from. Returns the new version
@@ -173,9 +232,10 @@ impl Package {
     pub(crate) fn write_version(
         mut self,
         version: &VersionFromSource,
+        build_metadata: Option<&BuildMetadata>,
         dry_run: DryRun,
     ) -> Result<Self, UpdatePackageVersionError> {
-        let version_str = version.version.to_string();
+        let version_str = version.version.with_build(build_metadata);
         let Some(files) = self.files.clone() else {
             return Ok(self);
         };
@@ -186,11 +246,25 @@ impl Package {
             } => GoVersioning::BumpMajor,
             _ => self.go_versioning,
         };
-        let actions = files.set_version(&version.version, go_versioning)?;
+        // Some files (with `strip_prerelease`) get a different version than the package
+        let file_versions = files
+            .versioned_files()
+            .iter()
+            .map(|file| (file.path().clone(), file.version_for(&version.version)))
+            .filter(|(_, file_version)| *file_version != version.version)
+            .collect_vec();
+        let actions = files.set_version(&version.version, build_metadata, go_versioning)?;
         for action in actions {
             match action {
                 Action::WriteToFile { path, content } => {
-                    fs::write(dry_run, &version_str, &path.to_path(""), content)?;
+                    let version_str = file_versions
+                        .iter()
+                        .find(|(file_path, _)| *file_path == path)
+                        .map_or_else(|| version_str.clone(), |(_, version)| version.to_string());
+                    fs::write(dry_run, &version_str, &path.to_path(""), &content)?;
+                    self.pending_writes
+                        .retain(|(pending_path, _)| *pending_path != path);
+                    self.pending_writes.push((path, content));
                 }
                 Action::AddTag { tag } => self.pending_tags.push(tag),
             }
@@ -220,6 +294,9 @@ pub(crate) enum Error {
     #[error(transparent)]
     #[diagnostic(transparent)]
     UpdatePackageVersion(#[from] UpdatePackageVersionError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fs(#[from] fs::Error),
 }
 
 #[derive(Debug, Diagnostic, thiserror::Error)]
@@ -227,8 +304,8 @@ pub(crate) enum Error {
 #[diagnostic(
     code(semver::invalid_pre_release_version),
     help(
-        "The pre-release component of a version must be in the format of `-<label>.N` \
-                    where <label> is a string and `N` is an integer"
+        "The pre-release component of a version must be in the format of `-<label>.N` or \
+                    `-<label>N` where <label> is a string and `N` is an integer"
     ),
     url("https://knope.tech/reference/concepts/semantic-versioning/#types-of-releases")
 )]
@@ -242,13 +319,16 @@ pub(crate) struct InvalidPreReleaseVersion(String);
 /// different behavior:
 /// 1. [`Rule::Major`] will bump the minor component.
 /// 2. [`Rule::Minor`] will bump the patch component.
+///
+/// Unless `zero_versioning` is [`ZeroVersioning::Strict`], which bumps them like any other version.
 pub(crate) fn bump(
     mut versions: CurrentVersions,
     rule: &Rule,
+    zero_versioning: ZeroVersioning,
     verbose: Verbose,
 ) -> Result<Version, InvalidPreReleaseVersion> {
     let stable = versions.stable.unwrap_or_default();
-    let is_0 = stable.major == 0;
+    let is_0 = stable.major == 0 && zero_versioning == ZeroVersioning::InitialDevelopment;
     match (rule, is_0) {
         (Rule::Major, false) => {
             let new_stable = stable.increment_major();
@@ -297,9 +377,22 @@ pub(crate) fn bump(
                 })?;
             Ok(Version::Stable(version))
         }
-        (Rule::Pre { label, stable_rule }, _) => {
-            bump_pre(stable, &versions.prereleases, label, *stable_rule, verbose)
-        }
+        (
+            Rule::Pre {
+                label,
+                stable_rule,
+                format,
+            },
+            _,
+        ) => bump_pre(
+            stable,
+            &versions.prereleases,
+            label,
+            *stable_rule,
+            *format,
+            zero_versioning,
+            verbose,
+        ),
     }
 }
 
@@ -309,6 +402,8 @@ pub(crate) fn bump(
 /// `semantic_rule` will be used to bump to primary components before the
 /// pre-release component is added.
 ///
+/// New pre-release components are written in `format`, even if the last one was written differently.
+///
 /// # Errors
 ///
 /// Can fail if there is an existing pre-release component that can't be incremented.
@@ -317,25 +412,35 @@ fn bump_pre(
     prereleases: &Prereleases,
     label: &Label,
     stable_rule: ConventionalRule,
+    format: PrereleaseFormat,
+    zero_versioning: ZeroVersioning,
     verbose: Verbose,
 ) -> Result<Version, InvalidPreReleaseVersion> {
     if let Verbose::Yes = verbose {
         println!("Pre-release label {label} selected. Determining next stable version...");
     }
-    let stable_component = bump(stable.into(), &stable_rule.into(), verbose)?.stable_component();
+    let stable_component =
+        bump(stable.into(), &stable_rule.into(), zero_versioning, verbose)?.stable_component();
     let pre_component = prereleases
         .get(&stable_component)
         .and_then(|pres| {
-            pres.get(label).cloned().map(|mut pre| {
+            pres.get(label).map(|pre| {
                 if let Verbose::Yes = verbose {
                     println!("Found existing pre-release version {pre}");
                 }
-                pre.version += 1;
-                pre
+                Prerelease {
+                    label: label.clone(),
+                    version: pre.version + 1,
+                    separator: format.separator,
+                }
             })
         })
         .unwrap_or_else(|| {
-            let pre = Prerelease::new(label.clone(), 0);
+            let pre = Prerelease {
+                label: label.clone(),
+                version: format.start,
+                separator: format.separator,
+            };
             if let Verbose::Yes = verbose {
                 println!("No existing pre-release version found; creating {pre}");
             }
@@ -348,6 +453,49 @@ fn bump_pre(
     }))
 }
 
+/// Increments only the pre-release number of the latest unreleased pre-release (like
+/// `1.2.0-rc.3` to `1.2.0-rc.4`), no matter which rule the changes would imply.
+///
+/// The label of that pre-release is kept, unless `label` is set.
+///
+/// Returns `None` if there is no pre-release newer than the latest stable version.
+pub(crate) fn bump_pre_only(
+    versions: CurrentVersions,
+    label: Option<&Label>,
+    format: PrereleaseFormat,
+    verbose: Verbose,
+) -> Option<Version> {
+    let CurrentVersions {
+        stable,
+        mut prereleases,
+    } = versions;
+    let (stable_component, pres) = prereleases
+        .pop_last()
+        .filter(|(stable_component, _)| stable.map_or(true, |stable| *stable_component > stable))?;
+    let label = label.map_or_else(|| pres.clone().into_last().label, Clone::clone);
+    let pre_component = pres.get(&label).map_or_else(
+        || Prerelease {
+            label: label.clone(),
+            version: format.start,
+            separator: format.separator,
+        },
+        |pre| {
+            if let Verbose::Yes = verbose {
+                println!("Found existing pre-release version {pre}, only bumping its number");
+            }
+            Prerelease {
+                label: label.clone(),
+                version: pre.version + 1,
+                separator: format.separator,
+            }
+        },
+    );
+    Some(Version::Pre(PreVersion {
+        stable_component,
+        pre_component,
+    }))
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test_bump {
@@ -358,7 +506,13 @@ mod test_bump {
     #[test]
     fn major() {
         let stable = Version::new(1, 2, 3, None);
-        let version = bump(stable.into(), &Rule::Major, Verbose::No).unwrap();
+        let version = bump(
+            stable.into(),
+            &Rule::Major,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(2, 0, 0, None));
     }
@@ -366,14 +520,54 @@ mod test_bump {
     #[test]
     fn major_0() {
         let stable = Version::new(0, 1, 2, None);
-        let version = bump(stable.into(), &Rule::Major, Verbose::No).unwrap();
+        let version = bump(
+            stable.into(),
+            &Rule::Major,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(0, 2, 0, None));
     }
 
+    #[test]
+    fn major_0_strict() {
+        let stable = Version::new(0, 2, 3, None);
+        let version = bump(
+            stable.into(),
+            &Rule::Major,
+            ZeroVersioning::Strict,
+            Verbose::No,
+        )
+        .unwrap();
+
+        assert_eq!(version, Version::new(1, 0, 0, None));
+    }
+
+    #[test]
+    fn minor_0_strict() {
+        let stable = Version::new(0, 2, 3, None);
+        let version = bump(
+            stable.into(),
+            &Rule::Minor,
+            ZeroVersioning::Strict,
+            Verbose::No,
+        )
+        .unwrap();
+
+        assert_eq!(version, Version::new(0, 3, 0, None));
+    }
+
     #[test]
     fn major_unset() {
-        let version = bump(CurrentVersions::default(), &Rule::Major, Verbose::No).unwrap();
+        let version = bump(
+            CurrentVersions::default(),
+            &Rule::Major,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(0, 1, 0, None));
     }
@@ -383,7 +577,13 @@ mod test_bump {
         for pre_version in ["1.2.4-rc.0", "1.3.0-rc.0", "2.0.0-rc.0"] {
             let mut versions = CurrentVersions::from(Version::new(1, 2, 3, None));
             versions.update_version(Version::from_str(pre_version).unwrap());
-            let version = bump(versions, &Rule::Major, Verbose::No).unwrap();
+            let version = bump(
+                versions,
+                &Rule::Major,
+                ZeroVersioning::default(),
+                Verbose::No,
+            )
+            .unwrap();
 
             assert_eq!(version, Version::new(2, 0, 0, None));
         }
@@ -392,7 +592,13 @@ mod test_bump {
     #[test]
     fn minor() {
         let stable = Version::new(1, 2, 3, None);
-        let version = bump(stable.into(), &Rule::Minor, Verbose::No).unwrap();
+        let version = bump(
+            stable.into(),
+            &Rule::Minor,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(1, 3, 0, None));
     }
@@ -400,14 +606,26 @@ mod test_bump {
     #[test]
     fn minor_0() {
         let stable = Version::new(0, 1, 2, None);
-        let version = bump(stable.into(), &Rule::Minor, Verbose::No).unwrap();
+        let version = bump(
+            stable.into(),
+            &Rule::Minor,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(0, 1, 3, None));
     }
 
     #[test]
     fn minor_unset() {
-        let version = bump(CurrentVersions::default(), &Rule::Minor, Verbose::No).unwrap();
+        let version = bump(
+            CurrentVersions::default(),
+            &Rule::Minor,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(0, 0, 1, None));
     }
@@ -417,7 +635,13 @@ mod test_bump {
         for pre_version in ["1.2.4-rc.0", "1.3.0-rc.0"] {
             let mut versions = CurrentVersions::from(Version::new(1, 2, 3, None));
             versions.update_version(Version::from_str(pre_version).unwrap());
-            let version = bump(versions, &Rule::Minor, Verbose::No).unwrap();
+            let version = bump(
+                versions,
+                &Rule::Minor,
+                ZeroVersioning::default(),
+                Verbose::No,
+            )
+            .unwrap();
 
             assert_eq!(version, Version::new(1, 3, 0, None));
         }
@@ -426,7 +650,13 @@ mod test_bump {
     #[test]
     fn patch() {
         let stable = Version::new(1, 2, 3, None);
-        let version = bump(stable.into(), &Rule::Patch, Verbose::No).unwrap();
+        let version = bump(
+            stable.into(),
+            &Rule::Patch,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(1, 2, 4, None));
     }
@@ -434,14 +664,26 @@ mod test_bump {
     #[test]
     fn patch_0() {
         let stable = Version::new(0, 1, 0, None);
-        let version = bump(stable.into(), &Rule::Patch, Verbose::No).unwrap();
+        let version = bump(
+            stable.into(),
+            &Rule::Patch,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(0, 1, 1, None));
     }
 
     #[test]
     fn patch_unset() {
-        let version = bump(CurrentVersions::default(), &Rule::Patch, Verbose::No).unwrap();
+        let version = bump(
+            CurrentVersions::default(),
+            &Rule::Patch,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(0, 0, 1, None));
     }
@@ -450,7 +692,13 @@ mod test_bump {
     fn patch_after_pre() {
         let mut versions = CurrentVersions::from(Version::new(1, 2, 3, None));
         versions.update_version(Version::from_str("1.2.4-rc.0").unwrap());
-        let version = bump(versions, &Rule::Patch, Verbose::No).unwrap();
+        let version = bump(
+            versions,
+            &Rule::Patch,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(1, 2, 4, None));
     }
@@ -463,7 +711,9 @@ mod test_bump {
             &Rule::Pre {
                 label: Label::from("rc"),
                 stable_rule: ConventionalRule::Minor,
+                format: PrereleaseFormat::default(),
             },
+            ZeroVersioning::default(),
             Verbose::No,
         )
         .unwrap();
@@ -482,7 +732,9 @@ mod test_bump {
             &Rule::Pre {
                 label: Label::from("rc"),
                 stable_rule: ConventionalRule::Minor,
+                format: PrereleaseFormat::default(),
             },
+            ZeroVersioning::default(),
             Verbose::No,
         )
         .unwrap();
@@ -500,7 +752,9 @@ mod test_bump {
             &Rule::Pre {
                 label: Label::from("beta"),
                 stable_rule: ConventionalRule::Patch,
+                format: PrereleaseFormat::default(),
             },
+            ZeroVersioning::default(),
             Verbose::No,
         )
         .unwrap();
@@ -517,7 +771,9 @@ mod test_bump {
             &Rule::Pre {
                 label: Label::from("rc"),
                 stable_rule: ConventionalRule::Minor,
+                format: PrereleaseFormat::default(),
             },
+            ZeroVersioning::default(),
             Verbose::No,
         )
         .unwrap();
@@ -525,6 +781,32 @@ mod test_bump {
         assert_eq!(new, Version::from_str("1.3.0-rc.0").unwrap());
     }
 
+    #[test]
+    fn pre_with_format() {
+        let format = PrereleaseFormat {
+            separator: PrereleaseSeparator::None,
+            start: 1,
+        };
+        let rule = Rule::Pre {
+            label: Label::from("rc"),
+            stable_rule: ConventionalRule::Minor,
+            format,
+        };
+        let mut versions = CurrentVersions::from(Version::new(1, 2, 3, None));
+        let first = bump(
+            versions.clone(),
+            &rule,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
+        assert_eq!(first.to_string(), "1.3.0-rc1");
+
+        versions.update_version(first);
+        let second = bump(versions, &rule, ZeroVersioning::default(), Verbose::No).unwrap();
+        assert_eq!(second.to_string(), "1.3.0-rc2");
+    }
+
     #[test]
     fn release() {
         let mut versions = CurrentVersions::default();
@@ -532,8 +814,48 @@ mod test_bump {
         versions.update_version(Version::from_str("1.2.4-rc.1").unwrap());
         versions.update_version(Version::from_str("2.0.0-rc.2").unwrap());
 
-        let version = bump(versions, &Rule::Release, Verbose::No).unwrap();
+        let version = bump(
+            versions,
+            &Rule::Release,
+            ZeroVersioning::default(),
+            Verbose::No,
+        )
+        .unwrap();
 
         assert_eq!(version, Version::new(2, 0, 0, None));
     }
+
+    #[test]
+    fn pre_only_keeps_stable_component() {
+        let mut versions = CurrentVersions::from(Version::new(1, 1, 0, None));
+        versions.update_version(Version::from_str("1.2.0-rc.3").unwrap());
+
+        let version =
+            bump_pre_only(versions, None, PrereleaseFormat::default(), Verbose::No).unwrap();
+
+        assert_eq!(version, Version::from_str("1.2.0-rc.4").unwrap());
+    }
+
+    #[test]
+    fn pre_only_new_label() {
+        let versions = CurrentVersions::from(Version::from_str("1.2.0-beta.3").unwrap());
+
+        let version = bump_pre_only(
+            versions,
+            Some(&Label::from("rc")),
+            PrereleaseFormat::default(),
+            Verbose::No,
+        )
+        .unwrap();
+
+        assert_eq!(version, Version::from_str("1.2.0-rc.0").unwrap());
+    }
+
+    #[test]
+    fn pre_only_already_released() {
+        let mut versions = CurrentVersions::from(Version::new(1, 2, 0, None));
+        versions.update_version(Version::from_str("1.2.0-rc.3").unwrap());
+
+        assert!(bump_pre_only(versions, None, PrereleaseFormat::default(), Verbose::No).is_none());
+    }
 }
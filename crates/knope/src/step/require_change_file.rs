@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use git_conventional::Commit;
+use itertools::Itertools;
+use miette::Diagnostic;
+
+use crate::{integrations::git, state::RunType};
+
+/// Fail if any commit since `HEAD` branched off of `base` needs a change file, but no change file
+/// was added.
+///
+/// Conventional commits document themselves (or are explicitly not worth documenting, like
+/// `chore:`), so only the other commits need a change file.
+pub(super) fn run(base: Option<&str>, run_type: RunType) -> Result<RunType, Error> {
+    let (state, dry_run) = run_type.decompose();
+    let base = base.unwrap_or(DEFAULT_BASE);
+    let undocumented =
+        git::get_commits_after_revision(base, None, false, None, false, state.verbose)?
+            .commits
+            .into_iter()
+            .filter(|commit| !commit.is_merge && Commit::parse(commit.message.trim()).is_err())
+            .map(|commit| {
+                commit
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect_vec();
+    if undocumented.is_empty() {
+        return Ok(RunType::recompose(state, dry_run));
+    }
+    let change_file_added = git::files_added_since(base)?
+        .iter()
+        .any(|path| is_change_file(path, &state.change_directory));
+    if !change_file_added {
+        return Err(Error::MissingChangeFile {
+            base: base.to_string(),
+            commits: undocumented
+                .iter()
+                .map(|commit| format!("\n- {commit}"))
+                .join(""),
+        });
+    }
+    Ok(RunType::recompose(state, dry_run))
+}
+
+const DEFAULT_BASE: &str = "main";
+fn is_change_file(path: &Path, change_directory: &Path) -> bool {
+    path.starts_with(change_directory) && path.extension().is_some_and(|ext| ext == "md")
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::Error),
+    #[error("No change file was added since {base}, but these commits need one:{commits}")]
+    #[diagnostic(
+        code(require_change_file::missing_change_file),
+        help("Run `knope document-change` (or create a Markdown file in `.changeset`) to describe these changes, or use conventional commits."),
+        url("https://knope.tech/reference/config-file/steps/require-change-file/")
+    )]
+    MissingChangeFile { base: String, commits: String },
+}
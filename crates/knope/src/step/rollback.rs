@@ -0,0 +1,75 @@
+use itertools::Itertools;
+use miette::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    integrations::git,
+    state::RunType,
+    step::releases::{
+        conventional_commits::{self, ReleaseCommits},
+        is_release_tag, Package,
+    },
+};
+
+/// The inner content of a [`super::Step::Rollback`] step.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct Rollback {
+    /// A regex for the first line of release commits, which are rolled back even without a
+    /// release tag. Defaults to the same pattern as `PrepareRelease`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    release_commit_pattern: Option<String>,
+}
+
+/// Undo the release on the current commit: revert the commit (which restores any change files it
+/// consumed), then delete its release tags.
+///
+/// A commit without release tags is still rolled back if its message matches
+/// `release_commit_pattern`, like when the release was never tagged.
+pub(super) fn run(rollback: &Rollback, run_type: RunType) -> Result<RunType, Error> {
+    let (mut state, mut dry_run) = run_type.decompose();
+    let release_tags = git::tags_on_head()?
+        .into_iter()
+        .filter(|tag| is_knope_release_tag(tag, &state.packages))
+        .collect_vec();
+    if release_tags.is_empty()
+        && !ReleaseCommits::new(rollback.release_commit_pattern.as_deref())?
+            .matches(&git::head_message()?)
+    {
+        return Err(Error::NothingToRollBack);
+    }
+
+    // Tags are only deleted once the revert worked, so a failed rollback leaves the release intact
+    git::revert_head(&mut dry_run, &state.git_config)?;
+    for tag in &release_tags {
+        git::delete_tag(&mut dry_run, tag)?;
+    }
+
+    state.all_git_tags.retain(|tag| !release_tags.contains(tag));
+    Ok(RunType::recompose(state, dry_run))
+}
+
+fn is_knope_release_tag(tag: &str, packages: &[Package]) -> bool {
+    packages
+        .iter()
+        .any(|package| is_release_tag(tag, &package.name))
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    ReleaseCommits(#[from] conventional_commits::Error),
+    #[error("There is no release on the current commit to roll back")]
+    #[diagnostic(
+        code(rollback::nothing_to_roll_back),
+        help(
+            "Rollback only undoes a release whose tag points at the current commit, or a release commit matching `release_commit_pattern`."
+        ),
+        url("https://knope.tech/reference/config-file/steps/rollback/")
+    )]
+    NothingToRollBack,
+}
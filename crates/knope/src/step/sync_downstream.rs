@@ -0,0 +1,172 @@
+use std::{io::Write, path::PathBuf};
+
+use itertools::Itertools;
+use knope_versioning::{
+    Action, GoVersioning, PackageNewError, SetError, UnknownFile, VersionedFile,
+    VersionedFileError, VersionedFilePath,
+};
+use miette::Diagnostic;
+use relative_path::RelativePathBuf;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fs,
+    integrations::git,
+    state::RunType,
+    step::releases::{package, Package},
+};
+
+/// Replaced with the version in the commit message.
+const VERSION_PLACEHOLDER: &str = "$version";
+
+/// The inner content of a [`super::Step::SyncDownstream`] step.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct SyncDownstream {
+    /// The root of the other checkout, relative to the current directory.
+    path: PathBuf,
+    /// The files to update, relative to `path`, in any format supported by a package's
+    /// `versioned_files`.
+    #[schemars(with = "Vec<String>")]
+    versioned_files: Vec<RelativePathBuf>,
+    /// If set, the updated files are committed in `path` with this message, where `$version` is
+    /// replaced with the version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit_message: Option<String>,
+    /// The package whose version is synced, only needed if there are multiple packages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    package: Option<String>,
+}
+
+/// Set the version of every one of `versioned_files` (relative to the checkout at `path`) to the
+/// version of `package` (or the only package), then commit them there if there's a
+/// `commit_message`.
+pub(super) fn run(sync: &SyncDownstream, run_type: RunType) -> Result<RunType, Error> {
+    let SyncDownstream {
+        path,
+        versioned_files,
+        commit_message,
+        package,
+    } = sync;
+    let (state, mut dry_run) = run_type.decompose();
+    let package = select_package(&state.packages, package.as_deref())?;
+    let version = package
+        .prepared_release
+        .as_ref()
+        .map(|release| &release.version)
+        .or_else(|| package.current_version())
+        .ok_or(Error::NoVersion)?;
+    if !path.is_dir() {
+        return Err(Error::MissingCheckout {
+            path: path.display().to_string(),
+        });
+    }
+
+    let mut written = Vec::with_capacity(versioned_files.len());
+    for file in versioned_files {
+        let file_path = VersionedFilePath::new(file.clone())?;
+        let full_path = file.to_path(path);
+        let content = fs::read_to_string(&full_path)?;
+        let versioned_file = VersionedFile::new(&file_path, content, &[] as &[String])?;
+        // Each file gets its own package, since they don't have to agree on their current version
+        let actions = knope_versioning::Package::new(vec![versioned_file])?.set_version(
+            version,
+            None,
+            GoVersioning::default(),
+        )?;
+        for action in actions {
+            if let Action::WriteToFile {
+                path: file,
+                content,
+            } = action
+            {
+                let full_path = file.to_path(path);
+                fs::write(&mut dry_run, &version.to_string(), &full_path, content)?;
+                written.push(file.to_path(""));
+            }
+        }
+    }
+
+    if let Some(message) = commit_message.as_deref() {
+        let message = message.replace(VERSION_PLACEHOLDER, &version.to_string());
+        if let Some(stdout) = dry_run.as_mut() {
+            writeln!(
+                stdout,
+                "Would commit {} in {} with the message {message:?}",
+                written.iter().map(|file| file.display()).join(", "),
+                path.display()
+            )
+            .map_err(fs::Error::Stdout)?;
+        } else {
+            git::commit_paths_in(path, &written, &message, &state.git_config)?;
+        }
+    }
+    Ok(RunType::recompose(state, dry_run))
+}
+
+fn select_package<'a>(packages: &'a [Package], name: Option<&str>) -> Result<&'a Package, Error> {
+    match (packages, name) {
+        ([], _) => Err(package::Error::NoDefinedPackages.into()),
+        (_, Some(name)) => packages
+            .iter()
+            .find(|package| package.name.as_deref() == Some(name))
+            .ok_or_else(|| Error::UnknownPackage {
+                name: name.to_string(),
+            }),
+        ([package], None) => Ok(package),
+        (_, None) => Err(Error::TooManyPackages),
+    }
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fs(#[from] fs::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Package(#[from] package::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    UnknownFile(#[from] UnknownFile),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    VersionedFile(#[from] VersionedFileError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    NewPackage(#[from] PackageNewError),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    SetVersion(#[from] SetError),
+    #[error("The downstream checkout {path} doesn't exist")]
+    #[diagnostic(
+        code(sync_downstream::missing_checkout),
+        help("Clone the downstream repository there first, `path` is relative to the current directory."),
+        url("https://knope.tech/reference/config-file/steps/sync-downstream/")
+    )]
+    MissingCheckout { path: String },
+    #[error("Could not determine the version to sync")]
+    #[diagnostic(
+        code(sync_downstream::no_version),
+        help("The package needs a versioned file, or a `PrepareRelease` or `BumpVersion` step before this one."),
+        url("https://knope.tech/reference/config-file/steps/sync-downstream/")
+    )]
+    NoVersion,
+    #[error("Too many packages defined")]
+    #[diagnostic(
+        code(sync_downstream::too_many_packages),
+        help("Set `package` to the name of the package whose version to sync."),
+        url("https://knope.tech/reference/config-file/steps/sync-downstream/")
+    )]
+    TooManyPackages,
+    #[error("There's no package named {name}")]
+    #[diagnostic(
+        code(sync_downstream::unknown_package),
+        help("`package` must be the name of one of the packages in `knope.toml`."),
+        url("https://knope.tech/reference/config-file/steps/sync-downstream/")
+    )]
+    UnknownPackage { name: String },
+}
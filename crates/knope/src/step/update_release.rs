@@ -0,0 +1,124 @@
+use std::mem;
+
+use knope_versioning::Version;
+use miette::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    integrations::github as github_api,
+    state::{self, RunType},
+    step::releases::{
+        self, changelog::ParseError, github, package, Package, Release, ReleaseNotes,
+    },
+};
+
+/// The inner content of a [`super::Step::UpdateRelease`] step.
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct UpdateRelease {
+    /// The version whose release to update, instead of the prepared (or current) version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    version: Option<Version>,
+    /// How to shorten the new release notes.
+    #[serde(default, skip_serializing_if = "ReleaseNotes::is_default")]
+    release_notes: ReleaseNotes,
+    /// If set, the release is changed to (or from) a draft.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    draft: Option<bool>,
+    /// If set, the release is changed to (or from) a prerelease.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prerelease: Option<bool>,
+}
+
+/// Replace the notes of the existing GitHub release of every package with the notes of that
+/// version in its changelog.
+pub(super) fn run(update: &UpdateRelease, run_type: RunType) -> Result<RunType, Error> {
+    let (mut state, mut dry_run) = run_type.decompose();
+    let Some(github_config) = state.github_config.clone() else {
+        return Err(Error::NotConfigured);
+    };
+    if state.packages.is_empty() {
+        return Err(package::Error::NoDefinedPackages.into());
+    }
+    for package in &state.packages {
+        let release = release_to_update(package, update.version.as_ref())?;
+        let tag = releases::tag_name(
+            &release.version,
+            release.build_metadata.as_ref(),
+            &package.name,
+        );
+        state.github = github::update_release(
+            package,
+            &release,
+            mem::replace(&mut state.github, state::GitHub::New),
+            &github_config,
+            &mut dry_run,
+            &tag,
+            &update.release_notes,
+            update.draft,
+            update.prerelease,
+        )?;
+    }
+    Ok(RunType::recompose(state, dry_run))
+}
+
+/// The release prepared earlier in the workflow, or the entry in the changelog for `version` (or
+/// the current version).
+fn release_to_update(package: &Package, version: Option<&Version>) -> Result<Release, Error> {
+    if let (None, Some(release)) = (version, package.prepared_release.as_ref()) {
+        return Ok(release.clone());
+    }
+    let version = version
+        .or_else(|| package.current_version())
+        .ok_or(Error::NoVersion)?;
+    let changelog = package.changelog.as_ref().ok_or(Error::NoChangelog)?;
+    changelog
+        .get_release(version, package.files.clone(), package.go_versioning)?
+        .ok_or_else(|| Error::NoChangelogEntry {
+            version: version.to_string(),
+        })
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("GitHub is not configured")]
+    #[diagnostic(
+        code(update_release::github::not_configured),
+        help("GitHub must be configured in order to use the UpdateRelease step"),
+        url("https://knope.tech/reference/config-file/github/")
+    )]
+    NotConfigured,
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Package(#[from] package::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    ChangelogParse(#[from] ParseError),
+    #[error("Could not determine the version of the release to update")]
+    #[diagnostic(
+        code(update_release::no_version),
+        help("Set `version`, or give the package a versioned file."),
+        url("https://knope.tech/reference/config-file/steps/update-release/")
+    )]
+    NoVersion,
+    #[error("The package has no changelog to take the release notes from")]
+    #[diagnostic(
+        code(update_release::no_changelog),
+        help("Set `changelog` for the package, the new release notes come from there."),
+        url("https://knope.tech/reference/config-file/packages/#changelog")
+    )]
+    NoChangelog,
+    #[error("The changelog has no entry for version {version}")]
+    #[diagnostic(
+        code(update_release::no_changelog_entry),
+        help(
+            "Add a section for {version} to the changelog, the new release notes come from there."
+        ),
+        url("https://knope.tech/reference/config-file/steps/update-release/")
+    )]
+    NoChangelogEntry { version: String },
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    GitHub(#[from] github_api::UpdateReleaseError),
+}
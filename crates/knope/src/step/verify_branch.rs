@@ -0,0 +1,85 @@
+use miette::Diagnostic;
+
+use crate::{integrations::git, state::RunType};
+
+/// Check that `HEAD` is on one of the `allowed` branches, so releases don't happen from the wrong
+/// branch by accident.
+pub(super) fn run(allowed: &[String], run_type: RunType) -> Result<RunType, Error> {
+    let branch = git::current_branch_name()?;
+    if !allowed
+        .iter()
+        .any(|pattern| matches_pattern(pattern, &branch))
+    {
+        return Err(Error::NotAllowed {
+            branch,
+            allowed: allowed.join(", "),
+        });
+    }
+    Ok(run_type)
+}
+
+pub(super) fn default_allowed() -> Vec<String> {
+    vec![String::from("main")]
+}
+
+/// Whether `branch` matches `pattern`, where any `*` in the pattern matches any characters
+/// (including none).
+fn matches_pattern(pattern: &str, branch: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return false;
+    };
+    let Some(mut rest) = branch.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // The last part has to be at the very end
+            return rest.len() >= part.len() && rest.ends_with(part);
+        }
+        let Some(index) = rest.find(part) else {
+            return false;
+        };
+        rest = rest.get(index + part.len()..).unwrap_or_default();
+    }
+    // There was no `*`, so the whole branch had to match
+    rest.is_empty()
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Git(#[from] git::Error),
+    #[error("Releasing from branch {branch} isn't allowed")]
+    #[diagnostic(
+        code(verify_branch::not_allowed),
+        help("Switch to one of these branches first: {allowed}"),
+        url("https://knope.tech/reference/config-file/steps/verify-branch/")
+    )]
+    NotAllowed { branch: String, allowed: String },
+}
+
+#[cfg(test)]
+mod test_matches_pattern {
+    use super::matches_pattern;
+
+    #[test]
+    fn exact() {
+        assert!(matches_pattern("main", "main"));
+        assert!(!matches_pattern("main", "main-2"));
+        assert!(!matches_pattern("main", "not-main"));
+    }
+
+    #[test]
+    fn wildcard() {
+        assert!(matches_pattern("release/*", "release/1.2.0"));
+        assert!(matches_pattern("release/*", "release/"));
+        assert!(!matches_pattern("release/*", "feature/release/1.2.0"));
+        assert!(matches_pattern("*-stable", "1.x-stable"));
+        assert!(matches_pattern("v*.x", "v1.x"));
+        assert!(!matches_pattern("v*.x", "v1.y"));
+        assert!(matches_pattern("*", "anything"));
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use knope_versioning::Version;
+use miette::Diagnostic;
+
+use crate::{
+    state::RunType,
+    step::releases::{changelog::ParseError, package},
+};
+
+/// Check that the changelog of every package has an entry for the version being released.
+pub(super) fn run(run_type: RunType) -> Result<RunType, Error> {
+    let (state, dry_run) = run_type.decompose();
+    if state.packages.is_empty() {
+        return Err(package::Error::NoDefinedPackages.into());
+    }
+    let mut checked_any = false;
+    for package in &state.packages {
+        let Some(changelog) = package.changelog.as_ref() else {
+            continue;
+        };
+        let version = package
+            .prepared_release
+            .as_ref()
+            .map(|release| &release.version)
+            .or_else(|| package.current_version());
+        let Some(version) = version else {
+            continue;
+        };
+        checked_any = true;
+        if changelog
+            .get_release(version, None, package.go_versioning)?
+            .is_none()
+        {
+            return Err(Error::MissingEntry {
+                version: version.clone(),
+                path: changelog.path.clone(),
+            });
+        }
+    }
+    if !checked_any {
+        return Err(Error::NoChangelog);
+    }
+    Ok(RunType::recompose(state, dry_run))
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Package(#[from] package::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Parse(#[from] ParseError),
+    #[error("{path} has no entry for version {version}", path = path.display())]
+    #[diagnostic(
+        code(verify_changelog_entry::missing_entry),
+        help("Run `PrepareRelease` before releasing, or add a heading for this version by hand."),
+        url("https://knope.tech/reference/config-file/steps/verify-changelog-entry/")
+    )]
+    MissingEntry { version: Version, path: PathBuf },
+    #[error("There is no changelog to verify")]
+    #[diagnostic(
+        code(verify_changelog_entry::no_changelog),
+        help("Set `changelog` on a package with a version to check its changelog."),
+        url("https://knope.tech/reference/config-file/steps/verify-changelog-entry/")
+    )]
+    NoChangelog,
+}
@@ -0,0 +1,125 @@
+use std::io::Write;
+
+use miette::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fs,
+    integrations::registry::{self, Registry},
+    state::RunType,
+    step::releases::{package, Package},
+};
+
+/// The inner content of a [`super::Step::VerifyRegistryVersion`] step.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct VerifyRegistryVersion {
+    /// The registry the package is published to.
+    registry: Registry,
+    /// The name of the package in the registry.
+    name: String,
+    /// The package whose version is checked, only needed if there are multiple packages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    package: Option<String>,
+}
+
+/// Fail unless the version of `package` (or the only package) is greater than every version of
+/// `name` already published to `registry`.
+pub(super) fn run(verify: &VerifyRegistryVersion, run_type: RunType) -> Result<RunType, Error> {
+    let VerifyRegistryVersion {
+        registry,
+        name,
+        package,
+    } = verify;
+    let (state, mut dry_run) = run_type.decompose();
+    let package = select_package(&state.packages, package.as_deref())?;
+    let version = package
+        .prepared_release
+        .as_ref()
+        .map(|release| &release.version)
+        .or_else(|| package.current_version())
+        .ok_or(Error::NoVersion)?;
+
+    if let Some(stdout) = dry_run.as_mut() {
+        writeln!(
+            stdout,
+            "Would check that {version} is greater than every version of {name} on {registry}"
+        )
+        .map_err(fs::Error::Stdout)?;
+        return Ok(RunType::recompose(state, dry_run));
+    }
+
+    if let Some(published) = registry.latest_version(name)? {
+        if published >= *version {
+            return Err(Error::NotGreater {
+                version: version.to_string(),
+                published: published.to_string(),
+                name: name.clone(),
+                registry: *registry,
+            });
+        }
+    }
+    Ok(RunType::recompose(state, dry_run))
+}
+
+fn select_package<'a>(packages: &'a [Package], name: Option<&str>) -> Result<&'a Package, Error> {
+    match (packages, name) {
+        ([], _) => Err(package::Error::NoDefinedPackages.into()),
+        (_, Some(name)) => packages
+            .iter()
+            .find(|package| package.name.as_deref() == Some(name))
+            .ok_or_else(|| Error::UnknownPackage {
+                name: name.to_string(),
+            }),
+        ([package], None) => Ok(package),
+        (_, None) => Err(Error::TooManyPackages),
+    }
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fs(#[from] fs::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Package(#[from] package::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Registry(#[from] registry::Error),
+    #[error("Version {version} is not greater than {published}, which is already published for {name} on {registry}")]
+    #[diagnostic(
+        code(verify_registry_version::not_greater),
+        help(
+            "The local tags are probably behind the registry, fetch them or bump past {published}."
+        ),
+        url("https://knope.tech/reference/config-file/steps/verify-registry-version/")
+    )]
+    NotGreater {
+        version: String,
+        published: String,
+        name: String,
+        registry: Registry,
+    },
+    #[error("Could not determine the version to check")]
+    #[diagnostic(
+        code(verify_registry_version::no_version),
+        help("The package needs a versioned file, or a `PrepareRelease` or `BumpVersion` step before this one."),
+        url("https://knope.tech/reference/config-file/steps/verify-registry-version/")
+    )]
+    NoVersion,
+    #[error("Too many packages defined")]
+    #[diagnostic(
+        code(verify_registry_version::too_many_packages),
+        help("Set `package` to the name of the package whose version to check."),
+        url("https://knope.tech/reference/config-file/steps/verify-registry-version/")
+    )]
+    TooManyPackages,
+    #[error("There's no package named {name}")]
+    #[diagnostic(
+        code(verify_registry_version::unknown_package),
+        help("`package` must be the name of one of the packages in `knope.toml`."),
+        url("https://knope.tech/reference/config-file/steps/verify-registry-version/")
+    )]
+    UnknownPackage { name: String },
+}
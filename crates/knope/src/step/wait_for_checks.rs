@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use miette::Diagnostic;
+
+use crate::{integrations::github, state::RunType};
+
+pub(super) fn run(
+    checks: Option<&[String]>,
+    timeout_seconds: u64,
+    poll_interval_seconds: u64,
+    run_type: RunType,
+) -> Result<RunType, Error> {
+    let (mut state, mut dry_run) = run_type.decompose();
+    let Some(github_config) = &state.github_config else {
+        return Err(Error::NotConfigured);
+    };
+    if poll_interval_seconds == 0 {
+        return Err(Error::ZeroPollInterval);
+    }
+    state.github = github::wait_for_checks(
+        checks,
+        Duration::from_secs(timeout_seconds),
+        Duration::from_secs(poll_interval_seconds),
+        state.github,
+        github_config,
+        &mut dry_run,
+        state.verbose,
+    )?;
+    Ok(RunType::recompose(state, dry_run))
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error("GitHub is not configured")]
+    #[diagnostic(
+        code(wait_for_checks::github::not_configured),
+        help("GitHub must be configured in order to use the WaitForChecks step"),
+        url("https://knope.tech/reference/config-file/github/")
+    )]
+    NotConfigured,
+    #[error("`poll_interval_seconds` must be greater than 0")]
+    #[diagnostic(
+        code(wait_for_checks::zero_poll_interval),
+        help("Polling without a break would hit GitHub's rate limit, wait at least a second."),
+        url(
+            "https://knope.tech/reference/config-file/steps/wait-for-checks/#poll_interval_seconds"
+        )
+    )]
+    ZeroPollInterval,
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    GitHub(#[from] github::WaitForChecksError),
+}
@@ -0,0 +1,112 @@
+use std::{env, io::Write};
+
+use indexmap::IndexMap;
+use miette::Diagnostic;
+use serde_json::Value;
+
+use crate::{
+    fs,
+    integrations::ureq_err_to_string,
+    state::{RunType, State},
+    variables,
+    variables::{replace_variables, Template, Variable},
+};
+
+pub(super) fn run(
+    url: Option<&str>,
+    url_env_var: Option<&str>,
+    payload: Value,
+    variables: &IndexMap<String, Variable>,
+    run_type: RunType,
+) -> Result<RunType, Error> {
+    let (state, mut dry_run) = run_type.decompose();
+    if url.is_none() && url_env_var.is_none() {
+        return Err(Error::NoUrl);
+    }
+    let payload = replace_in_strings(payload, variables, &state)?;
+
+    if let Some(stdout) = dry_run.as_mut() {
+        // The URL is left out, since it's often a secret
+        writeln!(
+            stdout,
+            "Would send the following JSON to the webhook:\n{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        )
+        .map_err(fs::Error::Stdout)?;
+        return Ok(RunType::recompose(state, dry_run));
+    }
+
+    let url = match (url, url_env_var) {
+        (Some(url), _) => url.to_string(),
+        (None, Some(name)) => env::var(name).map_err(|_| Error::MissingEnvVar {
+            name: name.to_string(),
+        })?,
+        (None, None) => return Err(Error::NoUrl),
+    };
+    ureq::post(&url)
+        .send_json(&payload)
+        .map_err(|source| Error::Request {
+            err: ureq_err_to_string(source),
+        })?;
+    Ok(RunType::recompose(state, dry_run))
+}
+
+/// Replace `variables` in every string within `value`, so the results are escaped as JSON.
+fn replace_in_strings(
+    value: Value,
+    variables: &IndexMap<String, Variable>,
+    state: &State,
+) -> Result<Value, Error> {
+    Ok(match value {
+        Value::String(template) => Value::String(replace_variables(
+            Template {
+                template,
+                variables: variables.clone(),
+            },
+            state,
+        )?),
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| replace_in_strings(value, variables, state))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| Ok((key, replace_in_strings(value, variables, state)?)))
+                .collect::<Result<_, Error>>()?,
+        ),
+        other => other,
+    })
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Variables(#[from] variables::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fs(#[from] fs::Error),
+    #[error("The Webhook step needs a URL")]
+    #[diagnostic(
+        code(webhook::no_url),
+        help("Set either `url` or `url_env_var` on the step."),
+        url("https://knope.tech/reference/config-file/steps/webhook/")
+    )]
+    NoUrl,
+    #[error("The environment variable {name} is not set")]
+    #[diagnostic(
+        code(webhook::missing_env_var),
+        help("The webhook URL is read from `url_env_var`, so set it to the URL."),
+        url("https://knope.tech/reference/config-file/steps/webhook/")
+    )]
+    MissingEnvVar { name: String },
+    #[error("Could not send the webhook: {err}")]
+    #[diagnostic(
+        code(webhook::request),
+        help("Check that the URL is correct and accepts JSON."),
+        url("https://knope.tech/reference/config-file/steps/webhook/")
+    )]
+    Request { err: String },
+}
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use miette::Diagnostic;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fs,
+    state::RunType,
+    step::releases::{package, Package, TimeError},
+};
+
+/// The inner content of a [`super::Step::WriteReleaseNotes`] step.
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
+pub(crate) struct WriteReleaseNotes {
+    /// The file to write, relative to the current directory. It's replaced if it already exists.
+    path: PathBuf,
+    /// The package whose release notes are written, only needed if there are multiple packages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    package: Option<String>,
+}
+
+/// Write the changelog section of the release prepared for `package` (or the only package) to its
+/// own file at `path`.
+pub(super) fn run(write: &WriteReleaseNotes, run_type: RunType) -> Result<RunType, Error> {
+    let WriteReleaseNotes { path, package } = write;
+    let (state, mut dry_run) = run_type.decompose();
+    let release = select_package(&state.packages, package.as_deref())?
+        .prepared_release
+        .as_ref()
+        .ok_or(Error::NoPreparedRelease)?;
+    let title = release.title(true, true)?;
+    let section = release.body().map_or_else(
+        || format!("{title}\n"),
+        |body| format!("{title}\n\n{body}\n"),
+    );
+    fs::write(&mut dry_run, &format!("\n{section}"), path, &section)?;
+    Ok(RunType::recompose(state, dry_run))
+}
+
+fn select_package<'a>(packages: &'a [Package], name: Option<&str>) -> Result<&'a Package, Error> {
+    match (packages, name) {
+        ([], _) => Err(package::Error::NoDefinedPackages.into()),
+        (_, Some(name)) => packages
+            .iter()
+            .find(|package| package.name.as_deref() == Some(name))
+            .ok_or_else(|| Error::UnknownPackage {
+                name: name.to_string(),
+            }),
+        ([package], None) => Ok(package),
+        (_, None) => Err(Error::TooManyPackages),
+    }
+}
+
+#[derive(Debug, Diagnostic, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Fs(#[from] fs::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Package(#[from] package::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Time(#[from] TimeError),
+    #[error("There is no prepared release to write the notes of")]
+    #[diagnostic(
+        code(write_release_notes::no_prepared_release),
+        help("Run PrepareRelease before WriteReleaseNotes, which writes the notes of the new version."),
+        url("https://knope.tech/reference/config-file/steps/write-release-notes/")
+    )]
+    NoPreparedRelease,
+    #[error("Too many packages defined")]
+    #[diagnostic(
+        code(write_release_notes::too_many_packages),
+        help("Set `package` to the name of the package whose release notes to write."),
+        url("https://knope.tech/reference/config-file/steps/write-release-notes/")
+    )]
+    TooManyPackages,
+    #[error("There's no package named {name}")]
+    #[diagnostic(
+        code(write_release_notes::unknown_package),
+        help("`package` must be the name of one of the packages in `knope.toml`."),
+        url("https://knope.tech/reference/config-file/steps/write-release-notes/")
+    )]
+    UnknownPackage { name: String },
+}
@@ -1,31 +1,46 @@
 use indexmap::IndexMap;
 use knope_versioning::Version;
 use miette::Diagnostic;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     integrations::git::branch_name_from_issue,
     state,
     state::State,
-    step::releases::{package, semver, Package, Release},
-    workflow::Verbose,
+    step::{
+        issues::Issue,
+        releases::{package, semver, Package, Release},
+    },
 };
 
 /// Describes a value that can replace an arbitrary string in certain steps.
 ///
 /// <https://knope.tech/reference/config-file/variables//>
-#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, JsonSchema, Serialize)]
 pub(crate) enum Variable {
     /// The version of the package, if only a single package is configured (error if multiple).
     Version,
     /// The generated branch name for the selected issue. Note that this means the workflow must
     /// already be in [`State::IssueSelected`] when this variable is used.
     IssueBranch,
+    /// The key of the selected issue, like `123` for GitHub or `PROJ-123` for Jira.
+    IssueKey,
+    /// The title (summary) of the selected issue.
+    IssueTitle,
+    /// The URL to view the selected issue in a browser.
+    IssueUrl,
     /// Get the current changelog entry from the latest release.
     ChangelogEntry,
+    /// The comma-separated names of the packages released by `PrepareRelease` in this workflow.
+    ReleasedPackages,
+    /// The branch created by `CreateReleaseBranch` in this workflow.
+    ReleaseBranch,
+    /// The number of the GitHub milestone created by `CreateMilestone` in this workflow.
+    Milestone,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, JsonSchema, Serialize)]
 /// A template string and the variables that should be replaced in it.
 pub(crate) struct Template {
     pub(crate) template: String,
@@ -53,7 +68,7 @@ pub(crate) fn replace_variables(template: Template, state: &State) -> Result<Str
                         first_package(state)?
                     };
                     package_cache = Some(package);
-                    latest_version(state.verbose, package, &state.all_git_tags)?
+                    latest_version(state, package)?
                 };
                 template = template.replace(&var_name, &version.to_string());
                 version_cache = Some(version);
@@ -68,11 +83,9 @@ pub(crate) fn replace_variables(template: Template, state: &State) -> Result<Str
                 let version = if let Some(version) = version_cache.take() {
                     version
                 } else {
-                    latest_version(state.verbose, package, state.all_git_tags.as_ref())?
+                    latest_version(state, package)?
                 };
-                let changelog_entry = package
-                    .prepared_release
-                    .as_ref()
+                let changelog_entry = prepared_release(state, package)
                     .and_then(Release::body)
                     .map_or_else(
                         || {
@@ -97,32 +110,73 @@ pub(crate) fn replace_variables(template: Template, state: &State) -> Result<Str
                 template = template.replace(&var_name, &changelog_entry);
                 version_cache = Some(version);
             }
-            Variable::IssueBranch => match &state.issue {
-                state::Issue::Initial => return Err(Error::NoIssueSelected),
-                state::Issue::Selected(issue) => {
-                    template = template.replace(&var_name, &branch_name_from_issue(issue));
-                }
-            },
+            Variable::ReleasedPackages => {
+                template = template.replace(&var_name, &state.released_packages.join(","));
+            }
+            Variable::ReleaseBranch => {
+                let branch = state
+                    .release_branch
+                    .as_ref()
+                    .ok_or(Error::NoReleaseBranch)?;
+                template = template.replace(&var_name, branch);
+            }
+            Variable::Milestone => {
+                let milestone = state.milestone.ok_or(Error::NoMilestone)?;
+                template = template.replace(&var_name, &milestone.to_string());
+            }
+            Variable::IssueBranch => {
+                // Prefixes are part of the `SwitchBranches` step, so aren't known here
+                let branch_name = branch_name_from_issue(selected_issue(state)?, &IndexMap::new());
+                template = template.replace(&var_name, &branch_name);
+            }
+            Variable::IssueKey => {
+                template = template.replace(&var_name, &selected_issue(state)?.key);
+            }
+            Variable::IssueTitle => {
+                template = template.replace(&var_name, &selected_issue(state)?.summary);
+            }
+            Variable::IssueUrl => {
+                let url = selected_issue(state)?
+                    .url
+                    .as_ref()
+                    .ok_or(Error::NoIssueUrl)?;
+                template = template.replace(&var_name, url);
+            }
         }
     }
     Ok(template)
 }
 
-fn latest_version(
-    verbose: Verbose,
-    package: &Package,
-    git_tags: &[String],
-) -> Result<Version, Error> {
-    Ok(if let Some(release) = package.prepared_release.as_ref() {
+fn latest_version(state: &State, package: &Package) -> Result<Version, Error> {
+    Ok(if let Some(release) = prepared_release(state, package) {
         release.version.clone()
     } else {
         package
-            .get_version(verbose, git_tags)
+            .get_version(state.verbose, &state.all_git_tags)
             .into_latest()
             .ok_or(Error::NoCurrentVersion)?
     })
 }
 
+/// The release prepared for `package`, even if a `Release` step already released it.
+fn prepared_release<'a>(state: &'a State, package: &'a Package) -> Option<&'a Release> {
+    package.prepared_release.as_ref().or_else(|| {
+        state
+            .released
+            .iter()
+            .find(|released| released.name == package.name)?
+            .prepared_release
+            .as_ref()
+    })
+}
+
+fn selected_issue(state: &State) -> Result<&Issue, Error> {
+    match &state.issue {
+        state::Issue::Initial => Err(Error::NoIssueSelected),
+        state::Issue::Selected(issue) => Ok(issue),
+    }
+}
+
 fn first_package(state: &State) -> Result<&Package, Error> {
     if state.packages.len() > 1 {
         Err(Error::TooManyPackages)
@@ -159,9 +213,32 @@ pub(crate) enum Error {
     #[error("No issue selected")]
     #[diagnostic(
         code(variables::no_issue_selected),
-        help("The IssueBranch command variable requires selecting an issue first with SelectGitHubIssue or SelectJiraIssue")
+        help("Issue variables (like IssueBranch) require selecting an issue first with SelectGitHubIssue, SelectGiteaIssue, or SelectJiraIssue")
     )]
     NoIssueSelected,
+    #[error("The selected issue has no URL")]
+    #[diagnostic(
+        code(variables::no_issue_url),
+        help("Issues selected with SelectIssueFromBranch only know their key and title, select the issue with SelectGitHubIssue, SelectGiteaIssue, or SelectJiraIssue to use IssueUrl")
+    )]
+    NoIssueUrl,
+    #[error("No release branch created")]
+    #[diagnostic(
+        code(variables::no_release_branch),
+        help(
+            "The ReleaseBranch variable requires creating a branch first with CreateReleaseBranch"
+        )
+    )]
+    NoReleaseBranch,
+    #[error("No milestone created")]
+    #[diagnostic(
+        code(variables::no_milestone),
+        help(
+            "The Milestone variable requires creating a milestone first with CreateMilestone, \
+            which doesn't happen in a dry run"
+        )
+    )]
+    NoMilestone,
     #[error(transparent)]
     #[diagnostic(transparent)]
     SemVer(#[from] semver::Error),
@@ -181,12 +258,13 @@ mod test_replace_variables {
     use tempfile::TempDir;
 
     use super::*;
-    use crate::step::{
-        issues::Issue,
-        releases::{
-            changelog::HeaderLevel, conventional_commits::ConventionalCommit,
-            package::ChangelogSections, Change, ChangeType,
+    use crate::{
+        config::Git,
+        step::releases::{
+            changelog, changelog::HeaderLevel, conventional_commits::ConventionalCommit,
+            package::ChangelogSections, Change, ChangeType, DEFAULT_CHANGE_DIRECTORY,
         },
+        workflow::Verbose,
     };
 
     fn package() -> (Package, TempDir) {
@@ -215,7 +293,15 @@ mod test_replace_variables {
         let template = "blah $$ other blah".to_string();
         let mut variables = IndexMap::new();
         variables.insert("$$".to_string(), Variable::Version);
-        let mut state = State::new(None, None, None, vec![package().0], Vec::new(), Verbose::No);
+        let mut state = State::new(
+            None,
+            None,
+            None,
+            Git::default(),
+            vec![package().0],
+            Vec::new(),
+            Verbose::No,
+        );
         let version = Version::new(1, 2, 3, None);
         state.packages[0].prepared_release = Some(Release::empty(version.clone(), Vec::new()));
 
@@ -231,6 +317,63 @@ mod test_replace_variables {
         assert_eq!(result, format!("blah {version} other blah"));
     }
 
+    #[test]
+    fn replace_released_packages() {
+        let template = "publish $$".to_string();
+        let mut variables = IndexMap::new();
+        variables.insert("$$".to_string(), Variable::ReleasedPackages);
+        let mut state = State::new(
+            None,
+            None,
+            None,
+            Git::default(),
+            Vec::new(),
+            Vec::new(),
+            Verbose::No,
+        );
+        state.released_packages = vec!["first".to_string(), "second".to_string()];
+
+        let result = replace_variables(
+            Template {
+                template,
+                variables,
+            },
+            &state,
+        )
+        .unwrap();
+
+        assert_eq!(result, "publish first,second");
+    }
+
+    #[test]
+    fn replace_release_branch() {
+        let template = "git push -u origin $$".to_string();
+        let mut variables = IndexMap::new();
+        variables.insert("$$".to_string(), Variable::ReleaseBranch);
+        let mut state = State::new(
+            None,
+            None,
+            None,
+            Git::default(),
+            Vec::new(),
+            Vec::new(),
+            Verbose::No,
+        );
+        let template = Template {
+            template,
+            variables,
+        };
+        assert!(matches!(
+            replace_variables(template.clone(), &state),
+            Err(Error::NoReleaseBranch)
+        ));
+
+        state.release_branch = Some("release/1.2.0".to_string());
+        let result = replace_variables(template, &state).unwrap();
+
+        assert_eq!(result, "git push -u origin release/1.2.0");
+    }
+
     #[test]
     fn replace_issue_branch() {
         let template = "blah $$ other blah".to_string();
@@ -239,18 +382,27 @@ mod test_replace_variables {
         let issue = Issue {
             key: "13".to_string(),
             summary: "1234".to_string(),
+            labels: Vec::new(),
+            url: None,
         };
-        let expected_branch_name = branch_name_from_issue(&issue);
+        let expected_branch_name = branch_name_from_issue(&issue, &IndexMap::new());
         let state = State {
             jira_config: None,
             github: state::GitHub::New,
             github_config: None,
             gitea: state::Gitea::New,
             gitea_config: None,
+            git_config: Git::default(),
             issue: state::Issue::Selected(issue),
             packages: Vec::new(),
             all_git_tags: Vec::new(),
             verbose: Verbose::No,
+            released_packages: Vec::new(),
+            released: Vec::new(),
+            release_branch: None,
+            milestone: None,
+            output_file: None,
+            change_directory: DEFAULT_CHANGE_DIRECTORY.into(),
         };
 
         let result = replace_variables(
@@ -265,12 +417,66 @@ mod test_replace_variables {
         assert_eq!(result, format!("blah {expected_branch_name} other blah"));
     }
 
+    #[test]
+    fn replace_issue_details() {
+        let mut variables = IndexMap::new();
+        variables.insert("$key".to_string(), Variable::IssueKey);
+        variables.insert("$title".to_string(), Variable::IssueTitle);
+        variables.insert("$url".to_string(), Variable::IssueUrl);
+        let template = Template {
+            template: "gh pr create --title '$key: $title' --body '$url'".to_string(),
+            variables,
+        };
+        let mut state = State::new(
+            None,
+            None,
+            None,
+            Git::default(),
+            Vec::new(),
+            Vec::new(),
+            Verbose::No,
+        );
+        assert!(matches!(
+            replace_variables(template.clone(), &state),
+            Err(Error::NoIssueSelected)
+        ));
+
+        let mut issue = Issue {
+            key: "13".to_string(),
+            summary: "Fix the thing".to_string(),
+            labels: Vec::new(),
+            url: None,
+        };
+        state.issue = state::Issue::Selected(issue.clone());
+        assert!(matches!(
+            replace_variables(template.clone(), &state),
+            Err(Error::NoIssueUrl)
+        ));
+
+        issue.url = Some("https://github.com/knope-dev/knope/issues/13".to_string());
+        state.issue = state::Issue::Selected(issue);
+        let result = replace_variables(template, &state).unwrap();
+
+        assert_eq!(
+            result,
+            "gh pr create --title '13: Fix the thing' --body 'https://github.com/knope-dev/knope/issues/13'"
+        );
+    }
+
     #[test]
     fn replace_changelog_entry_prepared_release() {
         let template = "blah $$ other blah".to_string();
         let mut variables = IndexMap::new();
         variables.insert("$$".to_string(), Variable::ChangelogEntry);
-        let mut state = State::new(None, None, None, vec![package().0], Vec::new(), Verbose::No);
+        let mut state = State::new(
+            None,
+            None,
+            None,
+            Git::default(),
+            vec![package().0],
+            Vec::new(),
+            Verbose::No,
+        );
         let version = Version::new(1, 2, 3, None);
         let changes = [Change::ConventionalCommit(ConventionalCommit {
             change_type: ChangeType::Feature,
@@ -282,7 +488,9 @@ mod test_replace_variables {
             version.clone(),
             &changes,
             &changelog_sections,
+            changelog::Sort::default(),
             HeaderLevel::H2,
+            None,
             Vec::new(),
         ));
 
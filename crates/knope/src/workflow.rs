@@ -1,14 +1,26 @@
-use std::{fmt::Debug, io::sink};
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    io::{sink, Write},
+    rc::Rc,
+};
 
 use itertools::Itertools;
 use miette::Diagnostic;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::{state::RunType, step, step::Step, State};
+use crate::{
+    logging,
+    state::RunType,
+    step,
+    step::{releases, Step},
+    State,
+};
 
 /// A workflow is basically the state machine to run for a single execution of knope.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
 pub(crate) struct Workflow {
     /// The display name of this Workflow. This is what you'll see when you go to select it.
     pub(crate) name: String,
@@ -25,6 +37,27 @@ impl Workflow {
             step.set_prerelease_label(prerelease_label);
         }
     }
+
+    /// Set `keep_change_files` for any steps that are `PrepareRelease` steps.
+    pub(crate) fn set_keep_change_files(&mut self) {
+        for step in &mut self.steps {
+            step.set_keep_change_files();
+        }
+    }
+
+    /// Set `since_date` for any steps that are `PrepareRelease` steps.
+    pub(crate) fn set_since_date(&mut self, since_date: &str) {
+        for step in &mut self.steps {
+            step.set_since_date(since_date);
+        }
+    }
+
+    /// Set the arguments of any steps that are `CreateChangeFile` steps.
+    pub(crate) fn set_change_file_args(&mut self, args: &releases::ChangeFileArgs) {
+        for step in &mut self.steps {
+            step.set_change_file_args(args);
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -60,12 +93,24 @@ pub struct Error {
     inner: Box<[step::Error; 1]>,
 }
 
+impl Error {
+    /// Whether this workflow stopped because `PrepareRelease` found nothing to release.
+    pub(crate) fn is_no_release(&self) -> bool {
+        matches!(
+            self.inner.as_ref(),
+            [step::Error::Release(releases::Error::NoRelease)]
+        )
+    }
+}
+
 /// Run a series of [`Step`], each of which updates `state`.
 pub(crate) fn run(workflow: Workflow, mut state: RunType) -> Result<(), Error> {
     for step in workflow.steps {
+        logging::set_context(Some(&workflow.name), Some(step.name()));
         state = match step.run(state) {
             Ok(state) => state,
             Err(err) => {
+                logging::set_context(None, None);
                 return Err(Error {
                     name: workflow.name,
                     inner: Box::new([err]),
@@ -73,9 +118,115 @@ pub(crate) fn run(workflow: Workflow, mut state: RunType) -> Result<(), Error> {
             }
         };
     }
+    logging::set_context(None, None);
+    Ok(())
+}
+
+/// Dry-run output which can be read back after each step, for [`plan`].
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> String {
+        String::from_utf8_lossy(&self.0.take()).into_owned()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Dry-run every step of `workflow`, adding what each one would do to `output` as a numbered list,
+/// followed by a summary of the packages that would be released and the changes why.
+///
+/// If a step fails, `output` has the plan up to that step.
+pub(crate) fn plan(workflow: Workflow, state: State, output: &mut String) -> Result<(), Error> {
+    let buffer = SharedBuffer::default();
+    let mut state = state;
+    output.push_str(&format!("Plan for workflow {}:\n", workflow.name));
+    for (index, step) in workflow.steps.into_iter().enumerate() {
+        let step_name = step.name();
+        logging::set_context(Some(&workflow.name), Some(step_name));
+        let result = step.run(RunType::DryRun {
+            state,
+            stdout: Box::new(buffer.clone()),
+        });
+        logging::set_context(None, None);
+        let step_output = buffer.take();
+        let step_output = step_output.trim_end();
+        let details = if step_output.is_empty() {
+            "    Nothing to report".to_string()
+        } else {
+            indent(step_output)
+        };
+        output.push_str(&format!("\n{}. {step_name}\n{details}\n", index + 1));
+        state = match result {
+            Ok(run_type) => run_type.decompose().0,
+            Err(err) => {
+                return Err(Error {
+                    name: workflow.name,
+                    inner: Box::new([err]),
+                })
+            }
+        };
+    }
+    output.push_str(&release_summary(&state));
     Ok(())
 }
 
+fn release_summary(state: &State) -> String {
+    let released = state
+        .released
+        .iter()
+        .chain(&state.packages)
+        .filter_map(|package| {
+            package
+                .prepared_release
+                .as_ref()
+                .map(|release| (package, release))
+        })
+        .collect_vec();
+    if released.is_empty() {
+        return String::from("\nNo packages would be released\n");
+    }
+    let mut summary = String::from("\nPackages to release:\n");
+    for (package, release) in released {
+        let version = &release.version;
+        summary.push_str(&match &package.name {
+            Some(name) => format!("- {name} version {version}, because of:\n"),
+            None => format!("- Version {version}, because of:\n"),
+        });
+        for change in &package.pending_changes {
+            let source = match change {
+                releases::Change::ConventionalCommit(_) => "commit",
+                releases::Change::ChangeSet(_) => "change file",
+            };
+            summary.push_str(&indent(&format!("- {source} {change}")));
+            summary.push('\n');
+        }
+    }
+    summary
+}
+
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("    {line}")
+            }
+        })
+        .join("\n")
+}
+
 #[allow(clippy::needless_pass_by_value)] // Lifetime errors if State is passed by ref.
 pub(crate) fn validate(
     workflows: Vec<Workflow>,
@@ -0,0 +1,11 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// Commits made by `Command` steps keep the user's own Git identity, even when `[git]` sets one
+/// for Knope's commits.
+#[test]
+fn git_identity() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .env(&[("KNOPE_GIT_USER_EMAIL", "env@knope.tech")])
+        .run("release");
+}
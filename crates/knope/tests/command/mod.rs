@@ -1,3 +1,6 @@
+#[cfg(not(windows))]
+mod git_identity;
+mod released_packages;
 mod replace_version;
 #[cfg(not(windows))]
 mod shell;
@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// The `ReleasedPackages` variable contains only the packages that `PrepareRelease` released.
+#[test]
+fn released_packages() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("Initial commit"),
+            Tag("first/v1.2.3"),
+            Tag("second/v0.4.6"),
+            Tag("third/v1.2.3"),
+            Commit("feat(first): A feature"),
+            Commit("fix(third): A fix"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// `--config` loads workflows from that file instead of `knope.toml`.
+#[test]
+fn custom_path() {
+    TestCase::new(file!()).run("--config ci.toml --list");
+}
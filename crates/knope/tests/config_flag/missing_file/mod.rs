@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// A `--config` file that doesn't exist is an error, rather than falling back to the default config.
+#[test]
+fn missing_file() {
+    TestCase::new(file!()).run("--config missing.toml --list");
+}
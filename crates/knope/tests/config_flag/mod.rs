@@ -0,0 +1,2 @@
+mod custom_path;
+mod missing_file;
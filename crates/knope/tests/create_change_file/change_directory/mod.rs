@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// Change files are created in the configured `change_directory` instead of `.changeset`.
+#[test]
+fn change_directory() {
+    TestCase::new(file!()).run("document-change --change patch --summary Typo");
+}
@@ -0,0 +1,4 @@
+mod change_directory;
+mod multiple_packages;
+mod single_package;
+mod unknown_change_type;
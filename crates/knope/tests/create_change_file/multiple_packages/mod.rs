@@ -0,0 +1,8 @@
+use crate::helpers::TestCase;
+
+/// `--change` can be repeated to create one change file covering several packages, without prompting.
+#[test]
+fn multiple_packages() {
+    TestCase::new(file!())
+        .run("document-change --change first=major --change second=minor --summary Parallelize");
+}
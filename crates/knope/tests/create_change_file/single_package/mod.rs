@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// With only one package, `--change` doesn't need a package name.
+#[test]
+fn single_package() {
+    TestCase::new(file!()).run("document-change --change patch --summary Typo");
+}
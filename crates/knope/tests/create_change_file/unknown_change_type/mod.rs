@@ -0,0 +1,6 @@
+use crate::helpers::TestCase;
+
+#[test]
+fn unknown_change_type() {
+    TestCase::new(file!()).run("document-change --change first=huge --summary Parallelize");
+}
@@ -0,0 +1,2 @@
+mod branch_exists;
+mod new_branch;
@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// A workflow name takes precedence over `default_workflow`.
+#[test]
+fn explicit_workflow() {
+    TestCase::new(file!()).run("other");
+}
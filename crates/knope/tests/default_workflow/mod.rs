@@ -0,0 +1,3 @@
+mod explicit_workflow;
+mod runs_default;
+mod unknown_workflow;
@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// Without a workflow name, `default_workflow` runs.
+#[test]
+fn runs_default() {
+    TestCase::new(file!()).run("");
+}
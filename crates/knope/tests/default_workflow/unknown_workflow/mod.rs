@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// `default_workflow` must be the name of a workflow.
+#[test]
+fn unknown_workflow() {
+    TestCase::new(file!()).run("--validate");
+}
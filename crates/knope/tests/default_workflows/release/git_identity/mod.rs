@@ -0,0 +1,57 @@
+use std::{path::Path, process::Command};
+
+use crate::helpers::{
+    add_remote,
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// The release commit of the default workflow uses the identity from the environment, even
+/// though the repo's Git config has another one.
+#[test]
+fn git_identity() {
+    let test = TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing"),
+            Tag("v1.0.0"),
+            Commit("feat: New Feature"),
+        ])
+        .env(&[
+            ("KNOPE_GIT_USER_NAME", "Knope Bot"),
+            ("KNOPE_GIT_USER_EMAIL", "bot@knope.tech"),
+        ])
+        .expected_tags(&["v1.1.0"]);
+    let working_dir = test.arrange();
+    let remote = tempfile::tempdir().unwrap();
+    git(remote.path(), &["init", "--bare", "--initial-branch=main"]);
+    add_remote(working_dir.path(), remote.path().to_str().unwrap());
+    git(
+        working_dir.path(),
+        &["push", "--quiet", "--set-upstream", "origin", "main"],
+    );
+
+    test.assert(test.act(working_dir, "release"));
+
+    let identity = git(
+        remote.path(),
+        &["log", "-1", "--format=%an <%ae>, %cn <%ce>", "main"],
+    );
+    assert_eq!(
+        identity.trim(),
+        "Knope Bot <bot@knope.tech>, Knope Bot <bot@knope.tech>"
+    );
+}
+
+fn git(path: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
@@ -1,4 +1,5 @@
 mod cargo_workspace;
+mod git_identity;
 mod gitea;
 mod github;
 mod help_multi_package;
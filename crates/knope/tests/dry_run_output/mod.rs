@@ -0,0 +1,10 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// `--dry-run-output` writes what would happen to a file instead of stdout, without changing
+/// anything else.
+#[test]
+fn dry_run_output() {
+    TestCase::new(file!())
+        .git(&[Commit("feat: A new feature")])
+        .run("release --dry-run --dry-run-output plan.txt");
+}
@@ -0,0 +1,9 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// A dry run shows the fetch, but the real run fails because there's no `origin` remote.
+#[test]
+fn fetch_tags() {
+    TestCase::new(file!())
+        .git(&[Commit("feat: A new feature")])
+        .run("release");
+}
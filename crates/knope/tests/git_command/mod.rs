@@ -0,0 +1,4 @@
+#[cfg(not(windows))]
+mod remote_auth;
+mod remote_not_supported;
+mod tag;
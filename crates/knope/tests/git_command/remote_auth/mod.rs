@@ -0,0 +1,15 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// A `push` which can't authenticate to the remote explains how to set up credentials, like
+/// `FetchTags`.
+#[test]
+fn remote_auth() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial")])
+        .with_remote("ssh://git@localhost/knope.git")
+        .env(&[(
+            "GIT_SSH_COMMAND",
+            "echo 'git@localhost: Permission denied (publickey).' >&2; false",
+        )])
+        .run("push");
+}
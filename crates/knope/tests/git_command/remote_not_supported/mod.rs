@@ -0,0 +1,9 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// Only `fetch` and `push` take a `remote`.
+#[test]
+fn remote_not_supported() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial")])
+        .run("switch");
+}
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// By default, releasing a version whose tag already exists is an error.
+#[test]
+fn existing_tag_fails() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Tag("v1.1.0"),
+        ])
+        .run("release --override-version=1.1.0");
+}
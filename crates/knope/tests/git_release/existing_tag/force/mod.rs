@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `existing_tag = "force"`, an existing tag is moved to the release commit.
+#[test]
+fn existing_tag_force() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Tag("v1.1.0"),
+        ])
+        .expected_tags(&["v1.1.0"])
+        .run("release --override-version=1.1.0");
+}
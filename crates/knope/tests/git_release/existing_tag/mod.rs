@@ -0,0 +1,5 @@
+mod fail;
+#[cfg(not(windows))]
+mod force;
+#[cfg(not(windows))]
+mod skip;
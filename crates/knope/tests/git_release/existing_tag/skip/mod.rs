@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `existing_tag = "skip"`, an existing tag is left where it is.
+#[test]
+fn existing_tag_skip() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Tag("v1.1.0"),
+        ])
+        .expected_tags(&[])
+        .run("release --override-version=1.1.0");
+}
@@ -0,0 +1,21 @@
+use crate::helpers::{
+    GitCommand::{Commit, Note},
+    TestCase,
+};
+
+/// With `notes_ref` set, the previous version comes from a Git note instead of a tag, and the
+/// new release is recorded in a note instead of a tag.
+#[test]
+fn git_note() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Note {
+                notes_ref: "knope",
+                message: r#"{"releases": [{"version": "1.0.0", "tag": "v1.0.0"}]}"#,
+            },
+            Commit("feat: New feature"),
+        ])
+        .expected_tags(&[])
+        .run("release");
+}
@@ -1,2 +1,7 @@
+mod existing_tag;
+#[cfg(not(windows))]
+mod git_identity;
+mod git_note;
 mod multiple_packages;
 mod single_package;
+mod twice;
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Gitea can't move tags, so `existing_tag = "force"` is an error when the tag exists.
+#[test]
+fn existing_tag_force() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Tag("v1.1.0"),
+        ])
+        .run("release --override-version=1.1.0 --dry-run");
+}
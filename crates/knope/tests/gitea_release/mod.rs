@@ -1,4 +1,5 @@
 mod auto_generate_release_notes;
+mod existing_tag_force;
 mod multiple_packages;
 mod no_previous_tag;
 mod release_assets;
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `existing_tag = "skip"`, a GitHub release is only created if there isn't one for the tag.
+#[test]
+fn existing_tag() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Tag("v1.1.0"),
+        ])
+        .run("release --override-version=1.1.0 --dry-run");
+}
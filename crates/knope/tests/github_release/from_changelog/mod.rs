@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `from_changelog`, the latest version in a hand-written changelog is released with its notes.
+#[test]
+fn from_changelog() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("Initial commit"),
+            Tag("v1.1.0"),
+            Commit("Write the changelog for 1.2.0"),
+        ])
+        .run("release --dry-run");
+}
@@ -0,0 +1,9 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// With `from_changelog`, a changelog without any versions is an error.
+#[test]
+fn from_changelog_no_release() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .run("release --dry-run");
+}
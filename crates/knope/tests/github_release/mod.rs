@@ -1,6 +1,15 @@
 mod auto_generate_release_notes;
+mod create_discussion;
+mod create_milestone;
+mod existing_tag;
+mod from_changelog;
+mod from_changelog_no_release;
 mod multiple_packages;
+mod no_prepared_milestone;
 mod no_previous_tag;
 mod release_assets;
 mod simple;
+mod summarized_release_notes;
+mod update_release;
+mod update_release_no_changelog_entry;
 mod version_go_mod;
@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// `CreateMilestone` needs the version from `PrepareRelease`.
+#[test]
+fn no_prepared_milestone() {
+    TestCase::new(file!()).run("milestone --dry-run");
+}
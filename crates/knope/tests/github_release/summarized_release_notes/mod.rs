@@ -0,0 +1,22 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `release_notes`, the GitHub release only has some sections and is cut short with a link
+/// to the changelog, which still gets everything.
+#[test]
+fn summarized_release_notes() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat!: A breaking change"),
+            Commit("feat: A new feature"),
+            Commit("feat: Another new feature"),
+            Commit("feat: A third new feature"),
+            Commit("feat: A fourth new feature"),
+            Commit("fix: A fix which is left out"),
+        ])
+        .run("release --dry-run"); // Cannot run real release without integration testing GitHub.
+}
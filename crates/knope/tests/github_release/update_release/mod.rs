@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// Replace the notes of the release for the current version with the changelog's, publishing it.
+#[test]
+fn update_release() {
+    TestCase::new(file!()).run("update-release --dry-run"); // Cannot update a real release without integration testing GitHub.
+}
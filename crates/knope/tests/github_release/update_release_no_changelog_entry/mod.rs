@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// It's an error if the changelog doesn't have the version whose release is being updated.
+#[test]
+fn update_release_no_changelog_entry() {
+    TestCase::new(file!()).run("update-release --dry-run");
+}
@@ -77,6 +77,26 @@ pub fn commit(path: &Path, message: &str) {
     );
 }
 
+/// Create a commit with `message`, authored and committed on `date`, in the Git repo which exists
+/// in `path`.
+pub fn commit_on(path: &Path, date: &str, message: &str) {
+    let output = Command::new("git")
+        .arg("commit")
+        .arg("--allow-empty")
+        .arg("-m")
+        .arg(message)
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_DATE", date)
+        .current_dir(path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 /// Create a tag with `label` in the Git repo which exists in `path`.
 pub fn tag(path: &Path, label: &str) {
     let output = Command::new("git")
@@ -92,6 +112,25 @@ pub fn tag(path: &Path, label: &str) {
     );
 }
 
+/// Add a note with `message` to `HEAD` in `notes_ref` of the Git repo which exists in `path`.
+pub fn note(path: &Path, notes_ref: &str, message: &str) {
+    let output = Command::new("git")
+        .arg("notes")
+        .arg("--ref")
+        .arg(notes_ref)
+        .arg("add")
+        .arg("-m")
+        .arg(message)
+        .current_dir(path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 /// Create and switch to a new branch
 pub fn create_branch(path: &Path, name: &str) {
     let output = Command::new("git")
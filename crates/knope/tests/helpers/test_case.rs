@@ -9,14 +9,18 @@ use snapbox::{
 };
 use tempfile::TempDir;
 
-use crate::helpers::{add_remote, assert, commit, copy_dir_contents, get_tags, init, tag};
+use crate::helpers::{
+    add_all, add_remote, assert, commit, commit_on, copy_dir_contents, get_tags, init, note, tag,
+};
 
 pub struct TestCase {
     file_name: &'static str,
     git: &'static [GitCommand],
-    env: Option<(&'static str, &'static str)>,
+    env: &'static [(&'static str, &'static str)],
     remote: Option<&'static str>,
     expected_tags: Option<&'static [&'static str]>,
+    exit_code: Option<i32>,
+    subdirectory: Option<&'static str>,
 }
 
 impl TestCase {
@@ -24,10 +28,12 @@ impl TestCase {
     pub const fn new(file_name: &'static str) -> Self {
         Self {
             file_name,
-            env: None,
+            env: &[],
             git: &[],
             remote: None,
             expected_tags: None,
+            exit_code: None,
+            subdirectory: None,
         }
     }
 
@@ -36,8 +42,10 @@ impl TestCase {
             file_name: self.file_name,
             remote: self.remote,
             git: commands,
-            env: None,
+            env: self.env,
             expected_tags: self.expected_tags,
+            exit_code: self.exit_code,
+            subdirectory: self.subdirectory,
         }
     }
 
@@ -51,6 +59,18 @@ impl TestCase {
         self
     }
 
+    /// Expect the real (not dry-run) command to exit with `code` and print `stdout.log`.
+    pub fn exit_code(mut self, code: i32) -> Self {
+        self.exit_code = Some(code);
+        self
+    }
+
+    /// Run knope from this directory (relative to the root of `in`) instead of the root.
+    pub fn in_subdirectory(mut self, subdirectory: &'static str) -> Self {
+        self.subdirectory = Some(subdirectory);
+        self
+    }
+
     /// Set up a new temporary directory with the contents of the `in` directory (if any).
     /// Initialize a git repository and run the commands in `git`.
     pub fn arrange(&self) -> TempDir {
@@ -68,34 +88,46 @@ impl TestCase {
         }
         for command in self.git {
             match command {
+                GitCommand::AddAll => {
+                    add_all(path);
+                }
                 GitCommand::Commit(message) => {
                     commit(path, message);
                 }
+                GitCommand::CommitOn { date, message } => {
+                    commit_on(path, date, message);
+                }
                 GitCommand::Tag(name) => {
                     tag(path, name);
                 }
+                GitCommand::Note { notes_ref, message } => {
+                    note(path, notes_ref, message);
+                }
             }
         }
 
         working_dir
     }
 
-    /// Run `command` in `working_dir` with any `self.env` set.
+    /// Run `command` in `working_dir` with all of `self.env` set.
     pub fn act(&self, working_dir: TempDir, command: &str) -> Asserts {
         let data_path = self.data_path();
         let parts = command.split_whitespace().collect::<Vec<_>>();
+        let current_dir = working_dir
+            .path()
+            .join(self.subdirectory.unwrap_or_default());
         let mut real = Command::new(cargo_bin!("knope"))
-            .current_dir(working_dir.path())
+            .current_dir(&current_dir)
             .with_assert(assert(true));
         let mut dry_run = Command::new(cargo_bin!("knope"))
-            .current_dir(working_dir.path())
+            .current_dir(&current_dir)
             .with_assert(assert(true));
 
         for arg in parts {
             real = real.arg(arg);
             dry_run = dry_run.arg(arg);
         }
-        if let Some((key, value)) = self.env {
+        for (key, value) in self.env {
             real = real.env(key, value);
             dry_run = dry_run.env(key, value);
         }
@@ -138,7 +170,11 @@ impl TestCase {
         }
 
         let stderr_file = data_path.join("stderr.log");
-        if stderr_file.exists() {
+        if let Some(code) = self.exit_code {
+            let stdout_file = data_path.join("stdout.log");
+            real.code(code)
+                .stdout_matches(Data::read_from(&stdout_file, None));
+        } else if stderr_file.exists() {
             real.failure()
                 .stderr_matches(Data::read_from(&stderr_file, None));
         } else {
@@ -181,13 +217,16 @@ impl TestCase {
         self.assert(self.act(self.arrange(), command));
     }
 
-    pub fn env(self, key: &'static str, value: &'static str) -> TestCase {
+    /// Set each environment variable (a key and value) in `env` for the command.
+    pub const fn env(self, env: &'static [(&'static str, &'static str)]) -> TestCase {
         TestCase {
             file_name: self.file_name,
             git: self.git,
             remote: self.remote,
-            env: Some((key, value)),
+            env,
             expected_tags: self.expected_tags,
+            exit_code: self.exit_code,
+            subdirectory: self.subdirectory,
         }
     }
 
@@ -211,6 +250,18 @@ pub struct Asserts {
 
 #[derive(Clone, Copy, Debug)]
 pub enum GitCommand {
+    /// Stage every file (like the contents of `in`) for the next commit
+    AddAll,
     Commit(&'static str),
+    /// Commit with an author and commit date of `date` (like `2024-01-31T12:00:00Z`)
+    CommitOn {
+        date: &'static str,
+        message: &'static str,
+    },
     Tag(&'static str),
+    /// Add a Git note to `HEAD`
+    Note {
+        notes_ref: &'static str,
+        message: &'static str,
+    },
 }
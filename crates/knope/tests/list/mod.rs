@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// `--list` prints every workflow, along with its help text if it has any.
+#[test]
+fn list_workflows() {
+    TestCase::new(file!()).run("--list");
+}
@@ -1,14 +1,36 @@
 #![allow(clippy::unwrap_used)]
 mod bump_version;
 mod command;
+mod config_flag;
+mod create_change_file;
+mod create_release_branch;
+mod default_workflow;
 mod default_workflows;
+mod dry_run_output;
+mod fetch_tags;
 mod generate;
+mod git_command;
 mod git_release;
 mod gitea_release;
 mod github_release;
 mod helpers;
+mod list;
 mod multi_forge_release;
 mod no_config;
+mod plan;
 mod prepare_release;
+mod preview_changelog;
+mod print_schema;
+mod require_change_file;
+mod rollback;
+mod sync_downstream;
+mod sync_versions;
+mod tag;
 mod upgrade;
 mod validate;
+mod verify_branch;
+mod verify_changelog_entry;
+mod verify_registry_version;
+mod wait_for_checks;
+mod webhook;
+mod write_release_notes;
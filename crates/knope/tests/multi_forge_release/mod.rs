@@ -1,6 +1,8 @@
 mod auto_generate_release_notes;
+mod forge_not_configured;
 mod multiple_packages;
 mod no_previous_tag;
+mod only_one_forge;
 mod release_assets;
 mod simple;
 mod version_go_mod;
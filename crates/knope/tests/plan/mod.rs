@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// `--plan` prints what every step would do, then which packages would be released and why,
+/// without changing anything.
+#[test]
+fn plan() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: A new feature"),
+            Commit("fix: A bug fix"),
+        ])
+        .run("release --plan");
+}
@@ -0,0 +1,8 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+#[test]
+fn invalid() {
+    TestCase::new(file!())
+        .git(&[Commit("feat: New feature")])
+        .run("release");
+}
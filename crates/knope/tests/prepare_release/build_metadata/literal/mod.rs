@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Build metadata is added to the versioned files and the tag, but not the changelog. Existing
+/// build metadata is ignored when finding the previous version.
+#[test]
+fn literal() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0+build.1"),
+            Commit("feat: New feature"),
+        ])
+        .expected_tags(&["v1.1.0+exp.5"])
+        .run("release");
+}
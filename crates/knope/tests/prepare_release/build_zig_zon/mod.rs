@@ -0,0 +1,2 @@
+mod bump;
+mod missing_version;
@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Every released package with `update_workspace_dependencies` has its entry in the root
+/// `Cargo.toml` updated.
+#[test]
+fn update_workspace_dependencies() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("Initial commit"),
+            Tag("first-package/v1.0.0"),
+            Tag("second-package/v0.1.0"),
+            Commit("feat(first-package): A feature"),
+            Commit("feat(second-package)!: A breaking feature"),
+        ])
+        .run("release");
+}
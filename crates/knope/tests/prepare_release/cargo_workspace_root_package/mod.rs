@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// When the root `Cargo.toml` is also a versioned file, its `[workspace.dependencies]` are updated
+/// on top of its new version instead of replacing it.
+#[test]
+fn versioned_root_cargo_toml() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("Initial commit"),
+            Tag("first-package/v1.0.0"),
+            Tag("root-package/v2.0.0"),
+            Commit("feat(first-package): A feature"),
+            Commit("fix(root-package): A fix"),
+        ])
+        .run("release");
+}
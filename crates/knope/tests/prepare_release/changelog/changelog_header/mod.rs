@@ -0,0 +1,2 @@
+mod existing_changelog;
+mod new_changelog;
@@ -0,0 +1,12 @@
+use super::arrange;
+use crate::helpers::TestCase;
+
+/// Commits are listed in the order the history is walked, so the branch's commit comes first.
+#[test]
+fn as_is() {
+    let test = TestCase::new(file!());
+    let temp_dir = test.arrange();
+    arrange(temp_dir.path());
+
+    test.assert(test.act(temp_dir, "release"));
+}
@@ -0,0 +1,12 @@
+use super::arrange;
+use crate::helpers::TestCase;
+
+/// Commits are listed by commit time, unlike `as_is`, so the older commit on `main` comes first.
+#[test]
+fn chronological() {
+    let test = TestCase::new(file!());
+    let temp_dir = test.arrange();
+    arrange(temp_dir.path());
+
+    test.assert(test.act(temp_dir, "release"));
+}
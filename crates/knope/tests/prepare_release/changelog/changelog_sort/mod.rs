@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use crate::helpers::{commit_on, create_branch, merge_branch, switch_branch, tag};
+
+mod as_is;
+mod chronological;
+
+/// A merged branch whose commit is newer than the commit on `main`, so walking the history
+/// (which reaches `main` first) finds the commits in a different order than their commit times.
+fn arrange(path: &Path) {
+    commit_on(path, "2024-01-01T12:00:00Z", "feat: Initial release");
+    tag(path, "v1.0.0");
+    create_branch(path, "feature");
+    commit_on(
+        path,
+        "2024-01-03T12:00:00Z",
+        "feat: Newer feature on a branch",
+    );
+    switch_branch(path, "main");
+    commit_on(path, "2024-01-02T12:00:00Z", "feat: Older feature on main");
+    merge_branch(path, "feature");
+}
@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// New changelog entries are wrapped at `changelog_wrap` columns, but existing ones are left alone.
+#[test]
+fn changelog_wrap() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit(
+                "feat: A new feature with a description that is much too long to fit on one line",
+            ),
+            Commit("fix: A short fix"),
+        ])
+        .run("release");
+}
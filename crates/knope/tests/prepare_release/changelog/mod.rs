@@ -1,4 +1,10 @@
+mod changelog_anchor;
+mod changelog_header;
+mod changelog_sort;
+mod changelog_wrap;
 mod create_missing;
+mod existing_entry;
 mod extra_changelog_sections;
 mod header_level_detection;
+mod only_features;
 mod override_default_sections;
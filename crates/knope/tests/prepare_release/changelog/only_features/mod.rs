@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A release with only features has no headings for the other sections, since they'd be empty.
+#[test]
+fn only_features() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Commit("chore: Not in the changelog"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,12 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Change files are read from (and deleted in) the configured `change_directory`.
+#[test]
+fn change_directory() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit"), Tag("v1.2.3")])
+        .run("release");
+}
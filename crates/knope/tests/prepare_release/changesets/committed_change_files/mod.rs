@@ -0,0 +1,10 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// The change files consumed by `PrepareRelease` should be removed in the release commit.
+#[cfg(not(windows))]
+#[test]
+fn committed_change_files() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .run("release");
+}
@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `--keep-change-files`, change files are still released but not deleted.
+#[test]
+fn keep_change_files() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat!: Existing feature"),
+            Tag("v1.2.3"),
+            Commit("feat: A new shared feature from a conventional commit"),
+        ])
+        .run("release --keep-change-files");
+}
@@ -1,3 +1,7 @@
+mod change_directory;
+mod committed_change_files;
 mod happy;
 mod invalid_change_file;
+mod keep_change_files;
 mod prerelease;
+mod sorted_change_files;
@@ -0,0 +1,13 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Change files are always handled in order of their file names, no matter the order the file
+/// system lists them in.
+#[test]
+fn sorted_change_files() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit"), Tag("v1.2.3")])
+        .run("release");
+}
@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Commit types in `commit_type_aliases` are treated like the type they're an alias of.
+#[test]
+fn aliases() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.1.0"),
+            Commit("bug: Fix a bug"),
+            Commit("Feature: Add a feature"),
+            Commit("doc: Document the feature"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A `docs` commit is in the changelog but doesn't bump the version, a `perf` commit bumps the
+/// version without a changelog entry.
+#[test]
+fn changelog_without_bump() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.1.0"),
+            Commit("docs: Document the feature"),
+            Commit("perf: Make the feature faster"),
+            Commit("Docs: Document with a different case"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,3 @@
+mod aliases;
+mod changelog_without_bump;
+mod only_changes_without_bump;
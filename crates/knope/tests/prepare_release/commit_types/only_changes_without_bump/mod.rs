@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// If no change bumps the version, there is nothing to release.
+#[test]
+fn only_changes_without_bump() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.1.0"),
+            Commit("docs: Document the feature"),
+        ])
+        .run("release");
+}
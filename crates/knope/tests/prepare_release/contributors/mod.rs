@@ -0,0 +1,15 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// With `contributors`, the authors and co-authors of released commits are listed in their own
+/// section, except for the ignored ones.
+#[test]
+fn contributors() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: A new feature\n\nCo-authored-by: Jane Doe <jane@example.com>"),
+            Commit(
+                "fix: A new fix\n\nCo-authored-by: dependabot[bot] <support@github.com>\nCo-authored-by: jane doe <jane@example.com>",
+            ),
+        ])
+        .run("release");
+}
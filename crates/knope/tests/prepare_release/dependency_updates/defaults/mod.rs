@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `dependency_updates`, commits with the `deps` and `deps-dev` scopes get their own
+/// changelog section and bump the patch version, whatever their type.
+#[test]
+fn defaults() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.1.0"),
+            Commit("chore(deps): bump serde from 1.0.0 to 1.0.1"),
+            Commit("build(deps-dev): bump insta from 1.0.0 to 2.0.0"),
+            Commit("fix(deps): bump regex from 1.0.0 to 1.1.0"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,2 @@
+mod defaults;
+mod no_bump;
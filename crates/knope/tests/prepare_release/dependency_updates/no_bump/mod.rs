@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Dependency updates can be configured to not bump the version, so there's nothing to release
+/// if they're the only changes.
+#[test]
+fn no_bump() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.1.0"),
+            Commit("chore(deps): bump serde from 1.0.0 to 1.0.1"),
+        ])
+        .run("release");
+}
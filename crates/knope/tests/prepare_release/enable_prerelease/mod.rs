@@ -23,7 +23,7 @@ fn with_option() {
 #[test]
 fn with_env() {
     TEST_CASE
-        .env("KNOPE_PRERELEASE_LABEL", "rc")
+        .env(&[("KNOPE_PRERELEASE_LABEL", "rc")])
         .run("prerelease");
 }
 
@@ -35,6 +35,6 @@ fn with_env() {
 #[test]
 fn prerelease_label_option_overrides_env() {
     TEST_CASE
-        .env("KNOPE_PRERELEASE_LABEL", "alpha")
+        .env(&[("KNOPE_PRERELEASE_LABEL", "alpha")])
         .run("prerelease --prerelease-label=rc");
 }
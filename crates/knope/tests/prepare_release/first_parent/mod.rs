@@ -0,0 +1,20 @@
+use crate::helpers::{commit, create_branch, merge_branch, switch_branch, tag, TestCase};
+
+/// With `first_parent`, the commits on a merged branch are left out, only the commits made
+/// directly on the current branch count.
+#[test]
+fn first_parent() {
+    let test = TestCase::new(file!());
+    let temp_dir = test.arrange();
+    let temp_path = temp_dir.path();
+
+    commit(temp_path, "feat: Initial release");
+    tag(temp_path, "v1.0.0");
+    create_branch(temp_path, "feature");
+    commit(temp_path, "feat: Work in progress on a branch");
+    switch_branch(temp_path, "main");
+    commit(temp_path, "fix: A fix on main");
+    merge_branch(temp_path, "feature");
+
+    test.assert(test.act(temp_dir, "release"));
+}
@@ -0,0 +1,10 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// Running from a subdirectory uses the `knope.toml` in the project root, with paths relative to it.
+#[test]
+fn from_subdirectory() {
+    TestCase::new(file!())
+        .git(&[Commit("feat: A new feature")])
+        .in_subdirectory("src")
+        .run("release");
+}
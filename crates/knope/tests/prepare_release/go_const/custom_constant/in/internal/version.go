@@ -0,0 +1,6 @@
+package internal
+
+const (
+	AppName           = "app"
+	AppVersion string = "1.0.0"
+)
@@ -0,0 +1,8 @@
+package main
+
+// Version is set by Knope
+const Version = "1.0.0"
+
+func main() {
+	println("Version:", Version)
+}
@@ -0,0 +1,3 @@
+package main
+
+var Version = "1.0.0"
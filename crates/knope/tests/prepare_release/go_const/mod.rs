@@ -0,0 +1,3 @@
+mod custom_constant;
+mod default_constant;
+mod missing_constant;
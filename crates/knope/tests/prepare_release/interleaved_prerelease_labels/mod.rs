@@ -0,0 +1,24 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// The next `rc` is found from the last `rc` tag, even when `beta` tags were created after it.
+#[test]
+fn interleaved_prerelease_labels() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: First feature"),
+            Tag("v1.1.0-rc.0"),
+            Commit("feat: Second feature"),
+            Tag("v1.1.0-beta.0"),
+            Commit("feat: Third feature"),
+            Tag("v1.1.0-rc.1"),
+            Commit("feat: Fourth feature"),
+            Tag("v1.1.0-beta.1"),
+            Commit("feat: Fifth feature"),
+        ])
+        .run("release");
+}
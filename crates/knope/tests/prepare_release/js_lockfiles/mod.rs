@@ -0,0 +1,10 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// Releasing an npm package updates the version that `pnpm-lock.yaml` and `yarn.lock` record for it,
+/// leaving every other entry alone.
+#[test]
+fn js_lockfiles() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit"), Commit("feat: A feature")])
+        .run("release");
+}
@@ -0,0 +1,4 @@
+mod custom_variable;
+mod default_variable;
+mod missing_variable;
+mod variable_not_supported;
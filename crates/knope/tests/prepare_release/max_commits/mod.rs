@@ -0,0 +1,14 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// With `max_commits`, only that many commits are checked, even though there is no release tag to
+/// stop at.
+#[test]
+fn max_commits() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat!: Too old to be checked"),
+            Commit("feat: A new feature"),
+            Commit("fix: A new fix"),
+        ])
+        .run("release");
+}
@@ -1,27 +1,66 @@
 mod allow_empty;
 mod branching_history;
+mod build_metadata;
+mod build_zig_zon;
 mod cargo_workspace;
+mod cargo_workspace_dependencies;
+mod cargo_workspace_root_package;
 mod changelog;
 mod changesets;
+mod citation_cff;
+mod commit_types;
+mod contributors;
+mod dependency_updates;
 mod enable_prerelease;
+mod first_parent;
+mod from_subdirectory;
+mod go_const;
 mod go_modules;
 mod hande_pre_versions_that_are_too_new;
 mod ignore_conventional_commits;
 mod inconsistent_versions;
+mod interleaved_prerelease_labels;
 mod invalid_versioned_files;
+mod js_lockfiles;
+mod makefile;
+mod max_commits;
 mod missing_versioned_files;
+mod multi_line_footer;
 mod multiple_packages;
+mod nimble;
+mod no_changes_entry;
+mod no_release_exit_code;
 mod no_version_change;
 mod no_versioned_files;
+mod non_conventional_commits;
+mod output_file;
 mod override_prerelease_label;
 mod override_version;
 mod override_version_multiple_packages;
+mod package_filter;
+mod package_json_dependencies;
+mod package_order;
 mod package_selection;
+mod pattern;
+mod per_package_prerelease_label;
+mod pom_xml;
 mod prerelease_after_release;
+mod prerelease_format;
+mod prerelease_only;
+mod previous_version_source;
 mod pubspec_yaml;
 mod pyproject_toml;
 mod release_after_prerelease;
+mod release_commits;
+mod require_issue_reference;
 mod scopes;
 mod second_prerelease;
+mod separate_commits;
+mod since;
+mod strict_semver;
+mod strip_emoji;
+mod strip_prerelease;
 mod unknown_versioned_file_format;
 mod verbose;
+mod version_env_var;
+mod yaml;
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A `BREAKING CHANGE` footer which spans several lines and paragraphs is kept whole in the
+/// changelog, up to the next footer.
+#[test]
+fn multi_line_footer() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature\n\nBREAKING CHANGE: The config file moved, so the old one\nis no longer read.\n\nMove it to the new location before upgrading.\nRefs: #123"),
+        ])
+        .run("release");
+}
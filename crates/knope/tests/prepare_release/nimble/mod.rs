@@ -0,0 +1,2 @@
+mod bump;
+mod multiple_versions;
@@ -0,0 +1,12 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Forcing a release with no changes uses the configured `no_changes_entry` in the changelog
+#[test]
+fn no_changes_entry() {
+    TestCase::new(file!())
+        .git(&[Commit("feat: Existing feature"), Tag("v0.1.0")])
+        .run("release --override-version=0.1.1");
+}
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// When `--no-release-exit-code` is set, having nothing to release exits with that code instead of an error.
+#[test]
+fn test() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Old feat"),
+            Tag("v1.0.0"),
+            Commit("docs: Update README"),
+        ])
+        .exit_code(3)
+        .run("prepare-release --no-release-exit-code 3");
+}
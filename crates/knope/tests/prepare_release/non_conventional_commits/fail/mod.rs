@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `non_conventional_commits = "fail"`, every commit which isn't a conventional commit is listed in an error.
+#[test]
+fn fail() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Commit("Fixed the thing"),
+            Commit("WIP\n\nMore details"),
+        ])
+        .run("release");
+}
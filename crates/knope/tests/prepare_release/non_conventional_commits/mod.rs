@@ -0,0 +1,2 @@
+mod fail;
+mod patch;
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `non_conventional_commits = "patch"`, commits which aren't conventional commits are released as fixes.
+#[test]
+fn patch() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("Fixed the thing\n\nWith more details"),
+            Commit("chore: Not in the changelog"),
+        ])
+        .run("release");
+}
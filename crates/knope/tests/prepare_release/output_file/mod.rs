@@ -0,0 +1,2 @@
+mod multiple_packages;
+mod single_package;
@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Each released package gets a line named after it, and packages which aren't released are left
+/// out.
+#[test]
+fn multiple_packages() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("first/v1.0.0"),
+            Tag("second/v0.4.6"),
+            Commit("feat(first): New feature"),
+        ])
+        .run("release --output-file versions.txt");
+}
@@ -23,6 +23,6 @@ fn with_option() {
 #[test]
 fn with_env() {
     TEST_CASE
-        .env("KNOPE_PRERELEASE_LABEL", "alpha")
+        .env(&[("KNOPE_PRERELEASE_LABEL", "alpha")])
         .run("prerelease");
 }
@@ -0,0 +1,2 @@
+mod selected;
+mod unknown;
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// `--package` limits the whole workflow to one package, leaving the others alone.
+#[test]
+fn selected() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("first/v1.2.3"),
+            Tag("second/v0.4.6"),
+            Commit("feat!: New breaking feature"),
+        ])
+        .run("release --package second");
+}
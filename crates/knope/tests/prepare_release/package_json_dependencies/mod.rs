@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Releasing an npm package updates the ranges other packages' `package.json` files use to
+/// depend on it, keeping their formatting.
+#[test]
+fn package_json_dependencies() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("Initial commit"),
+            Tag("first/v1.0.0"),
+            Tag("second/v0.1.0"),
+            Commit("feat(first): A feature"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `package_order = "alphabetical"`, packages are released (and listed) sorted by name
+/// instead of in the order they're declared.
+#[test]
+fn alphabetical() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("Initial commit"),
+            Tag("zeta/v1.2.3"),
+            Tag("alpha/v0.4.6"),
+            Commit("feat(zeta): A feature"),
+            Commit("fix(alpha): A fix"),
+        ])
+        .run("release");
+}
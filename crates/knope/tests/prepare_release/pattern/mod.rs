@@ -0,0 +1,4 @@
+mod inconsistent_versions;
+mod no_match;
+mod readme_badge;
+mod search;
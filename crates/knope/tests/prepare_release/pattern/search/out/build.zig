@@ -0,0 +1,9 @@
+const std = @import("std");
+
+const version = "1.1.0";
+
+pub fn build(b: *std.Build) void {
+    const options = b.addOptions();
+    options.addOption([]const u8, "version", version);
+    _ = b.standardTargetOptions(.{});
+}
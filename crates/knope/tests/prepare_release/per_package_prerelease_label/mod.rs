@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A package's own `prerelease_label` takes precedence over the step's, while packages without
+/// one fall back to the step's label.
+#[test]
+fn per_package_prerelease_label() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("first/v1.2.3"),
+            Tag("second/v0.4.6"),
+            Commit("feat: New feature"),
+        ])
+        .run("prerelease");
+}
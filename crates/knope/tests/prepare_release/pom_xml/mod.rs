@@ -0,0 +1,2 @@
+mod bump;
+mod inherited_version;
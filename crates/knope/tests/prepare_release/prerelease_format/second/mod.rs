@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Pre-releases in the configured format are found and incremented.
+#[test]
+fn second() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature in first RC"),
+            Tag("v1.1.0-rc1"),
+            Commit("feat: New feature in second RC"),
+        ])
+        .run("prerelease");
+}
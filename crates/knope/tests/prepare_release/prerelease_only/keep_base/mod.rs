@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Only the pre-release number is bumped, even though a breaking change would bump the major version.
+#[test]
+fn keep_base() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.1.0"),
+            Commit("feat: New feature"),
+            Tag("v1.2.0-rc.3"),
+            Commit("feat!: Breaking change"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,2 @@
+mod keep_base;
+mod no_prerelease;
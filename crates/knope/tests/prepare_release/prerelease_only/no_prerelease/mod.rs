@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// There's no pre-release to bump when the latest version is stable.
+#[test]
+fn no_prerelease() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.2.0-rc.3"),
+            Commit("feat: New feature"),
+            Tag("v1.2.0"),
+            Commit("fix: A fix"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,2 @@
+mod github;
+mod not_configured;
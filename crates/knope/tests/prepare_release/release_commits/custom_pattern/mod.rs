@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `release_commit_pattern`, release commits are recognized by that pattern instead.
+#[test]
+fn custom_pattern() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Initial release"),
+            Tag("v1.0.0"),
+            Commit("chore: Release 1.0.0"),
+            Commit("chore: prepare release 1.0.0"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// The commit made by the previous release isn't part of the next one, even when commits of its
+/// type are.
+#[test]
+fn default() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Initial release"),
+            Tag("v1.0.0"),
+            Commit("chore: prepare release 1.0.0"),
+            Commit("chore: Update dependencies"),
+        ])
+        .run("release");
+}
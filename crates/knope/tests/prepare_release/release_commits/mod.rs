@@ -0,0 +1,2 @@
+mod custom_pattern;
+mod default;
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// The footers which reference an issue can be configured.
+#[test]
+fn custom_footers() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature\n\nJira: PROJ-123"),
+            Commit("fix: A bug\n\njira: PROJ-124"),
+        ])
+        .run("release");
+}
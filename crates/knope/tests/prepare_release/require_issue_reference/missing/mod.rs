@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Every feature and fix commit without an issue footer is listed in an error.
+#[test]
+fn missing() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: Referenced feature\n\nCloses #12"),
+            Commit("feat: Unreferenced feature"),
+            Commit("fix: Unreferenced fix"),
+            Commit("docs: Unreferenced docs"),
+        ])
+        .run("release");
+}
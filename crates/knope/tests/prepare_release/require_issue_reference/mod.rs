@@ -0,0 +1,3 @@
+mod custom_footers;
+mod missing;
+mod value_pattern;
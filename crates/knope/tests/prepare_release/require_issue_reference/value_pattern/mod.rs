@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A footer only references an issue if its value matches `value_pattern`.
+#[test]
+fn value_pattern() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: Issue number\n\nCloses #12"),
+            Commit("fix: Issue key\n\nRefs: PROJ-123"),
+            Commit("fix: Placeholder\n\nRefs: TODO"),
+        ])
+        .run("release");
+}
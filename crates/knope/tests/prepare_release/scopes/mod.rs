@@ -1,4 +1,6 @@
+mod multiple_scopes;
 mod no_scopes;
+mod scope_footer;
 mod shared_commits;
 mod skip_unchanged_packages;
 mod unscoped_commits;
@@ -0,0 +1,13 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// A commit with comma-separated scopes applies to every package with any of those scopes.
+#[test]
+fn multiple_scopes() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("fix(first): Fix for first only"),
+            Commit("feat(first,second): Shared feat"),
+            Commit("fix(second, other): Fix for second and an unknown scope"),
+        ])
+        .run("release");
+}
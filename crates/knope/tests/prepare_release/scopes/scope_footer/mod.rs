@@ -0,0 +1,14 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// A footer listing more packages (by scope or name) adds them to the packages a commit's scope
+/// applies to.
+#[test]
+fn scope_footer() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("fix(first): Fix for first only"),
+            Commit("feat(first): Feature which also affects second\n\nAffects: second"),
+            Commit("fix(other): Fix listing first by name\n\nAffects: first"),
+        ])
+        .run("release");
+}
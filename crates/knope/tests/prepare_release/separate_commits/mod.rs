@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `separate_commits`, the versioned files and the changelog are committed separately.
+#[test]
+fn separate_commits() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.1.0"),
+            Commit("feat: A new feature"),
+        ])
+        .run("release");
+}
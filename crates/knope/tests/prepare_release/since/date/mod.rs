@@ -0,0 +1,27 @@
+use crate::helpers::{
+    GitCommand::{CommitOn, Tag},
+    TestCase,
+};
+
+/// With `--since`, commits made before that date are left out, even if they're after the last
+/// release tag.
+#[test]
+fn date() {
+    TestCase::new(file!())
+        .git(&[
+            CommitOn {
+                date: "2024-01-01T12:00:00Z",
+                message: "Initial commit",
+            },
+            Tag("v1.0.0"),
+            CommitOn {
+                date: "2024-01-15T12:00:00Z",
+                message: "feat: Before the cutoff",
+            },
+            CommitOn {
+                date: "2024-02-01T12:00:00Z",
+                message: "fix: After the cutoff",
+            },
+        ])
+        .run("release --since 2024-02-01");
+}
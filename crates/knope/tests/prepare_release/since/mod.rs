@@ -0,0 +1,4 @@
+mod date;
+mod invalid;
+mod invalid_date;
+mod revision;
@@ -0,0 +1,19 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `since`, only commits after that revision are released, not every commit since the last
+/// release tag.
+#[test]
+fn revision() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("Initial commit"),
+            Tag("v1.0.0"),
+            Commit("feat: Before the cutoff"),
+            Tag("cutoff"),
+            Commit("fix: After the cutoff"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A breaking change to a `0.x` version bumps the minor component by default.
+#[test]
+fn initial_development() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.2.3"),
+            Commit("feat!: Breaking change"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,2 @@
+mod initial_development;
+mod strict;
@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `strict_semver`, a breaking change to a `0.x` version releases `1.0.0`.
+#[test]
+fn strict() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.2.3"),
+            Commit("feat!: Breaking change"),
+        ])
+        .run("release");
+}
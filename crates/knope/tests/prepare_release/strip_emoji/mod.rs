@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// With `strip_emoji`, commits with a leading gitmoji (as Unicode or a shortcode) are parsed as
+/// conventional commits.
+#[test]
+fn strip_emoji() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("✨ feat: A Unicode feature"),
+            Commit(":bug: fix: A shortcode fix"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,2 @@
+mod prerelease;
+mod release;
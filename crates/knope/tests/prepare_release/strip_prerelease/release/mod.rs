@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A file with `strip_prerelease` is consistent with a pre-release version of the package.
+#[test]
+fn release() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Tag("v1.1.0-rc.0"),
+            Commit("fix: A bug"),
+        ])
+        .run("release");
+}
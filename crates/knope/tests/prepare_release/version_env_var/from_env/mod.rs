@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// The version in `version_env_var` is used instead of the calculated one.
+#[test]
+fn from_env() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.1.0"),
+            Commit("fix: A bug fix"),
+        ])
+        .env(&[("RELEASE_VERSION", "v2.0.0")])
+        .run("release");
+}
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A `version_env_var` which isn't a valid version is an error.
+#[test]
+fn invalid() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v0.1.0"),
+            Commit("fix: A bug fix"),
+        ])
+        .env(&[("RELEASE_VERSION", "2.0")])
+        .run("release");
+}
@@ -0,0 +1,2 @@
+mod from_env;
+mod invalid;
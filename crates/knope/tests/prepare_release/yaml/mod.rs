@@ -0,0 +1,3 @@
+mod action_yml;
+mod key_not_supported;
+mod missing_key;
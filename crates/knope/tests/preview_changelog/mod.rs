@@ -0,0 +1,2 @@
+mod multiple_packages;
+mod no_change_files;
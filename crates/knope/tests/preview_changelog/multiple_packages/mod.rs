@@ -0,0 +1,18 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Prints the changelog sections the change files would produce for each package, leaving the
+/// change files (and everything else) alone.
+#[test]
+fn multiple_packages() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat!: Existing feature"),
+            Tag("first/v1.2.3"),
+            Tag("second/v0.4.6"),
+            Commit("feat: A conventional commit, which isn't previewed"),
+        ])
+        .run("preview");
+}
@@ -0,0 +1,15 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+#[test]
+fn no_change_files() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat!: Existing feature"),
+            Tag("first/v1.2.3"),
+            Tag("second/v0.4.6"),
+        ])
+        .run("preview");
+}
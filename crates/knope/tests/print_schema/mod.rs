@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// `--print-schema` prints the JSON Schema of `knope.toml`, even if the current one is invalid.
+#[test]
+fn print_schema() {
+    TestCase::new(file!()).run("--print-schema");
+}
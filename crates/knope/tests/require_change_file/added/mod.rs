@@ -0,0 +1,24 @@
+use std::fs::{create_dir, write};
+
+use crate::helpers::{add_all, commit, create_branch, TestCase};
+
+/// A change file was added on the branch, so commits which aren't conventional are fine.
+#[test]
+fn added() {
+    let test = TestCase::new(file!());
+    let temp_dir = test.arrange();
+    let temp_path = temp_dir.path();
+
+    commit(temp_path, "Initial commit");
+    create_branch(temp_path, "feature");
+    create_dir(temp_path.join(".changeset")).unwrap();
+    write(
+        temp_path.join(".changeset/fix_the_thing.md"),
+        "---\ndefault: patch\n---\n\n# Fix the thing\n",
+    )
+    .unwrap();
+    add_all(temp_path);
+    commit(temp_path, "Fix the thing");
+
+    test.assert(test.act(temp_dir, "check"));
+}
@@ -0,0 +1,16 @@
+use crate::helpers::{commit, create_branch, TestCase};
+
+/// A commit on the branch isn't a conventional commit and no change file was added for it.
+#[test]
+fn missing() {
+    let test = TestCase::new(file!());
+    let temp_dir = test.arrange();
+    let temp_path = temp_dir.path();
+
+    commit(temp_path, "Initial commit");
+    create_branch(temp_path, "feature");
+    commit(temp_path, "feat: A documented feature");
+    commit(temp_path, "Fix the thing\n\nWith more details");
+
+    test.assert(test.act(temp_dir, "check"));
+}
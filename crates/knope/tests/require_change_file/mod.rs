@@ -0,0 +1,2 @@
+mod added;
+mod missing;
@@ -0,0 +1,4 @@
+mod nothing_to_roll_back;
+mod release_workflow;
+mod revert_fails;
+mod tagless;
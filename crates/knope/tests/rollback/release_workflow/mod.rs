@@ -0,0 +1,13 @@
+use crate::helpers::{
+    GitCommand::{AddAll, Commit, Tag},
+    TestCase,
+};
+
+/// Rolling back a release deletes its tag and restores the files it changed, including the
+/// change files it consumed.
+#[test]
+fn release_workflow() {
+    TestCase::new(file!())
+        .git(&[AddAll, Commit("chore: Initial commit"), Tag("v1.0.0")])
+        .run("release");
+}
@@ -0,0 +1,14 @@
+use crate::helpers::{
+    GitCommand::{AddAll, Commit, Tag},
+    TestCase,
+};
+
+/// When the commit can't be reverted, like because of uncommitted changes, the release tags are
+/// kept.
+#[test]
+fn revert_fails() {
+    TestCase::new(file!())
+        .git(&[AddAll, Commit("chore: Initial commit"), Tag("v1.0.0")])
+        .expected_tags(&["v1.1.0"])
+        .run("release");
+}
@@ -0,0 +1,13 @@
+use crate::helpers::{
+    GitCommand::{AddAll, Commit, Tag},
+    TestCase,
+};
+
+/// A release commit without a release tag (like when the workflow stopped before `Release`) is
+/// still rolled back.
+#[test]
+fn tagless() {
+    TestCase::new(file!())
+        .git(&[AddAll, Commit("chore: Initial commit"), Tag("v1.0.0")])
+        .run("release");
+}
@@ -0,0 +1,15 @@
+use crate::helpers::{add_all, commit, init, TestCase};
+
+/// The version of the package is written to the files in the downstream checkout, then they're
+/// committed there.
+#[test]
+fn commit_downstream() {
+    let test = TestCase::new(file!());
+    let temp_dir = test.arrange();
+    let docs = temp_dir.path().join("docs");
+    init(&docs);
+    add_all(&docs);
+    commit(&docs, "Initial commit");
+
+    test.assert(test.act(temp_dir, "sync"));
+}
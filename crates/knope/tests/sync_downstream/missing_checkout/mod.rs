@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// The downstream checkout has to exist already, Knope doesn't clone it.
+#[test]
+fn missing_checkout() {
+    TestCase::new(file!()).run("sync");
+}
@@ -0,0 +1,2 @@
+mod commit;
+mod missing_checkout;
@@ -0,0 +1,3 @@
+mod no_source_of_truth;
+mod source_of_truth;
+mod unknown_source_of_truth;
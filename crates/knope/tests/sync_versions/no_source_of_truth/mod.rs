@@ -0,0 +1,9 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// `SyncVersions` fails if no package has a `source_of_truth`.
+#[test]
+fn no_source_of_truth() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .run("sync");
+}
@@ -0,0 +1,10 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// Every other versioned file is overwritten with the version of the `source_of_truth`, even
+/// though they didn't match to begin with.
+#[test]
+fn source_of_truth() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .run("sync");
+}
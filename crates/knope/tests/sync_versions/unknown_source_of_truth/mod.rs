@@ -0,0 +1,9 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// The `source_of_truth` must be one of the `versioned_files`.
+#[test]
+fn unknown_source_of_truth() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .run("sync");
+}
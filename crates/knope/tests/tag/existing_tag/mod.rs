@@ -0,0 +1,12 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// By default, tagging a version whose tag already exists is an error.
+#[test]
+fn existing_tag() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit"), Tag("v1.0.0")])
+        .run("tag");
+}
@@ -0,0 +1,2 @@
+mod existing_tag;
+mod multiple_packages;
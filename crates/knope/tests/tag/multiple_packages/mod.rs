@@ -0,0 +1,10 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// Every package is tagged at its current version, without changing any files.
+#[test]
+fn multiple_packages() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .expected_tags(&["first/v1.2.3", "second/v0.4.6"])
+        .run("tag");
+}
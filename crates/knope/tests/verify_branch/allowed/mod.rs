@@ -0,0 +1,9 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// The workflow continues on `main`, which is allowed by default.
+#[test]
+fn allowed() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .run("release");
+}
@@ -0,0 +1,2 @@
+mod allowed;
+mod not_allowed;
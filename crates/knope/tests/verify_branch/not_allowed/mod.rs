@@ -0,0 +1,9 @@
+use crate::helpers::{GitCommand::Commit, TestCase};
+
+/// The workflow stops on a branch which doesn't match any of the allowed patterns.
+#[test]
+fn not_allowed() {
+    TestCase::new(file!())
+        .git(&[Commit("Initial commit")])
+        .run("release");
+}
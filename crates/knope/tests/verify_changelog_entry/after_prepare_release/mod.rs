@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// The entry written by `PrepareRelease` in the same workflow counts, even in a dry run.
+#[test]
+fn after_prepare_release() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: The first feature"),
+            Tag("v1.0.0"),
+            Commit("feat: A new feature"),
+        ])
+        .run("release");
+}
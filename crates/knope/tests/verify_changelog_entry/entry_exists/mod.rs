@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// A changelog which already has an entry for the current version lets the release continue.
+#[test]
+fn entry_exists() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: The first feature"),
+            Tag("v1.0.0"),
+            Commit("feat: A new feature"),
+        ])
+        .run("release");
+}
@@ -0,0 +1,16 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Releasing a version which the changelog doesn't mention is an error.
+#[test]
+fn missing_entry() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: The first feature"),
+            Tag("v1.0.0"),
+            Commit("feat: A new feature"),
+        ])
+        .run("release");
+}
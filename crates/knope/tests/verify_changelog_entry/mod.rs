@@ -0,0 +1,3 @@
+mod after_prepare_release;
+mod entry_exists;
+mod missing_entry;
@@ -0,0 +1,2 @@
+mod prepared_version;
+mod too_many_packages;
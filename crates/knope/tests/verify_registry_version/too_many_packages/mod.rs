@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// With multiple packages, `package` must say which one to check.
+#[test]
+fn too_many_packages() {
+    TestCase::new(file!()).run("release");
+}
@@ -0,0 +1 @@
+mod zero_poll_interval;
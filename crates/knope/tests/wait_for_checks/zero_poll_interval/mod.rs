@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// Polling without a break is rejected before anything is requested from GitHub.
+#[test]
+fn zero_poll_interval() {
+    TestCase::new(file!()).run("release");
+}
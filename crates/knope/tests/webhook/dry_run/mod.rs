@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// Variables are replaced within every string of the payload, which is printed instead of sent.
+#[test]
+fn dry_run() {
+    TestCase::new(file!()).run("notify --dry-run");
+}
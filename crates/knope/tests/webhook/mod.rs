@@ -0,0 +1,2 @@
+mod dry_run;
+mod no_url;
@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// Either `url` or `url_env_var` must be set.
+#[test]
+fn no_url() {
+    TestCase::new(file!()).run("notify");
+}
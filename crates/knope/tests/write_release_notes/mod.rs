@@ -0,0 +1,2 @@
+mod no_prepared_release;
+mod prepared_release;
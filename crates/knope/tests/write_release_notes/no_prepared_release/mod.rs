@@ -0,0 +1,7 @@
+use crate::helpers::TestCase;
+
+/// `WriteReleaseNotes` needs the release from `PrepareRelease`.
+#[test]
+fn no_prepared_release() {
+    TestCase::new(file!()).run("release");
+}
@@ -0,0 +1,17 @@
+use crate::helpers::{
+    GitCommand::{Commit, Tag},
+    TestCase,
+};
+
+/// Only the new section is written to the file, while the changelog still gets it too.
+#[test]
+fn prepared_release() {
+    TestCase::new(file!())
+        .git(&[
+            Commit("feat: Existing feature"),
+            Tag("v1.0.0"),
+            Commit("feat: New feature"),
+            Commit("fix: A bug"),
+        ])
+        .run("release");
+}